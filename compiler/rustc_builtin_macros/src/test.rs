@@ -342,6 +342,8 @@ pub fn expand_test_or_bench(
                                             }
                                         },
                                     ),
+                                    // timeout: None
+                                    field("timeout", cx.expr_none(sp)),
                                     // },
                                 ],
                             ),
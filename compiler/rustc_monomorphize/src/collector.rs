@@ -289,6 +289,46 @@ pub fn collect_crate_mono_items(
     (visited.into_inner(), usage_map.into_inner())
 }
 
+/// Collect all monomorphized items that are transitively used by `root`, including `root`
+/// itself.
+///
+/// This walks the same graph as [`collect_crate_mono_items`], but starts from a single item
+/// instead of the crate roots. Nothing is collected if `root` does not need to be codegened in
+/// the local crate.
+#[instrument(skip(tcx), level = "debug")]
+pub fn collect_mono_items_from<'tcx>(
+    tcx: TyCtxt<'tcx>,
+    root: MonoItem<'tcx>,
+) -> FxHashSet<MonoItem<'tcx>> {
+    let mut visited = MTLock::new(FxHashSet::default());
+    let mut usage_map = MTLock::new(UsageMap::new());
+
+    let is_local_root = match root {
+        MonoItem::Fn(instance) => {
+            !matches!(instance.def, InstanceDef::Intrinsic(_) | InstanceDef::Virtual(..))
+                && should_codegen_locally(tcx, &instance)
+        }
+        MonoItem::Static(def_id) => should_codegen_locally(tcx, &Instance::mono(tcx, def_id)),
+        MonoItem::GlobalAsm(_) => true,
+    };
+
+    if is_local_root {
+        let visited: MTLockRef<'_, _> = &mut visited;
+        let usage_map: MTLockRef<'_, _> = &mut usage_map;
+        let mut recursion_depths = DefIdMap::default();
+        collect_items_rec(
+            tcx,
+            dummy_spanned(root),
+            visited,
+            &mut recursion_depths,
+            tcx.recursion_limit(),
+            usage_map,
+        );
+    }
+
+    visited.into_inner()
+}
+
 // Find all non-generic items by walking the HIR. These items serve as roots to
 // start monomorphizing from.
 #[instrument(skip(tcx, mode), level = "debug")]
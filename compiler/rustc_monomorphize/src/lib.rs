@@ -24,6 +24,8 @@
 mod polymorphize;
 mod util;
 
+pub use collector::collect_mono_items_from;
+
 fluent_messages! { "../messages.ftl" }
 
 fn custom_coerce_unsize_info<'tcx>(
@@ -0,0 +1,1063 @@
+//! Module containing the translation from stable mir constructs to the rustc counterpart.
+//!
+//! This module will only include a few constructs to allow users to invoke internal rustc APIs
+//! due to incomplete stable coverage.
+
+// Prefer importing stable_mir over internal rustc constructs to make this file more readable.
+use crate::rustc_smir::alloc::new_const_value;
+use crate::rustc_smir::{MaybeStable, Tables};
+use rustc_hir as hir;
+use rustc_middle::mir;
+use rustc_middle::mir::interpret::{
+    alloc_range, read_target_uint, Allocation as InternalAllocation, ConstAllocation, Pointer,
+    Scalar,
+};
+use rustc_middle::mir::Mutability as InternalMutability;
+use rustc_middle::ty::{self as rustc_ty, Ty as InternalTy};
+use rustc_span::Symbol;
+use rustc_target::abi::{Align, FieldIdx, Size, VariantIdx};
+use stable_mir::mir::{
+    AggregateKind, AssertMessage, AsyncGeneratorKind, BasicBlock, BasicBlockIdx, BinOp, Body,
+    BorrowKind, CastKind, Constant, GeneratorKind, MutBorrowKind, Mutability,
+    NonDivergingIntrinsic, NullOp, Operand, Place, PointerCoercion, ProjectionElem, RetagKind,
+    Rvalue, Safety, Statement, StatementKind, Terminator, TerminatorKind, UnOp, UnwindAction,
+};
+use stable_mir::ty::{
+    Abi, AliasKind, AliasTy, Allocation, Binder, BoundRegion, BoundRegionKind, BoundTy,
+    BoundTyKind, BoundVariableKind, Const, ConstantKind, DynKind, ExistentialPredicate,
+    ExistentialProjection, ExistentialTraitRef, FloatTy, FnSig, GenericArgKind, GenericArgs, IntTy,
+    Movability, Region, RegionKind, RigidTy, TermKind, TraitRef, Ty, TyKind, UintTy,
+};
+
+/// Trait used to translate a stable construct to its rustc counterpart.
+///
+/// This is basically a mirror of [crate::rustc_smir::Stable].
+pub(crate) trait RustcInternal<'tcx> {
+    type T;
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Self::T;
+}
+
+impl<'tcx> RustcInternal<'tcx> for Ty {
+    type T = InternalTy<'tcx>;
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        match tables.types[self.0].clone() {
+            MaybeStable::Rustc(ty) => ty,
+            MaybeStable::Stable(kind) => kind.internal(tables),
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for TyKind {
+    type T = InternalTy<'tcx>;
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            TyKind::RigidTy(rigid_ty) => rigid_ty.internal(tables),
+            TyKind::Param(param) => {
+                InternalTy::new_param(tables.tcx, param.index, Symbol::intern(&param.name))
+            }
+            TyKind::Alias(alias_kind, alias_ty) => InternalTy::new_alias(
+                tables.tcx,
+                alias_kind.internal(tables),
+                alias_ty.internal(tables),
+            ),
+            TyKind::Bound(debruijn_idx, bound_ty) => InternalTy::new_bound(
+                tables.tcx,
+                rustc_ty::DebruijnIndex::from_usize(*debruijn_idx),
+                bound_ty.internal(tables),
+            ),
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for RigidTy {
+    type T = InternalTy<'tcx>;
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        let tcx = tables.tcx;
+        match self {
+            RigidTy::Bool => tcx.types.bool,
+            RigidTy::Char => tcx.types.char,
+            RigidTy::Int(int_ty) => InternalTy::new_int(tcx, int_ty.internal(tables)),
+            RigidTy::Uint(uint_ty) => InternalTy::new_uint(tcx, uint_ty.internal(tables)),
+            RigidTy::Float(float_ty) => InternalTy::new_float(tcx, float_ty.internal(tables)),
+            RigidTy::Never => tcx.types.never,
+            RigidTy::Str => tcx.types.str_,
+            RigidTy::Adt(def, args) => {
+                InternalTy::new_adt(tcx, tcx.adt_def(tables[def.0]), args.internal(tables))
+            }
+            RigidTy::Foreign(def) => InternalTy::new_foreign(tcx, tables[def.0]),
+            RigidTy::Array(ty, len) => {
+                InternalTy::new_array_with_const_len(tcx, ty.internal(tables), len.internal(tables))
+            }
+            RigidTy::Slice(ty) => InternalTy::new_slice(tcx, ty.internal(tables)),
+            RigidTy::RawPtr(ty, mutability) => InternalTy::new_ptr(
+                tcx,
+                rustc_ty::TypeAndMut {
+                    ty: ty.internal(tables),
+                    mutbl: mutability.internal(tables),
+                },
+            ),
+            RigidTy::Ref(region, ty, mutability) => InternalTy::new_ref(
+                tcx,
+                region.internal(tables),
+                rustc_ty::TypeAndMut {
+                    ty: ty.internal(tables),
+                    mutbl: mutability.internal(tables),
+                },
+            ),
+            RigidTy::FnDef(def, args) => {
+                InternalTy::new_fn_def(tcx, tables[def.0], args.internal(tables))
+            }
+            RigidTy::Closure(def, args) => {
+                InternalTy::new_closure(tcx, tables[def.0], args.internal(tables))
+            }
+            RigidTy::Generator(def, args, movability) => InternalTy::new_generator(
+                tcx,
+                tables[def.0],
+                args.internal(tables),
+                movability.internal(tables),
+            ),
+            RigidTy::Tuple(tys) => {
+                InternalTy::new_tup_from_iter(tcx, tys.iter().map(|ty| ty.internal(tables)))
+            }
+            RigidTy::FnPtr(sig) => InternalTy::new_fn_ptr(tcx, sig.internal(tables)),
+            RigidTy::Dynamic(predicates, region, dyn_kind) => InternalTy::new_dynamic(
+                tcx,
+                tcx.mk_poly_existential_predicates_from_iter(
+                    predicates.iter().map(|predicate| predicate.internal(tables)),
+                ),
+                region.internal(tables),
+                dyn_kind.internal(tables),
+            ),
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for GenericArgs {
+    type T = rustc_ty::GenericArgsRef<'tcx>;
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        let tcx = tables.tcx;
+        tcx.mk_args_from_iter(self.0.iter().map(|arg| arg.internal(tables)))
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for GenericArgKind {
+    type T = rustc_ty::GenericArg<'tcx>;
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            GenericArgKind::Lifetime(region) => region.internal(tables).into(),
+            GenericArgKind::Type(ty) => ty.internal(tables).into(),
+            GenericArgKind::Const(cnst) => cnst.internal(tables).into(),
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for TraitRef {
+    type T = rustc_ty::TraitRef<'tcx>;
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        let def_id = tables[self.def_id.0];
+        let args = self.args.internal(tables);
+        rustc_ty::TraitRef::new(tables.tcx, def_id, args)
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for Region {
+    type T = rustc_ty::Region<'tcx>;
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        // Late-bound regions are rebuilt, so that the binders of function pointer and trait object
+        // types stay consistent. Stable MIR does not keep enough information to rebuild the other
+        // regions. That is fine for now, since the constructs that need this conversion operate on
+        // erased regions anyway.
+        match &self.kind {
+            RegionKind::ReStatic => tables.tcx.lifetimes.re_static,
+            RegionKind::ReLateBound(debruijn_idx, bound_region) => {
+                rustc_ty::Region::new_late_bound(
+                    tables.tcx,
+                    rustc_ty::DebruijnIndex::from_u32(*debruijn_idx),
+                    bound_region.internal(tables),
+                )
+            }
+            _ => tables.tcx.lifetimes.re_erased,
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for Const {
+    type T = rustc_ty::Const<'tcx>;
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        let tcx = tables.tcx;
+        let ty = self.ty.internal(tables);
+        match &self.literal {
+            ConstantKind::Param(param) => rustc_ty::Const::new_param(
+                tcx,
+                rustc_ty::ParamConst::new(param.index, Symbol::intern(&param.name)),
+                ty,
+            ),
+            ConstantKind::Unevaluated(uv) => rustc_ty::Const::new_unevaluated(
+                tcx,
+                rustc_ty::UnevaluatedConst::new(tables[uv.def.0], uv.args.internal(tables)),
+                ty,
+            ),
+            ConstantKind::Allocated(alloc) if alloc.bytes.is_empty() => {
+                rustc_ty::Const::new_value(tcx, rustc_ty::ValTree::zst(), ty)
+            }
+            ConstantKind::Allocated(alloc) => {
+                // Only scalar values can be used as type level constants for now.
+                let bytes: Vec<u8> = alloc
+                    .bytes
+                    .iter()
+                    .map(|byte| byte.expect("type level constants must be initialized"))
+                    .collect();
+                let bits = read_target_uint(tcx.data_layout.endian, &bytes).unwrap();
+                rustc_ty::Const::from_bits(tcx, bits, rustc_ty::ParamEnv::empty().and(ty))
+            }
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for Allocation {
+    type T = ConstAllocation<'tcx>;
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        let tcx = tables.tcx;
+        let bytes: Vec<u8> = self.bytes.iter().map(|byte| byte.unwrap_or_default()).collect();
+        let align = Align::from_bytes(self.align).unwrap();
+        let mut alloc = InternalAllocation::from_bytes(bytes, align, InternalMutability::Mut);
+        for (offset, byte) in self.bytes.iter().enumerate() {
+            if byte.is_none() {
+                let range = alloc_range(Size::from_bytes(offset), Size::from_bytes(1));
+                alloc.write_uninit(&tcx, range).unwrap();
+            }
+        }
+        let ptr_size = tcx.data_layout.pointer_size;
+        for (offset, prov) in &self.provenance.ptrs {
+            // The bytes of a pointer store its offset into the allocation it points to.
+            let range = alloc_range(Size::from_bytes(*offset), ptr_size);
+            let addr = alloc.read_scalar(&tcx, range, false).unwrap();
+            let addr = addr.to_target_usize(&tcx).unwrap();
+            let ptr = Pointer::new(tables[prov.0], Size::from_bytes(addr));
+            alloc.write_scalar(&tcx, range, Scalar::from_pointer(ptr, &tcx)).unwrap();
+        }
+        alloc.mutability = self.mutability.internal(tables);
+        tcx.mk_const_alloc(alloc)
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for Mutability {
+    type T = InternalMutability;
+    fn internal(&self, _: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            Mutability::Not => InternalMutability::Not,
+            Mutability::Mut => InternalMutability::Mut,
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for Movability {
+    type T = hir::Movability;
+    fn internal(&self, _: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            Movability::Static => hir::Movability::Static,
+            Movability::Movable => hir::Movability::Movable,
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for IntTy {
+    type T = rustc_ty::IntTy;
+    fn internal(&self, _: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            IntTy::Isize => rustc_ty::IntTy::Isize,
+            IntTy::I8 => rustc_ty::IntTy::I8,
+            IntTy::I16 => rustc_ty::IntTy::I16,
+            IntTy::I32 => rustc_ty::IntTy::I32,
+            IntTy::I64 => rustc_ty::IntTy::I64,
+            IntTy::I128 => rustc_ty::IntTy::I128,
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for UintTy {
+    type T = rustc_ty::UintTy;
+    fn internal(&self, _: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            UintTy::Usize => rustc_ty::UintTy::Usize,
+            UintTy::U8 => rustc_ty::UintTy::U8,
+            UintTy::U16 => rustc_ty::UintTy::U16,
+            UintTy::U32 => rustc_ty::UintTy::U32,
+            UintTy::U64 => rustc_ty::UintTy::U64,
+            UintTy::U128 => rustc_ty::UintTy::U128,
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for FloatTy {
+    type T = rustc_ty::FloatTy;
+    fn internal(&self, _: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            FloatTy::F32 => rustc_ty::FloatTy::F32,
+            FloatTy::F64 => rustc_ty::FloatTy::F64,
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for Safety {
+    type T = hir::Unsafety;
+    fn internal(&self, _: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            Safety::Unsafe => hir::Unsafety::Unsafe,
+            Safety::Normal => hir::Unsafety::Normal,
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for Abi {
+    type T = rustc_target::spec::abi::Abi;
+    fn internal(&self, _: &mut Tables<'tcx>) -> Self::T {
+        use rustc_target::spec::abi;
+        match *self {
+            Abi::Rust => abi::Abi::Rust,
+            Abi::C { unwind } => abi::Abi::C { unwind },
+            Abi::Cdecl { unwind } => abi::Abi::Cdecl { unwind },
+            Abi::Stdcall { unwind } => abi::Abi::Stdcall { unwind },
+            Abi::Fastcall { unwind } => abi::Abi::Fastcall { unwind },
+            Abi::Vectorcall { unwind } => abi::Abi::Vectorcall { unwind },
+            Abi::Thiscall { unwind } => abi::Abi::Thiscall { unwind },
+            Abi::Aapcs { unwind } => abi::Abi::Aapcs { unwind },
+            Abi::Win64 { unwind } => abi::Abi::Win64 { unwind },
+            Abi::SysV64 { unwind } => abi::Abi::SysV64 { unwind },
+            Abi::PtxKernel => abi::Abi::PtxKernel,
+            Abi::Msp430Interrupt => abi::Abi::Msp430Interrupt,
+            Abi::X86Interrupt => abi::Abi::X86Interrupt,
+            Abi::AmdGpuKernel => abi::Abi::AmdGpuKernel,
+            Abi::EfiApi => abi::Abi::EfiApi,
+            Abi::AvrInterrupt => abi::Abi::AvrInterrupt,
+            Abi::AvrNonBlockingInterrupt => abi::Abi::AvrNonBlockingInterrupt,
+            Abi::CCmseNonSecureCall => abi::Abi::CCmseNonSecureCall,
+            Abi::Wasm => abi::Abi::Wasm,
+            Abi::System { unwind } => abi::Abi::System { unwind },
+            Abi::RustIntrinsic => abi::Abi::RustIntrinsic,
+            Abi::RustCall => abi::Abi::RustCall,
+            Abi::PlatformIntrinsic => abi::Abi::PlatformIntrinsic,
+            Abi::Unadjusted => abi::Abi::Unadjusted,
+            Abi::RustCold => abi::Abi::RustCold,
+            Abi::RiscvInterruptM => abi::Abi::RiscvInterruptM,
+            Abi::RiscvInterruptS => abi::Abi::RiscvInterruptS,
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for FnSig {
+    type T = rustc_ty::FnSig<'tcx>;
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        rustc_ty::FnSig {
+            inputs_and_output: tables.tcx.mk_type_list_from_iter(
+                self.inputs_and_output.iter().map(|ty| ty.internal(tables)),
+            ),
+            c_variadic: self.c_variadic,
+            unsafety: self.unsafety.internal(tables),
+            abi: self.abi.internal(tables),
+        }
+    }
+}
+
+impl<'tcx, S> RustcInternal<'tcx> for Binder<S>
+where
+    S: RustcInternal<'tcx>,
+    S::T: rustc_ty::TypeVisitable<rustc_ty::TyCtxt<'tcx>>,
+{
+    type T = rustc_ty::Binder<'tcx, S::T>;
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        let value = self.value.internal(tables);
+        let bound_vars = tables.tcx.mk_bound_variable_kinds_from_iter(
+            self.bound_vars.iter().map(|bound_var| bound_var.internal(tables)),
+        );
+        rustc_ty::Binder::bind_with_vars(value, bound_vars)
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for BoundVariableKind {
+    type T = rustc_ty::BoundVariableKind;
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            BoundVariableKind::Ty(kind) => rustc_ty::BoundVariableKind::Ty(kind.internal(tables)),
+            BoundVariableKind::Region(kind) => {
+                rustc_ty::BoundVariableKind::Region(kind.internal(tables))
+            }
+            BoundVariableKind::Const => rustc_ty::BoundVariableKind::Const,
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for BoundTyKind {
+    type T = rustc_ty::BoundTyKind;
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            BoundTyKind::Anon => rustc_ty::BoundTyKind::Anon,
+            BoundTyKind::Param(def, name) => {
+                rustc_ty::BoundTyKind::Param(tables[def.0], Symbol::intern(name))
+            }
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for BoundRegionKind {
+    type T = rustc_ty::BoundRegionKind;
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            BoundRegionKind::BrAnon => rustc_ty::BoundRegionKind::BrAnon,
+            BoundRegionKind::BrNamed(def, name) => {
+                rustc_ty::BoundRegionKind::BrNamed(tables[def.0], Symbol::intern(name))
+            }
+            BoundRegionKind::BrEnv => rustc_ty::BoundRegionKind::BrEnv,
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for BoundRegion {
+    type T = rustc_ty::BoundRegion;
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        rustc_ty::BoundRegion {
+            var: rustc_ty::BoundVar::from_u32(self.var),
+            kind: self.kind.internal(tables),
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for BoundTy {
+    type T = rustc_ty::BoundTy;
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        rustc_ty::BoundTy {
+            var: rustc_ty::BoundVar::from_usize(self.var),
+            kind: self.kind.internal(tables),
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for AliasKind {
+    type T = rustc_ty::AliasKind;
+    fn internal(&self, _: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            AliasKind::Projection => rustc_ty::AliasKind::Projection,
+            AliasKind::Inherent => rustc_ty::AliasKind::Inherent,
+            AliasKind::Opaque => rustc_ty::AliasKind::Opaque,
+            AliasKind::Weak => rustc_ty::AliasKind::Weak,
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for AliasTy {
+    type T = rustc_ty::AliasTy<'tcx>;
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        let def_id = tables[self.def_id.0];
+        let args = self.args.internal(tables);
+        tables.tcx.mk_alias_ty(def_id, args)
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for DynKind {
+    type T = rustc_ty::DynKind;
+    fn internal(&self, _: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            DynKind::Dyn => rustc_ty::DynKind::Dyn,
+            DynKind::DynStar => rustc_ty::DynKind::DynStar,
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for ExistentialPredicate {
+    type T = rustc_ty::ExistentialPredicate<'tcx>;
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            ExistentialPredicate::Trait(trait_ref) => {
+                rustc_ty::ExistentialPredicate::Trait(trait_ref.internal(tables))
+            }
+            ExistentialPredicate::Projection(projection) => {
+                rustc_ty::ExistentialPredicate::Projection(projection.internal(tables))
+            }
+            ExistentialPredicate::AutoTrait(def) => {
+                rustc_ty::ExistentialPredicate::AutoTrait(tables[def.0])
+            }
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for ExistentialTraitRef {
+    type T = rustc_ty::ExistentialTraitRef<'tcx>;
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        rustc_ty::ExistentialTraitRef {
+            def_id: tables[self.def_id.0],
+            args: self.generic_args.internal(tables),
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for ExistentialProjection {
+    type T = rustc_ty::ExistentialProjection<'tcx>;
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        rustc_ty::ExistentialProjection {
+            def_id: tables[self.def_id.0],
+            args: self.generic_args.internal(tables),
+            term: self.term.internal(tables),
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for TermKind {
+    type T = rustc_ty::Term<'tcx>;
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            TermKind::Type(ty) => ty.internal(tables).into(),
+            TermKind::Const(cnst) => cnst.internal(tables).into(),
+        }
+    }
+}
+
+/// Build a replacement for the `original` body from a stable `body`.
+///
+/// Stable MIR does not expose source scopes, debug information and user type annotations, so
+/// those are taken from the original body. Locals that already existed in the original body also
+/// keep their debug information.
+///
+/// Returns a description of the offending constructs if the body contains any that cannot be
+/// rebuilt, see [unsupported_construct].
+pub(crate) fn replace_body<'tcx>(
+    body: &Body,
+    original: &mir::Body<'tcx>,
+    tables: &mut Tables<'tcx>,
+) -> Result<mir::Body<'tcx>, &'static str> {
+    if let Some(unsupported) = unsupported_construct(body) {
+        return Err(unsupported);
+    }
+    let mut new_body = original.clone();
+    let cleanup = cleanup_blocks(&body.blocks);
+    *new_body.basic_blocks_mut() = body
+        .blocks
+        .iter()
+        .zip(cleanup)
+        .map(|(block, is_cleanup)| mir::BasicBlockData {
+            statements: block.statements.iter().map(|stmt| stmt.internal(tables)).collect(),
+            terminator: Some(block.terminator.internal(tables)),
+            is_cleanup,
+        })
+        .collect();
+    new_body.local_decls = body
+        .locals
+        .iter()
+        .enumerate()
+        .map(|(local, decl)| {
+            let ty = decl.ty.internal(tables);
+            let span = tables[decl.span];
+            let mut new_decl = match original.local_decls.get(mir::Local::from_usize(local)) {
+                Some(original_decl) => original_decl.clone(),
+                None => mir::LocalDecl::new(ty, span),
+            };
+            new_decl.ty = ty;
+            new_decl.source_info.span = span;
+            new_decl.mutability = decl.mutability.internal(tables);
+            new_decl
+        })
+        .collect();
+    Ok(new_body)
+}
+
+/// Find a statement or terminator that cannot be translated back to rustc MIR.
+///
+/// Stable MIR only keeps a textual representation of inline assembly, and it does not keep enough
+/// information to rebuild coverage statements, or the statements that are only used before
+/// borrow checking.
+fn unsupported_construct(body: &Body) -> Option<&'static str> {
+    body.blocks.iter().find_map(|block| {
+        let statement = block.statements.iter().find_map(|stmt| match stmt.kind {
+            StatementKind::FakeRead(..) => Some("fake reads"),
+            StatementKind::AscribeUserType { .. } => Some("user type ascriptions"),
+            StatementKind::Coverage(_) => Some("coverage statements"),
+            _ => None,
+        });
+        let terminator = match block.terminator.kind {
+            TerminatorKind::InlineAsm { .. } => Some("inline assembly"),
+            _ => None,
+        };
+        statement.or(terminator)
+    })
+}
+
+/// Compute which blocks lie on an unwind path, i.e., which blocks are reachable from the cleanup
+/// target of some terminator.
+fn cleanup_blocks(blocks: &[BasicBlock]) -> Vec<bool> {
+    let mut cleanup = vec![false; blocks.len()];
+    let mut worklist: Vec<_> =
+        blocks.iter().filter_map(|block| block.terminator.kind.unwind()?.cleanup()).collect();
+    while let Some(idx) = worklist.pop() {
+        if !cleanup[idx] {
+            cleanup[idx] = true;
+            worklist.extend(blocks[idx].terminator.kind.successors());
+        }
+    }
+    cleanup
+}
+
+impl<'tcx> RustcInternal<'tcx> for Statement {
+    type T = mir::Statement<'tcx>;
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        mir::Statement {
+            source_info: mir::SourceInfo::outermost(tables[self.span]),
+            kind: self.kind.internal(tables),
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for StatementKind {
+    type T = mir::StatementKind<'tcx>;
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            StatementKind::Assign(place, rvalue) => mir::StatementKind::Assign(Box::new((
+                place.internal(tables),
+                rvalue.internal(tables),
+            ))),
+            StatementKind::SetDiscriminant { place, variant_index } => {
+                mir::StatementKind::SetDiscriminant {
+                    place: Box::new(place.internal(tables)),
+                    variant_index: VariantIdx::from_usize(*variant_index),
+                }
+            }
+            StatementKind::Deinit(place) => {
+                mir::StatementKind::Deinit(Box::new(place.internal(tables)))
+            }
+            StatementKind::StorageLive(local) => {
+                mir::StatementKind::StorageLive(mir::Local::from_usize(*local))
+            }
+            StatementKind::StorageDead(local) => {
+                mir::StatementKind::StorageDead(mir::Local::from_usize(*local))
+            }
+            StatementKind::Retag(kind, place) => {
+                mir::StatementKind::Retag(kind.internal(tables), Box::new(place.internal(tables)))
+            }
+            StatementKind::PlaceMention(place) => {
+                mir::StatementKind::PlaceMention(Box::new(place.internal(tables)))
+            }
+            StatementKind::Intrinsic(intrinsic) => {
+                mir::StatementKind::Intrinsic(Box::new(intrinsic.internal(tables)))
+            }
+            StatementKind::ConstEvalCounter => mir::StatementKind::ConstEvalCounter,
+            StatementKind::Nop => mir::StatementKind::Nop,
+            // These statements are rejected by `replace_body`.
+            StatementKind::FakeRead(..)
+            | StatementKind::AscribeUserType { .. }
+            | StatementKind::Coverage(_) => unreachable!("{self:?}"),
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for RetagKind {
+    type T = mir::RetagKind;
+    fn internal(&self, _: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            RetagKind::FnEntry => mir::RetagKind::FnEntry,
+            RetagKind::TwoPhase => mir::RetagKind::TwoPhase,
+            RetagKind::Raw => mir::RetagKind::Raw,
+            RetagKind::Default => mir::RetagKind::Default,
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for NonDivergingIntrinsic {
+    type T = mir::NonDivergingIntrinsic<'tcx>;
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            NonDivergingIntrinsic::Assume(op) => {
+                mir::NonDivergingIntrinsic::Assume(op.internal(tables))
+            }
+            NonDivergingIntrinsic::CopyNonOverlapping(copy) => {
+                mir::NonDivergingIntrinsic::CopyNonOverlapping(mir::CopyNonOverlapping {
+                    src: copy.src.internal(tables),
+                    dst: copy.dst.internal(tables),
+                    count: copy.count.internal(tables),
+                })
+            }
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for Rvalue {
+    type T = mir::Rvalue<'tcx>;
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            Rvalue::AddressOf(mutability, place) => {
+                mir::Rvalue::AddressOf(mutability.internal(tables), place.internal(tables))
+            }
+            Rvalue::Aggregate(kind, operands) => mir::Rvalue::Aggregate(
+                Box::new(kind.internal(tables)),
+                operands.iter().map(|op| op.internal(tables)).collect(),
+            ),
+            Rvalue::BinaryOp(bin_op, lhs, rhs) => mir::Rvalue::BinaryOp(
+                bin_op.internal(tables),
+                Box::new((lhs.internal(tables), rhs.internal(tables))),
+            ),
+            Rvalue::Cast(kind, op, ty) => {
+                mir::Rvalue::Cast(kind.internal(tables), op.internal(tables), ty.internal(tables))
+            }
+            Rvalue::CheckedBinaryOp(bin_op, lhs, rhs) => mir::Rvalue::CheckedBinaryOp(
+                bin_op.internal(tables),
+                Box::new((lhs.internal(tables), rhs.internal(tables))),
+            ),
+            Rvalue::CopyForDeref(place) => mir::Rvalue::CopyForDeref(place.internal(tables)),
+            Rvalue::Discriminant(place) => mir::Rvalue::Discriminant(place.internal(tables)),
+            Rvalue::Len(place) => mir::Rvalue::Len(place.internal(tables)),
+            Rvalue::Ref(region, kind, place) => mir::Rvalue::Ref(
+                region.internal(tables),
+                kind.internal(tables),
+                place.internal(tables),
+            ),
+            Rvalue::Repeat(op, count) => {
+                mir::Rvalue::Repeat(op.internal(tables), count.internal(tables))
+            }
+            Rvalue::ShallowInitBox(op, ty) => {
+                mir::Rvalue::ShallowInitBox(op.internal(tables), ty.internal(tables))
+            }
+            Rvalue::ThreadLocalRef(item) => mir::Rvalue::ThreadLocalRef(tables[item.0]),
+            Rvalue::NullaryOp(null_op, ty) => {
+                mir::Rvalue::NullaryOp(null_op.internal(tables), ty.internal(tables))
+            }
+            Rvalue::UnaryOp(un_op, op) => {
+                mir::Rvalue::UnaryOp(un_op.internal(tables), op.internal(tables))
+            }
+            Rvalue::Use(op) => mir::Rvalue::Use(op.internal(tables)),
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for AggregateKind {
+    type T = mir::AggregateKind<'tcx>;
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            AggregateKind::Array(ty) => mir::AggregateKind::Array(ty.internal(tables)),
+            AggregateKind::Tuple => mir::AggregateKind::Tuple,
+            AggregateKind::Adt(def, variant, args, user_ty, field) => mir::AggregateKind::Adt(
+                tables[def.0],
+                VariantIdx::from_usize(*variant),
+                args.internal(tables),
+                user_ty.map(rustc_ty::UserTypeAnnotationIndex::from_usize),
+                field.map(FieldIdx::from_usize),
+            ),
+            AggregateKind::Closure(def, args) => {
+                mir::AggregateKind::Closure(tables[def.0], args.internal(tables))
+            }
+            AggregateKind::Generator(def, args, movability) => mir::AggregateKind::Generator(
+                tables[def.0],
+                args.internal(tables),
+                movability.internal(tables),
+            ),
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for BorrowKind {
+    type T = mir::BorrowKind;
+    fn internal(&self, _: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            BorrowKind::Shared => mir::BorrowKind::Shared,
+            BorrowKind::Shallow => mir::BorrowKind::Shallow,
+            BorrowKind::Mut { kind } => mir::BorrowKind::Mut {
+                kind: match kind {
+                    MutBorrowKind::Default => mir::MutBorrowKind::Default,
+                    MutBorrowKind::TwoPhaseBorrow => mir::MutBorrowKind::TwoPhaseBorrow,
+                    MutBorrowKind::ClosureCapture => mir::MutBorrowKind::ClosureCapture,
+                },
+            },
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for CastKind {
+    type T = mir::CastKind;
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            CastKind::PointerExposeAddress => mir::CastKind::PointerExposeAddress,
+            CastKind::PointerFromExposedAddress => mir::CastKind::PointerFromExposedAddress,
+            CastKind::PointerCoercion(coercion) => {
+                mir::CastKind::PointerCoercion(coercion.internal(tables))
+            }
+            CastKind::DynStar => mir::CastKind::DynStar,
+            CastKind::IntToInt => mir::CastKind::IntToInt,
+            CastKind::FloatToInt => mir::CastKind::FloatToInt,
+            CastKind::FloatToFloat => mir::CastKind::FloatToFloat,
+            CastKind::IntToFloat => mir::CastKind::IntToFloat,
+            CastKind::PtrToPtr => mir::CastKind::PtrToPtr,
+            CastKind::FnPtrToPtr => mir::CastKind::FnPtrToPtr,
+            CastKind::Transmute => mir::CastKind::Transmute,
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for PointerCoercion {
+    type T = rustc_ty::adjustment::PointerCoercion;
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        use rustc_ty::adjustment::PointerCoercion as InternalCoercion;
+        match self {
+            PointerCoercion::ReifyFnPointer => InternalCoercion::ReifyFnPointer,
+            PointerCoercion::UnsafeFnPointer => InternalCoercion::UnsafeFnPointer,
+            PointerCoercion::ClosureFnPointer(safety) => {
+                InternalCoercion::ClosureFnPointer(safety.internal(tables))
+            }
+            PointerCoercion::MutToConstPointer => InternalCoercion::MutToConstPointer,
+            PointerCoercion::ArrayToPointer => InternalCoercion::ArrayToPointer,
+            PointerCoercion::Unsize => InternalCoercion::Unsize,
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for NullOp {
+    type T = mir::NullOp<'tcx>;
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            NullOp::SizeOf => mir::NullOp::SizeOf,
+            NullOp::AlignOf => mir::NullOp::AlignOf,
+            NullOp::OffsetOf(indices) => mir::NullOp::OffsetOf(
+                tables.tcx.mk_fields_from_iter(indices.iter().copied().map(FieldIdx::from_usize)),
+            ),
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for BinOp {
+    type T = mir::BinOp;
+    fn internal(&self, _: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            BinOp::Add => mir::BinOp::Add,
+            BinOp::AddUnchecked => mir::BinOp::AddUnchecked,
+            BinOp::Sub => mir::BinOp::Sub,
+            BinOp::SubUnchecked => mir::BinOp::SubUnchecked,
+            BinOp::Mul => mir::BinOp::Mul,
+            BinOp::MulUnchecked => mir::BinOp::MulUnchecked,
+            BinOp::Div => mir::BinOp::Div,
+            BinOp::Rem => mir::BinOp::Rem,
+            BinOp::BitXor => mir::BinOp::BitXor,
+            BinOp::BitAnd => mir::BinOp::BitAnd,
+            BinOp::BitOr => mir::BinOp::BitOr,
+            BinOp::Shl => mir::BinOp::Shl,
+            BinOp::ShlUnchecked => mir::BinOp::ShlUnchecked,
+            BinOp::Shr => mir::BinOp::Shr,
+            BinOp::ShrUnchecked => mir::BinOp::ShrUnchecked,
+            BinOp::Eq => mir::BinOp::Eq,
+            BinOp::Lt => mir::BinOp::Lt,
+            BinOp::Le => mir::BinOp::Le,
+            BinOp::Ne => mir::BinOp::Ne,
+            BinOp::Ge => mir::BinOp::Ge,
+            BinOp::Gt => mir::BinOp::Gt,
+            BinOp::Offset => mir::BinOp::Offset,
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for UnOp {
+    type T = mir::UnOp;
+    fn internal(&self, _: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            UnOp::Not => mir::UnOp::Not,
+            UnOp::Neg => mir::UnOp::Neg,
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for Operand {
+    type T = mir::Operand<'tcx>;
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            Operand::Copy(place) => mir::Operand::Copy(place.internal(tables)),
+            Operand::Move(place) => mir::Operand::Move(place.internal(tables)),
+            Operand::Constant(constant) => {
+                mir::Operand::Constant(Box::new(constant.internal(tables)))
+            }
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for Constant {
+    type T = mir::ConstOperand<'tcx>;
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        let ty = self.literal.ty.internal(tables);
+        let const_ = match &self.literal.literal {
+            ConstantKind::Allocated(alloc) => {
+                let alloc = alloc.internal(tables);
+                mir::Const::Val(new_const_value(ty, alloc, tables), ty)
+            }
+            ConstantKind::Unevaluated(uv) => mir::Const::Unevaluated(
+                mir::UnevaluatedConst {
+                    def: tables[uv.def.0],
+                    args: uv.args.internal(tables),
+                    promoted: uv.promoted.map(mir::Promoted::from_u32),
+                },
+                ty,
+            ),
+            ConstantKind::Param(_) => mir::Const::Ty(self.literal.internal(tables)),
+        };
+        mir::ConstOperand {
+            span: tables[self.span],
+            user_ty: self.user_ty.map(rustc_ty::UserTypeAnnotationIndex::from_usize),
+            const_,
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for Place {
+    type T = mir::Place<'tcx>;
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        let projection: Vec<_> = self.projection.iter().map(|elem| elem.internal(tables)).collect();
+        mir::Place {
+            local: mir::Local::from_usize(self.local),
+            projection: tables.tcx.mk_place_elems(&projection),
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for ProjectionElem {
+    type T = mir::PlaceElem<'tcx>;
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            ProjectionElem::Deref => mir::ProjectionElem::Deref,
+            ProjectionElem::Field(idx, ty) => {
+                mir::ProjectionElem::Field(FieldIdx::from_usize(*idx), ty.internal(tables))
+            }
+            ProjectionElem::Index(local) => {
+                mir::ProjectionElem::Index(mir::Local::from_usize(*local))
+            }
+            ProjectionElem::ConstantIndex { offset, min_length, from_end } => {
+                mir::ProjectionElem::ConstantIndex {
+                    offset: *offset,
+                    min_length: *min_length,
+                    from_end: *from_end,
+                }
+            }
+            ProjectionElem::Subslice { from, to, from_end } => {
+                mir::ProjectionElem::Subslice { from: *from, to: *to, from_end: *from_end }
+            }
+            ProjectionElem::Downcast(idx) => {
+                mir::ProjectionElem::Downcast(None, VariantIdx::from_usize(*idx))
+            }
+            ProjectionElem::OpaqueCast(ty) => mir::ProjectionElem::OpaqueCast(ty.internal(tables)),
+            ProjectionElem::Subtype(ty) => mir::ProjectionElem::Subtype(ty.internal(tables)),
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for Terminator {
+    type T = mir::Terminator<'tcx>;
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        let span = tables[self.span];
+        let kind = match &self.kind {
+            TerminatorKind::Goto { target } => mir::TerminatorKind::Goto { target: block(*target) },
+            TerminatorKind::SwitchInt { discr, targets, otherwise } => {
+                mir::TerminatorKind::SwitchInt {
+                    discr: discr.internal(tables),
+                    targets: mir::SwitchTargets::new(
+                        targets.iter().map(|target| (target.value, block(target.target))),
+                        block(*otherwise),
+                    ),
+                }
+            }
+            TerminatorKind::Resume => mir::TerminatorKind::UnwindResume,
+            TerminatorKind::Abort => {
+                mir::TerminatorKind::UnwindTerminate(mir::UnwindTerminateReason::Abi)
+            }
+            TerminatorKind::Return => mir::TerminatorKind::Return,
+            TerminatorKind::Unreachable => mir::TerminatorKind::Unreachable,
+            TerminatorKind::Drop { place, target, unwind } => mir::TerminatorKind::Drop {
+                place: place.internal(tables),
+                target: block(*target),
+                unwind: unwind.internal(tables),
+                replace: false,
+            },
+            TerminatorKind::Call { func, args, destination, target, unwind } => {
+                mir::TerminatorKind::Call {
+                    func: func.internal(tables),
+                    args: args.iter().map(|arg| arg.internal(tables)).collect(),
+                    destination: destination.internal(tables),
+                    target: target.map(block),
+                    unwind: unwind.internal(tables),
+                    call_source: mir::CallSource::Misc,
+                    fn_span: span,
+                }
+            }
+            TerminatorKind::Assert { cond, expected, msg, target, unwind } => {
+                mir::TerminatorKind::Assert {
+                    cond: cond.internal(tables),
+                    expected: *expected,
+                    msg: Box::new(msg.internal(tables)),
+                    target: block(*target),
+                    unwind: unwind.internal(tables),
+                }
+            }
+            TerminatorKind::GeneratorDrop => mir::TerminatorKind::GeneratorDrop,
+            // Inline assembly is rejected by `replace_body`.
+            TerminatorKind::InlineAsm { .. } => unreachable!("{:?}", self.kind),
+        };
+        mir::Terminator { source_info: mir::SourceInfo::outermost(span), kind }
+    }
+}
+
+fn block(idx: BasicBlockIdx) -> mir::BasicBlock {
+    mir::BasicBlock::from_usize(idx)
+}
+
+impl<'tcx> RustcInternal<'tcx> for UnwindAction {
+    type T = mir::UnwindAction;
+    fn internal(&self, _: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            UnwindAction::Continue => mir::UnwindAction::Continue,
+            UnwindAction::Unreachable => mir::UnwindAction::Unreachable,
+            UnwindAction::Terminate => {
+                mir::UnwindAction::Terminate(mir::UnwindTerminateReason::Abi)
+            }
+            UnwindAction::Cleanup(target) => mir::UnwindAction::Cleanup(block(*target)),
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for AssertMessage {
+    type T = mir::AssertMessage<'tcx>;
+    fn internal(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            AssertMessage::BoundsCheck { len, index } => mir::AssertKind::BoundsCheck {
+                len: len.internal(tables),
+                index: index.internal(tables),
+            },
+            AssertMessage::Overflow(bin_op, lhs, rhs) => mir::AssertKind::Overflow(
+                bin_op.internal(tables),
+                lhs.internal(tables),
+                rhs.internal(tables),
+            ),
+            AssertMessage::OverflowNeg(op) => mir::AssertKind::OverflowNeg(op.internal(tables)),
+            AssertMessage::DivisionByZero(op) => {
+                mir::AssertKind::DivisionByZero(op.internal(tables))
+            }
+            AssertMessage::RemainderByZero(op) => {
+                mir::AssertKind::RemainderByZero(op.internal(tables))
+            }
+            AssertMessage::ResumedAfterReturn(kind) => {
+                mir::AssertKind::ResumedAfterReturn(kind.internal(tables))
+            }
+            AssertMessage::ResumedAfterPanic(kind) => {
+                mir::AssertKind::ResumedAfterPanic(kind.internal(tables))
+            }
+            AssertMessage::MisalignedPointerDereference { required, found } => {
+                mir::AssertKind::MisalignedPointerDereference {
+                    required: required.internal(tables),
+                    found: found.internal(tables),
+                }
+            }
+        }
+    }
+}
+
+impl<'tcx> RustcInternal<'tcx> for GeneratorKind {
+    type T = hir::GeneratorKind;
+    fn internal(&self, _: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            GeneratorKind::Async(kind) => hir::GeneratorKind::Async(match kind {
+                AsyncGeneratorKind::Block => hir::AsyncGeneratorKind::Block,
+                AsyncGeneratorKind::Closure => hir::AsyncGeneratorKind::Closure,
+                AsyncGeneratorKind::Fn => hir::AsyncGeneratorKind::Fn,
+            }),
+            GeneratorKind::Gen => hir::GeneratorKind::Gen,
+        }
+    }
+}
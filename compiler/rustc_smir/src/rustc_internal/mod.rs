@@ -3,17 +3,29 @@
 //! For that, we define APIs that will temporarily be public to 3P that exposes rustc internal APIs
 //! until stable MIR is complete.
 
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
 use std::ops::{ControlFlow, Index};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
 use crate::rustc_internal;
-use crate::rustc_smir::Tables;
+use crate::rustc_smir::{Stable, Tables};
 use rustc_driver::{Callbacks, Compilation, RunCompiler};
 use rustc_interface::{interface, Queries};
+use rustc_middle::mir;
 use rustc_middle::mir::interpret::AllocId;
-use rustc_middle::ty::TyCtxt;
+use rustc_middle::ty::{self, TyCtxt};
 pub use rustc_span::def_id::{CrateNum, DefId};
+use rustc_span::def_id::LocalDefId;
 use rustc_span::Span;
-use stable_mir::CompilerError;
+use stable_mir::mir::Body;
+use stable_mir::serialize::{CrateDump, DumpFormat};
+use stable_mir::{CompilerError, CrateItem};
+
+mod internal;
+
+pub(crate) use internal::RustcInternal;
 
 impl<'tcx> Index<stable_mir::DefId> for Tables<'tcx> {
     type Output = DefId;
@@ -24,6 +36,15 @@ fn index(&self, index: stable_mir::DefId) -> &Self::Output {
     }
 }
 
+impl<'tcx> Index<stable_mir::AllocId> for Tables<'tcx> {
+    type Output = AllocId;
+
+    #[inline(always)]
+    fn index(&self, index: stable_mir::AllocId) -> &Self::Output {
+        &self.alloc_ids[index.0]
+    }
+}
+
 impl<'tcx> Index<stable_mir::ty::Span> for Tables<'tcx> {
     type Output = Span;
 
@@ -33,6 +54,15 @@ fn index(&self, index: stable_mir::ty::Span) -> &Self::Output {
     }
 }
 
+impl<'tcx> Index<stable_mir::mir::mono::InstanceDef> for Tables<'tcx> {
+    type Output = ty::Instance<'tcx>;
+
+    #[inline(always)]
+    fn index(&self, index: stable_mir::mir::mono::InstanceDef) -> &Self::Output {
+        &self.instances[index.0]
+    }
+}
+
 impl<'tcx> Tables<'tcx> {
     pub fn crate_item(&mut self, did: DefId) -> stable_mir::CrateItem {
         stable_mir::CrateItem(self.create_def_id(did))
@@ -90,11 +120,15 @@ pub fn region_def(&mut self, did: DefId) -> stable_mir::ty::RegionDef {
         stable_mir::ty::RegionDef(self.create_def_id(did))
     }
 
+    pub fn static_def(&mut self, did: DefId) -> stable_mir::mir::mono::StaticDef {
+        stable_mir::mir::mono::StaticDef(self.create_def_id(did))
+    }
+
     pub fn prov(&mut self, aid: AllocId) -> stable_mir::ty::Prov {
         stable_mir::ty::Prov(self.create_alloc_id(aid))
     }
 
-    fn create_def_id(&mut self, did: DefId) -> stable_mir::DefId {
+    pub(crate) fn create_def_id(&mut self, did: DefId) -> stable_mir::DefId {
         // FIXME: this becomes inefficient when we have too many ids
         for (i, &d) in self.def_ids.iter().enumerate() {
             if d == did {
@@ -111,11 +145,23 @@ fn create_alloc_id(&mut self, aid: AllocId) -> stable_mir::AllocId {
         if let Some(i) = self.alloc_ids.iter().position(|a| *a == aid) {
             return stable_mir::AllocId(i);
         };
-        let id = self.def_ids.len();
+        let id = self.alloc_ids.len();
         self.alloc_ids.push(aid);
         stable_mir::AllocId(id)
     }
 
+    pub(crate) fn instance_def(
+        &mut self,
+        instance: ty::Instance<'tcx>,
+    ) -> stable_mir::mir::mono::InstanceDef {
+        if let Some(i) = self.instances.iter().position(|inst| *inst == instance) {
+            return stable_mir::mir::mono::InstanceDef(i);
+        }
+        let id = self.instances.len();
+        self.instances.push(instance);
+        stable_mir::mir::mono::InstanceDef(id)
+    }
+
     pub(crate) fn create_span(&mut self, span: Span) -> stable_mir::ty::Span {
         for (i, &sp) in self.spans.iter().enumerate() {
             if sp == span {
@@ -132,11 +178,59 @@ pub fn crate_num(item: &stable_mir::Crate) -> CrateNum {
     item.id.into()
 }
 
+fn new_tables(tcx: TyCtxt<'_>) -> Tables<'_> {
+    Tables {
+        tcx,
+        def_ids: vec![],
+        alloc_ids: vec![],
+        spans: vec![],
+        types: vec![],
+        instances: vec![],
+    }
+}
+
 pub fn run(tcx: TyCtxt<'_>, f: impl FnOnce()) {
-    stable_mir::run(
-        Tables { tcx, def_ids: vec![], alloc_ids: vec![], spans: vec![], types: vec![] },
-        f,
-    );
+    stable_mir::run(new_tables(tcx), f);
+}
+
+/// A callback that receives the optimized MIR of a local item, and may return a new body that
+/// replaces it for the rest of the compilation. Returning `None` keeps the original body.
+pub type MirOverride = fn(&CrateItem, &Body) -> Option<Body>;
+
+/// The MIR override registered by the running [StableMir] instance.
+///
+/// Query providers are plain function pointers, so the callback cannot be stored in them.
+static MIR_OVERRIDE: Mutex<Option<MirOverride>> = Mutex::new(None);
+
+/// Replacement for the `optimized_mir` query provider, which gives the registered [MirOverride]
+/// a chance to replace the body computed by the default provider.
+fn optimized_mir<'tcx>(tcx: TyCtxt<'tcx>, def_id: LocalDefId) -> &'tcx mir::Body<'tcx> {
+    let body = (rustc_interface::DEFAULT_QUERY_PROVIDERS.optimized_mir)(tcx, def_id);
+    let Some(callback) = *MIR_OVERRIDE.lock().unwrap() else {
+        return body;
+    };
+    // The MIR of an item may be first requested while a stable MIR context is already set, e.g.,
+    // by the analysis callback. The override still needs to run then, since the result of this
+    // query is kept for the rest of the compilation, so it gets a context of its own.
+    let mut tables = new_tables(tcx);
+    let item = tables.crate_item(def_id.to_def_id());
+    let stable_body = body.stable(&mut tables);
+    let mut new_body = None;
+    stable_mir::run_scoped(&mut tables, || new_body = callback(&item, &stable_body));
+    let Some(new_body) = new_body else {
+        return body;
+    };
+    match internal::replace_body(&new_body, body, &mut tables) {
+        Ok(new_body) => tcx.arena.alloc(new_body),
+        Err(unsupported) => {
+            let path = tcx.def_path_str(def_id);
+            tcx.sess.span_err(
+                tcx.def_span(def_id),
+                format!("cannot replace the MIR of `{path}`: {unsupported} cannot be rebuilt"),
+            );
+            body
+        }
+    }
 }
 
 pub struct StableMir<B = (), C = ()>
@@ -146,6 +240,8 @@ pub struct StableMir<B = (), C = ()>
 {
     args: Vec<String>,
     callback: fn(TyCtxt<'_>) -> ControlFlow<B, C>,
+    mir_override: Option<MirOverride>,
+    body_dump: Option<(PathBuf, DumpFormat)>,
     result: Option<ControlFlow<B, C>>,
 }
 
@@ -156,13 +252,37 @@ impl<B, C> StableMir<B, C>
 {
     /// Creates a new `StableMir` instance, with given test_function and arguments.
     pub fn new(args: Vec<String>, callback: fn(TyCtxt<'_>) -> ControlFlow<B, C>) -> Self {
-        StableMir { args, callback, result: None }
+        StableMir { args, callback, mir_override: None, body_dump: None, result: None }
+    }
+
+    /// Registers a callback that can replace the optimized MIR of every local item before it
+    /// gets code generated. This allows tools to instrument the compiled code using only the
+    /// stable MIR APIs.
+    ///
+    /// The analysis callback also sees the replaced bodies. Note that it must return
+    /// `ControlFlow::Continue` for the compilation to reach code generation.
+    ///
+    /// Bodies with inline assembly or coverage statements cannot be replaced, since stable MIR
+    /// does not keep enough information about them. Doing so is reported as a compilation error.
+    pub fn with_mir_override(mut self, mir_override: MirOverride) -> Self {
+        self.mir_override = Some(mir_override);
+        self
+    }
+
+    /// Dumps the stable MIR of all local bodies to `path` after analysis, before invoking the
+    /// analysis callback. See [CrateDump] for the contents of the dump.
+    ///
+    /// Failing to write the dump is reported as a compilation error.
+    pub fn with_body_dump(mut self, path: impl Into<PathBuf>, format: DumpFormat) -> Self {
+        self.body_dump = Some((path.into(), format));
+        self
     }
 
     /// Runs the compiler against given target and tests it with `test_function`
     pub fn run(&mut self) -> Result<C, CompilerError<B>> {
         let compiler_result =
             rustc_driver::catch_fatal_errors(|| RunCompiler::new(&self.args.clone(), self).run());
+        *MIR_OVERRIDE.lock().unwrap() = None;
         match (compiler_result, self.result.take()) {
             (Ok(Ok(())), Some(ControlFlow::Continue(value))) => Ok(value),
             (Ok(Ok(())), Some(ControlFlow::Break(value))) => Err(CompilerError::Interrupted(value)),
@@ -178,6 +298,16 @@ impl<B, C> Callbacks for StableMir<B, C>
     B: Send,
     C: Send,
 {
+    /// Install the MIR override, if any, before the compiler starts.
+    fn config(&mut self, config: &mut interface::Config) {
+        *MIR_OVERRIDE.lock().unwrap() = self.mir_override;
+        if self.mir_override.is_some() {
+            config.override_queries = Some(|_session, providers| {
+                providers.optimized_mir = optimized_mir;
+            });
+        }
+    }
+
     /// Called after analysis. Return value instructs the compiler whether to
     /// continue the compilation afterwards (defaults to `Compilation::Continue`)
     fn after_analysis<'tcx>(
@@ -187,6 +317,13 @@ fn after_analysis<'tcx>(
     ) -> Compilation {
         queries.global_ctxt().unwrap().enter(|tcx| {
             rustc_internal::run(tcx, || {
+                if let Some((path, format)) = &self.body_dump {
+                    if let Err(err) = write_body_dump(path, *format) {
+                        let path = path.display();
+                        tcx.sess.err(format!("failed to write stable MIR to `{path}`: {err}"));
+                        return;
+                    }
+                }
                 self.result = Some((self.callback)(tcx));
             });
             if self.result.as_ref().is_some_and(|val| val.is_continue()) {
@@ -197,3 +334,9 @@ fn after_analysis<'tcx>(
         })
     }
 }
+
+fn write_body_dump(path: &Path, format: DumpFormat) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    CrateDump::local_crate().write(&mut writer, format)?;
+    writer.flush()
+}
@@ -0,0 +1,283 @@
+//! Conversion of internal layout and function ABI information to their stable counterparts.
+
+use crate::rustc_smir::{Stable, Tables};
+use rustc_middle::ty;
+use rustc_target::abi::call::{self, Conv, RiscvInterruptKind};
+use rustc_target::abi::{self, FieldIdx, VariantIdx};
+use stable_mir::abi::{
+    ArgAbi, ArgAttribute, ArgAttributes, ArgExtension, CallConvention, FieldsShape, FnAbi,
+    IntegerLength, LayoutShape, Niche, PassMode, Primitive, Scalar, TagEncoding, ValueAbi,
+    VariantsShape, WrappingRange,
+};
+use stable_mir::opaque;
+
+impl<'tcx> Stable<'tcx> for abi::LayoutS<FieldIdx, VariantIdx> {
+    type T = LayoutShape;
+
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        LayoutShape {
+            fields: self.fields.stable(tables),
+            variants: self.variants.stable(tables),
+            abi: self.abi.stable(tables),
+            largest_niche: self.largest_niche.map(|niche| niche.stable(tables)),
+            abi_align: self.align.abi.bytes(),
+            size: self.size.bytes_usize(),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for abi::FieldsShape<FieldIdx> {
+    type T = FieldsShape;
+
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            abi::FieldsShape::Primitive => FieldsShape::Primitive,
+            abi::FieldsShape::Union(count) => FieldsShape::Union(count.get()),
+            abi::FieldsShape::Array { stride, count } => {
+                FieldsShape::Array { stride: stride.bytes_usize(), count: *count }
+            }
+            abi::FieldsShape::Arbitrary { offsets, .. } => FieldsShape::Arbitrary {
+                offsets: offsets.iter().map(|offset| offset.bytes_usize()).collect(),
+            },
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for abi::Variants<FieldIdx, VariantIdx> {
+    type T = VariantsShape;
+
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            abi::Variants::Single { index } => {
+                VariantsShape::Single { index: index.stable(tables) }
+            }
+            abi::Variants::Multiple { tag, tag_encoding, tag_field, variants } => {
+                VariantsShape::Multiple {
+                    tag: tag.stable(tables),
+                    tag_encoding: tag_encoding.stable(tables),
+                    tag_field: *tag_field,
+                    variants: variants.iter().map(|variant| variant.stable(tables)).collect(),
+                }
+            }
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for abi::TagEncoding<VariantIdx> {
+    type T = TagEncoding;
+
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            abi::TagEncoding::Direct => TagEncoding::Direct,
+            abi::TagEncoding::Niche { untagged_variant, niche_variants, niche_start } => {
+                TagEncoding::Niche {
+                    untagged_variant: untagged_variant.stable(tables),
+                    niche_variants: niche_variants.start().stable(tables)
+                        ..=niche_variants.end().stable(tables),
+                    niche_start: *niche_start,
+                }
+            }
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for abi::Abi {
+    type T = ValueAbi;
+
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        match *self {
+            abi::Abi::Uninhabited => ValueAbi::Uninhabited,
+            abi::Abi::Scalar(scalar) => ValueAbi::Scalar(scalar.stable(tables)),
+            abi::Abi::ScalarPair(first, second) => {
+                ValueAbi::ScalarPair(first.stable(tables), second.stable(tables))
+            }
+            abi::Abi::Vector { element, count } => {
+                ValueAbi::Vector { element: element.stable(tables), count }
+            }
+            abi::Abi::Aggregate { sized } => ValueAbi::Aggregate { sized },
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for abi::Scalar {
+    type T = Scalar;
+
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            abi::Scalar::Initialized { value, valid_range } => Scalar::Initialized {
+                value: value.stable(tables),
+                valid_range: valid_range.stable(tables),
+            },
+            abi::Scalar::Union { value } => Scalar::Union { value: value.stable(tables) },
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for abi::Primitive {
+    type T = Primitive;
+
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            abi::Primitive::Int(length, signed) => {
+                Primitive::Int { length: length.stable(tables), signed: *signed }
+            }
+            abi::Primitive::F32 => Primitive::F32,
+            abi::Primitive::F64 => Primitive::F64,
+            abi::Primitive::Pointer(space) => Primitive::Pointer(space.0),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for abi::Integer {
+    type T = IntegerLength;
+
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            abi::Integer::I8 => IntegerLength::I8,
+            abi::Integer::I16 => IntegerLength::I16,
+            abi::Integer::I32 => IntegerLength::I32,
+            abi::Integer::I64 => IntegerLength::I64,
+            abi::Integer::I128 => IntegerLength::I128,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for abi::WrappingRange {
+    type T = WrappingRange;
+
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        WrappingRange { start: self.start, end: self.end }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for abi::Niche {
+    type T = Niche;
+
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        Niche {
+            offset: self.offset.bytes_usize(),
+            value: self.value.stable(tables),
+            valid_range: self.valid_range.stable(tables),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for call::FnAbi<'tcx, ty::Ty<'tcx>> {
+    type T = FnAbi;
+
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        FnAbi {
+            args: self.args.iter().map(|arg| arg.stable(tables)).collect(),
+            ret: self.ret.stable(tables),
+            fixed_count: self.fixed_count,
+            conv: self.conv.stable(tables),
+            c_variadic: self.c_variadic,
+            can_unwind: self.can_unwind,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for call::ArgAbi<'tcx, ty::Ty<'tcx>> {
+    type T = ArgAbi;
+
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        ArgAbi {
+            ty: tables.intern_ty(self.layout.ty),
+            layout: self.layout.layout.0.0.stable(tables),
+            mode: self.mode.stable(tables),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for call::PassMode {
+    type T = PassMode;
+
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            call::PassMode::Ignore => PassMode::Ignore,
+            call::PassMode::Direct(attrs) => PassMode::Direct(attrs.stable(tables)),
+            call::PassMode::Pair(first, second) => {
+                PassMode::Pair(first.stable(tables), second.stable(tables))
+            }
+            call::PassMode::Cast { pad_i32, cast } => {
+                PassMode::Cast { pad_i32: *pad_i32, cast: opaque(cast) }
+            }
+            call::PassMode::Indirect { attrs, meta_attrs, on_stack } => PassMode::Indirect {
+                attrs: attrs.stable(tables),
+                meta_attrs: meta_attrs.as_ref().map(|attrs| attrs.stable(tables)),
+                on_stack: *on_stack,
+            },
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for call::ArgAttributes {
+    type T = ArgAttributes;
+
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        let flags = [
+            (call::ArgAttribute::NoAlias, ArgAttribute::NoAlias),
+            (call::ArgAttribute::NoCapture, ArgAttribute::NoCapture),
+            (call::ArgAttribute::NonNull, ArgAttribute::NonNull),
+            (call::ArgAttribute::ReadOnly, ArgAttribute::ReadOnly),
+            (call::ArgAttribute::InReg, ArgAttribute::InReg),
+            (call::ArgAttribute::NoUndef, ArgAttribute::NoUndef),
+        ];
+        ArgAttributes {
+            regular: flags
+                .into_iter()
+                .filter(|(flag, _)| self.regular.contains(*flag))
+                .map(|(_, attr)| attr)
+                .collect(),
+            arg_ext: self.arg_ext.stable(tables),
+            pointee_size: self.pointee_size.bytes_usize(),
+            pointee_align: self.pointee_align.map(|align| align.bytes()),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for call::ArgExtension {
+    type T = ArgExtension;
+
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            call::ArgExtension::None => ArgExtension::None,
+            call::ArgExtension::Zext => ArgExtension::Zext,
+            call::ArgExtension::Sext => ArgExtension::Sext,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for Conv {
+    type T = CallConvention;
+
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            Conv::C => CallConvention::C,
+            Conv::Rust => CallConvention::Rust,
+            Conv::Cold => CallConvention::Cold,
+            Conv::PreserveMost => CallConvention::PreserveMost,
+            Conv::PreserveAll => CallConvention::PreserveAll,
+            Conv::ArmAapcs => CallConvention::ArmAapcs,
+            Conv::CCmseNonSecureCall => CallConvention::CCmseNonSecureCall,
+            Conv::Msp430Intr => CallConvention::Msp430Intr,
+            Conv::PtxKernel => CallConvention::PtxKernel,
+            Conv::X86Fastcall => CallConvention::X86Fastcall,
+            Conv::X86Intr => CallConvention::X86Intr,
+            Conv::X86Stdcall => CallConvention::X86Stdcall,
+            Conv::X86ThisCall => CallConvention::X86ThisCall,
+            Conv::X86VectorCall => CallConvention::X86VectorCall,
+            Conv::X86_64SysV => CallConvention::X86_64SysV,
+            Conv::X86_64Win64 => CallConvention::X86_64Win64,
+            Conv::AmdGpuKernel => CallConvention::AmdGpuKernel,
+            Conv::AvrInterrupt => CallConvention::AvrInterrupt,
+            Conv::AvrNonBlockingInterrupt => CallConvention::AvrNonBlockingInterrupt,
+            Conv::RiscvInterrupt { kind: RiscvInterruptKind::Machine } => {
+                CallConvention::RiscvInterruptMachine
+            }
+            Conv::RiscvInterrupt { kind: RiscvInterruptKind::Supervisor } => {
+                CallConvention::RiscvInterruptSupervisor
+            }
+        }
+    }
+}
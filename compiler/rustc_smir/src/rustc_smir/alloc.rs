@@ -1,11 +1,15 @@
 use rustc_middle::mir::{
-    interpret::{alloc_range, AllocRange, Pointer},
+    interpret::{alloc_range, AllocRange, ConstAllocation, Pointer, Scalar},
     ConstValue,
 };
+use rustc_middle::ty::{self, FloatTy, TypeVisitableExt};
+use rustc_target::abi::{Abi, Primitive, Size};
 
 use crate::rustc_smir::{Stable, Tables};
+use stable_mir::mir::alloc::Value;
 use stable_mir::mir::Mutability;
 use stable_mir::ty::{Allocation, ProvenanceMap};
+use stable_mir::Error;
 
 /// Creates new empty `Allocation` from given `Align`.
 fn new_empty_allocation(align: rustc_target::abi::Align) -> Allocation {
@@ -83,6 +87,96 @@ pub fn new_allocation<'tcx>(
     }
 }
 
+/// Creates a `ConstValue` from an allocation that holds a value of type `ty`.
+///
+/// This is the inverse of [new_allocation]. Scalars are read back into a `ConstValue::Scalar`,
+/// while any other value is kept in memory.
+#[allow(rustc::usage_of_qualified_ty)]
+pub(crate) fn new_const_value<'tcx>(
+    ty: rustc_middle::ty::Ty<'tcx>,
+    alloc: ConstAllocation<'tcx>,
+    tables: &Tables<'tcx>,
+) -> ConstValue<'tcx> {
+    let tcx = tables.tcx;
+    let size = alloc.inner().size();
+    if size == Size::ZERO {
+        return ConstValue::ZeroSized;
+    }
+    let layout = tcx.layout_of(rustc_middle::ty::ParamEnv::reveal_all().and(ty));
+    if let Ok(layout) = layout {
+        if let Abi::Scalar(scalar) = layout.abi {
+            let read_provenance = matches!(scalar.primitive(), Primitive::Pointer(_));
+            let range = alloc_range(Size::ZERO, size);
+            if let Ok(scalar) = alloc.inner().read_scalar(&tcx, range, read_provenance) {
+                return ConstValue::Scalar(scalar);
+            }
+        }
+    }
+    ConstValue::Indirect { alloc_id: tcx.reserve_and_set_memory_alloc(alloc), offset: Size::ZERO }
+}
+
+/// Decodes a constant value of type `ty` into its structured stable representation.
+#[allow(rustc::usage_of_qualified_ty)]
+pub(super) fn decode_value<'tcx>(
+    value: ConstValue<'tcx>,
+    ty: rustc_middle::ty::Ty<'tcx>,
+    tables: &mut Tables<'tcx>,
+) -> Result<Value, Error> {
+    let tcx = tables.tcx;
+    let unsupported = || Error::new(format!("Cannot decode constant of type `{ty}`"));
+    match *ty.kind() {
+        ty::Bool | ty::Char | ty::Int(_) | ty::Uint(_) | ty::Float(_) => {
+            let scalar = value.try_to_scalar_int().ok_or_else(unsupported)?;
+            let size = scalar.size();
+            let bits = scalar.assert_bits(size);
+            Ok(match *ty.kind() {
+                ty::Bool => Value::Bool(bits != 0),
+                ty::Char => Value::Char(char::from_u32(bits as u32).ok_or_else(unsupported)?),
+                ty::Int(_) => Value::Int(size.sign_extend(bits) as i128),
+                ty::Uint(_) => Value::Uint(bits),
+                ty::Float(FloatTy::F32) => Value::F32(f32::from_bits(bits as u32)),
+                ty::Float(FloatTy::F64) => Value::F64(f64::from_bits(bits as u64)),
+                _ => unreachable!(),
+            })
+        }
+        ty::Ref(_, inner, _) if inner.is_str() => {
+            let bytes = value.try_get_slice_bytes_for_diagnostics(tcx).ok_or_else(unsupported)?;
+            let string = String::from_utf8(bytes.to_vec()).map_err(|_| unsupported())?;
+            Ok(Value::Str(string))
+        }
+        ty::Ref(..) | ty::RawPtr(_) | ty::FnPtr(_) => match value.try_to_scalar() {
+            Some(Scalar::Ptr(ptr, _)) => {
+                let (alloc_id, offset) = ptr.into_parts();
+                Ok(Value::Pointer { alloc: Some(tables.prov(alloc_id).0), offset: offset.bytes() })
+            }
+            Some(Scalar::Int(int)) => {
+                Ok(Value::Pointer { alloc: None, offset: int.assert_bits(int.size()) as u64 })
+            }
+            // Wide pointers cannot be decoded yet.
+            None => Err(unsupported()),
+        },
+        ty::Array(..) | ty::Tuple(_) | ty::Adt(..) if !ty.has_non_region_param() => {
+            let contents = tcx
+                .try_destructure_mir_constant_for_diagnostics(value, ty)
+                .ok_or_else(unsupported)?;
+            let fields = contents
+                .fields
+                .iter()
+                .map(|(field, field_ty)| decode_value(*field, *field_ty, tables))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok(match ty.kind() {
+                ty::Array(..) => Value::Array(fields),
+                ty::Tuple(_) => Value::Tuple(fields),
+                _ => Value::Adt { variant: contents.variant.unwrap().as_usize(), fields },
+            })
+        }
+        ty::FnDef(..) | ty::Closure(..) if matches!(value, ConstValue::ZeroSized) => {
+            Ok(Value::ZeroSized)
+        }
+        _ => Err(unsupported()),
+    }
+}
+
 /// Creates an `Allocation` only from information within the `AllocRange`.
 pub(super) fn allocation_filter<'tcx>(
     alloc: &rustc_middle::mir::interpret::Allocation,
@@ -7,20 +7,36 @@
 //!
 //! For now, we are developing everything inside `rustc`, thus, we keep this module private.
 
+use crate::rustc_internal::RustcInternal;
 use crate::rustc_smir::hir::def::DefKind;
 use crate::rustc_smir::stable_mir::ty::{BoundRegion, EarlyBoundRegion, Region};
+use rustc_ast as ast;
+use rustc_ast_pretty::pprust;
 use rustc_hir as hir;
+use rustc_infer::infer::TyCtxtInferExt;
 use rustc_middle::mir;
-use rustc_middle::mir::interpret::{alloc_range, AllocId};
-use rustc_middle::ty::{self, Ty, TyCtxt, Variance};
+use rustc_middle::mir::interpret::{alloc_range, AllocId, ErrorHandled};
+use rustc_middle::traits::ImplSource;
+use rustc_middle::ty::print::with_no_trimmed_paths;
+use rustc_middle::ty::{self, Ty, TyCtxt, TypeVisitableExt, Variance};
+use rustc_resolve::rustdoc::{add_doc_fragment, attrs_to_doc_fragments};
 use rustc_span::def_id::{CrateNum, DefId, LOCAL_CRATE};
 use rustc_target::abi::FieldIdx;
+use rustc_trait_selection::infer::InferCtxtExt;
+use rustc_trait_selection::traits::query::evaluate_obligation::InferCtxtExt as _;
+use rustc_trait_selection::traits::{
+    Obligation, ObligationCause, PredicateObligation, SelectionContext, TraitQueryMode,
+};
+use stable_mir::abi::{FnAbi, LayoutShape};
+use stable_mir::mir::alloc::{GlobalAlloc, Value};
+use stable_mir::mir::mono::{Instance, InstanceDef, StaticDef};
 use stable_mir::mir::{CopyNonOverlapping, Statement, UserTypeProjection, VariantIdx};
 use stable_mir::ty::{FloatTy, GenericParamDef, IntTy, Movability, RigidTy, Span, TyKind, UintTy};
 use stable_mir::{self, opaque, Context};
 use tracing::debug;
 
-mod alloc;
+mod abi;
+pub(crate) mod alloc;
 
 impl<'tcx> Context for Tables<'tcx> {
     fn local_crate(&self) -> stable_mir::Crate {
@@ -46,6 +62,90 @@ fn print_span(&self, span: stable_mir::ty::Span) -> String {
         self.tcx.sess.source_map().span_to_diagnostic_string(self[span])
     }
 
+    fn get_filename(&self, span: stable_mir::ty::Span) -> stable_mir::ty::Filename {
+        let source_map = self.tcx.sess.source_map();
+        let filename = source_map.span_to_filename(self[span]);
+        source_map.filename_for_diagnostics(&filename).to_string()
+    }
+
+    fn get_lines(&self, span: stable_mir::ty::Span) -> stable_mir::ty::LineInfo {
+        let (_, start_line, start_col, end_line, end_col) =
+            self.tcx.sess.source_map().span_to_location_info(self[span]);
+        stable_mir::ty::LineInfo { start_line, start_col, end_line, end_col }
+    }
+
+    fn span_snippet(&self, span: stable_mir::ty::Span) -> Option<String> {
+        self.tcx.sess.source_map().span_to_snippet(self[span]).ok()
+    }
+
+    fn macro_backtrace(
+        &mut self,
+        span: stable_mir::ty::Span,
+    ) -> Vec<stable_mir::ty::MacroExpansion> {
+        self[span]
+            .macro_backtrace()
+            .map(|expn_data| stable_mir::ty::MacroExpansion {
+                name: expn_data.kind.descr(),
+                call_site: expn_data.call_site.stable(self),
+                def_site: expn_data.def_site.stable(self),
+            })
+            .collect()
+    }
+
+    fn emit_diagnostic(&self, diagnostic: &stable_mir::diagnostic::Diagnostic) {
+        use stable_mir::diagnostic::Level;
+        let level = match diagnostic.level {
+            Level::Error => rustc_errors::Level::Error { lint: false },
+            Level::Warning => rustc_errors::Level::Warning(None),
+            Level::Note => rustc_errors::Level::Note,
+            Level::Help => rustc_errors::Level::Help,
+        };
+        let mut diag = rustc_errors::Diagnostic::new(level, diagnostic.message.clone());
+        diag.set_span(self[diagnostic.span]);
+        for (span, label) in &diagnostic.labels {
+            diag.span_label(self[*span], label.clone());
+        }
+        for note in &diagnostic.notes {
+            diag.note(note.clone());
+        }
+        for help in &diagnostic.help {
+            diag.help(help.clone());
+        }
+        self.tcx.sess.diagnostic().emit_diagnostic(&mut diag);
+    }
+
+    fn attrs(&mut self, def_id: stable_mir::DefId) -> Vec<stable_mir::item::Attribute> {
+        let attrs = self.tcx.get_attrs_unchecked(self[def_id]);
+        attrs.iter().map(|attr| attr.stable(self)).collect()
+    }
+
+    fn doc_comments(&self, def_id: stable_mir::DefId) -> Option<String> {
+        let attrs = self.tcx.get_attrs_unchecked(self[def_id]);
+        let (fragments, _) = attrs_to_doc_fragments(attrs.iter().map(|attr| (attr, None)), true);
+        if fragments.is_empty() {
+            return None;
+        }
+        let mut docs = String::new();
+        for fragment in &fragments {
+            add_doc_fragment(&mut docs, fragment);
+        }
+        docs.pop();
+        Some(docs)
+    }
+
+    fn visibility(&mut self, def_id: stable_mir::DefId) -> stable_mir::item::Visibility {
+        let mut def_id = self[def_id];
+        // Anonymous constants are not items, so they inherit the visibility of their parent.
+        while matches!(self.tcx.def_kind(def_id), DefKind::AnonConst | DefKind::InlineConst) {
+            def_id = self.tcx.parent(def_id);
+        }
+        self.tcx.visibility(def_id).stable(self)
+    }
+
+    fn def_path(&mut self, def_id: stable_mir::DefId) -> stable_mir::item::DefPath {
+        self.tcx.def_path(self[def_id]).stable(self)
+    }
+
     fn def_kind(&mut self, def_id: stable_mir::DefId) -> stable_mir::DefKind {
         self.tcx.def_kind(self[def_id]).stable(self)
     }
@@ -92,29 +192,7 @@ fn trait_impl(&mut self, impl_def: &stable_mir::ty::ImplDef) -> stable_mir::ty::
 
     fn mir_body(&mut self, item: stable_mir::DefId) -> stable_mir::mir::Body {
         let def_id = self[item];
-        let mir = self.tcx.instance_mir(ty::InstanceDef::Item(def_id));
-        stable_mir::mir::Body {
-            blocks: mir
-                .basic_blocks
-                .iter()
-                .map(|block| stable_mir::mir::BasicBlock {
-                    terminator: block.terminator().stable(self),
-                    statements: block
-                        .statements
-                        .iter()
-                        .map(|statement| statement.stable(self))
-                        .collect(),
-                })
-                .collect(),
-            locals: mir
-                .local_decls
-                .iter()
-                .map(|decl| stable_mir::mir::LocalDecl {
-                    ty: self.intern_ty(decl.ty),
-                    span: decl.source_info.span.stable(self),
-                })
-                .collect(),
-        }
+        self.tcx.instance_mir(ty::InstanceDef::Item(def_id)).stable(self)
     }
 
     fn ty_kind(&mut self, ty: stable_mir::ty::Ty) -> TyKind {
@@ -163,6 +241,264 @@ fn explicit_predicates_of(
                 .collect(),
         }
     }
+
+    fn mono_instance(&mut self, item: stable_mir::DefId) -> Option<Instance> {
+        let def_id = self[item];
+        let requires_args = self.tcx.generics_of(def_id).requires_monomorphization(self.tcx);
+        (!requires_args).then(|| ty::Instance::mono(self.tcx, def_id).stable(self))
+    }
+
+    fn resolve_instance(
+        &mut self,
+        def: stable_mir::ty::FnDef,
+        args: &stable_mir::ty::GenericArgs,
+    ) -> Option<Instance> {
+        let def_id = self[def.0];
+        let args = args.internal(self);
+        match ty::Instance::resolve(self.tcx, ty::ParamEnv::reveal_all(), def_id, args) {
+            Ok(Some(instance)) => Some(instance.stable(self)),
+            Ok(None) | Err(_) => None,
+        }
+    }
+
+    fn trait_of_item(&mut self, def_id: stable_mir::DefId) -> Option<stable_mir::ty::TraitDef> {
+        self.tcx.trait_of_item(self[def_id]).map(|trait_def| self.trait_def(trait_def))
+    }
+
+    fn trait_ref_holds(&mut self, trait_ref: &stable_mir::ty::TraitRef) -> bool {
+        let Some(trait_ref) = self.selectable_trait_ref(trait_ref) else {
+            return false;
+        };
+        let infcx = self.tcx.infer_ctxt().build();
+        infcx
+            .type_implements_trait(trait_ref.def_id, trait_ref.args, ty::ParamEnv::reveal_all())
+            .must_apply_modulo_regions()
+    }
+
+    fn resolve_impl(
+        &mut self,
+        trait_ref: &stable_mir::ty::TraitRef,
+    ) -> Option<stable_mir::ty::ImplDef> {
+        let param_env = ty::ParamEnv::reveal_all();
+        let trait_ref = self.selectable_trait_ref(trait_ref)?;
+        let trait_ref = self.tcx.try_normalize_erasing_regions(param_env, trait_ref).ok()?;
+
+        // Unlike `codegen_select_candidate`, which treats any error other than an unimplemented
+        // trait as a bug, both steps run in canonical mode, where overflow is reported as an error
+        // instead. Selection alone does not check the where clauses of the impl it picks, so the
+        // obligation is evaluated first.
+        let infcx = self.tcx.infer_ctxt().ignoring_regions().build();
+        let obligation: PredicateObligation<'tcx> =
+            Obligation::new(self.tcx, ObligationCause::dummy(), param_env, trait_ref);
+        if !infcx.evaluate_obligation(&obligation).is_ok_and(|res| res.must_apply_modulo_regions())
+        {
+            return None;
+        }
+        let mut selcx = SelectionContext::with_query_mode(&infcx, TraitQueryMode::Canonical);
+        match selcx.select(&obligation.with(self.tcx, trait_ref)) {
+            Ok(Some(ImplSource::UserDefined(data))) => Some(self.impl_def(data.impl_def_id)),
+            Ok(Some(ImplSource::Param(..) | ImplSource::Builtin(..)) | None) | Err(_) => None,
+        }
+    }
+
+    fn resolve_drop_in_place(&mut self, ty: stable_mir::ty::Ty) -> Instance {
+        let ty = ty.internal(self);
+        ty::Instance::resolve_drop_in_place(self.tcx, ty).stable(self)
+    }
+
+    fn instance_body(&mut self, def: InstanceDef) -> Option<stable_mir::mir::Body> {
+        let instance = self[def];
+        let has_body = match instance.def {
+            ty::InstanceDef::Intrinsic(_) | ty::InstanceDef::Virtual(..) => false,
+            ty::InstanceDef::Item(def_id) => self.tcx.is_mir_available(def_id),
+            _ => true,
+        };
+        has_body.then(|| {
+            let body = self.tcx.instance_mir(instance.def);
+            instance
+                .instantiate_mir_and_normalize_erasing_regions(
+                    self.tcx,
+                    ty::ParamEnv::reveal_all(),
+                    ty::EarlyBinder::bind(body.clone()),
+                )
+                .stable(self)
+        })
+    }
+
+    fn instance_ty(&mut self, def: InstanceDef) -> stable_mir::ty::Ty {
+        let instance = self[def];
+        let ty = instance.ty(self.tcx, ty::ParamEnv::reveal_all());
+        self.intern_ty(ty)
+    }
+
+    fn instance_args(&mut self, def: InstanceDef) -> stable_mir::ty::GenericArgs {
+        let instance = self[def];
+        instance.args.stable(self)
+    }
+
+    fn instance_def_id(&mut self, def: InstanceDef) -> stable_mir::DefId {
+        let def_id = self[def].def_id();
+        self.create_def_id(def_id)
+    }
+
+    fn instance_name(&self, def: InstanceDef) -> String {
+        let instance = self[def];
+        with_no_trimmed_paths!(instance.to_string())
+    }
+
+    fn instance_mangled_name(&self, def: InstanceDef) -> String {
+        let instance = self[def];
+        self.tcx.symbol_name(instance).name.to_string()
+    }
+
+    #[allow(rustc::potential_query_instability)]
+    fn reachable_mono_items(&mut self, root: Instance) -> Vec<stable_mir::mir::mono::MonoItem> {
+        let root = mir::mono::MonoItem::Fn(self[root.def]);
+        rustc_monomorphize::collect_mono_items_from(self.tcx, root)
+            .into_iter()
+            .map(|item| item.stable(self))
+            .collect()
+    }
+
+    fn ty_layout(&mut self, ty: stable_mir::ty::Ty) -> Result<LayoutShape, stable_mir::Error> {
+        let ty = ty.internal(self);
+        match self.tcx.layout_of(ty::ParamEnv::reveal_all().and(ty)) {
+            Ok(layout) => Ok(layout.layout.0.0.stable(self)),
+            Err(err) => Err(stable_mir::Error::new(err.to_string())),
+        }
+    }
+
+    fn instance_abi(&mut self, def: InstanceDef) -> Result<FnAbi, stable_mir::Error> {
+        let instance = self[def];
+        let key = ty::ParamEnv::reveal_all().and((instance, ty::List::empty()));
+        match self.tcx.fn_abi_of_instance(key) {
+            Ok(fn_abi) => Ok(fn_abi.stable(self)),
+            Err(err) => Err(stable_mir::Error::new(format!("{err:?}"))),
+        }
+    }
+
+    fn ty_pretty(&mut self, ty: stable_mir::ty::Ty) -> String {
+        let ty = ty.internal(self);
+        with_no_trimmed_paths!(ty.to_string())
+    }
+
+    fn const_literal(&mut self, cnst: &stable_mir::ty::Const) -> String {
+        match &cnst.literal {
+            stable_mir::ty::ConstantKind::Allocated(alloc) => {
+                let ty = cnst.ty.internal(self);
+                let allocation = alloc.internal(self);
+                let value = alloc::new_const_value(ty, allocation, self);
+                with_no_trimmed_paths!(mir::Const::Val(value, ty).to_string())
+            }
+            stable_mir::ty::ConstantKind::Unevaluated(uv) => {
+                let def_id = self[uv.def.0];
+                let args = uv.args.internal(self);
+                let path = self.tcx.def_path_str_with_args(def_id, args);
+                match uv.promoted {
+                    Some(promoted) => format!("{path}::promoted[{promoted}]"),
+                    None => path,
+                }
+            }
+            stable_mir::ty::ConstantKind::Param(param) => param.name.clone(),
+        }
+    }
+
+    fn variant_path(
+        &mut self,
+        def: stable_mir::ty::AdtDef,
+        idx: VariantIdx,
+        args: &stable_mir::ty::GenericArgs,
+    ) -> String {
+        let adt = self.tcx.adt_def(self[def.0]);
+        let variant = adt.variant(rustc_target::abi::VariantIdx::from_usize(idx));
+        let args = args.internal(self);
+        self.tcx.def_path_str_with_args(variant.def_id, args)
+    }
+
+    fn item_kind(&mut self, def: stable_mir::DefId) -> stable_mir::ItemKind {
+        match self.tcx.def_kind(self[def]) {
+            DefKind::Fn
+            | DefKind::AssocFn
+            | DefKind::Closure
+            | DefKind::Generator
+            | DefKind::Ctor(..) => stable_mir::ItemKind::Fn,
+            DefKind::Static(_) => stable_mir::ItemKind::Static,
+            DefKind::Const | DefKind::AssocConst | DefKind::AnonConst | DefKind::InlineConst => {
+                stable_mir::ItemKind::Const
+            }
+            kind => unreachable!("Not a crate item: {kind:?}"),
+        }
+    }
+
+    fn def_ty(&mut self, def: stable_mir::DefId) -> stable_mir::ty::Ty {
+        let ty = self.tcx.type_of(self[def]).instantiate_identity();
+        self.intern_ty(ty)
+    }
+
+    fn eval_const(
+        &mut self,
+        cnst: &stable_mir::ty::Const,
+    ) -> Result<stable_mir::ty::Allocation, stable_mir::Error> {
+        let uv = match &cnst.literal {
+            stable_mir::ty::ConstantKind::Allocated(alloc) => return Ok(alloc.clone()),
+            stable_mir::ty::ConstantKind::Unevaluated(uv) => uv,
+            stable_mir::ty::ConstantKind::Param(param) => {
+                return Err(stable_mir::Error::new(format!(
+                    "Cannot evaluate generic constant `{}`",
+                    param.name
+                )));
+            }
+        };
+        let ty = cnst.ty.internal(self);
+        let uv = mir::UnevaluatedConst {
+            def: self[uv.def.0],
+            args: uv.args.internal(self),
+            promoted: uv.promoted.map(mir::Promoted::from_u32),
+        };
+        match self.tcx.const_eval_resolve(ty::ParamEnv::reveal_all(), uv, None) {
+            Ok(value) => Ok(alloc::new_allocation(ty, value, self)),
+            Err(err) => Err(eval_error(err)),
+        }
+    }
+
+    fn eval_static_initializer(
+        &mut self,
+        def: StaticDef,
+    ) -> Result<stable_mir::ty::Allocation, stable_mir::Error> {
+        match self.tcx.eval_static_initializer(self[def.0]) {
+            Ok(alloc) => {
+                let range = alloc_range(rustc_target::abi::Size::ZERO, alloc.inner().size());
+                Ok(alloc::allocation_filter(alloc.inner(), range, self))
+            }
+            Err(err) => Err(eval_error(err)),
+        }
+    }
+
+    fn decode_allocation(
+        &mut self,
+        alloc: &stable_mir::ty::Allocation,
+        ty: stable_mir::ty::Ty,
+    ) -> Result<Value, stable_mir::Error> {
+        let ty = ty.internal(self);
+        let allocation = alloc.internal(self);
+        let value = alloc::new_const_value(ty, allocation, self);
+        alloc::decode_value(value, ty, self)
+    }
+
+    fn global_alloc(&mut self, id: stable_mir::AllocId) -> GlobalAlloc {
+        self.tcx.global_alloc(self[id]).stable(self)
+    }
+}
+
+fn eval_error(err: ErrorHandled) -> stable_mir::Error {
+    match err {
+        ErrorHandled::TooGeneric(_) => {
+            stable_mir::Error::new("Constant is too generic to be evaluated".to_string())
+        }
+        ErrorHandled::Reported(..) => {
+            stable_mir::Error::new("Failed to evaluate constant".to_string())
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -198,6 +534,7 @@ pub struct Tables<'tcx> {
     pub alloc_ids: Vec<AllocId>,
     pub spans: Vec<rustc_span::Span>,
     pub types: Vec<MaybeStable<stable_mir::ty::TyKind, Ty<'tcx>>>,
+    pub instances: Vec<ty::Instance<'tcx>>,
 }
 
 impl<'tcx> Tables<'tcx> {
@@ -209,6 +546,29 @@ fn intern_ty(&mut self, ty: Ty<'tcx>) -> stable_mir::ty::Ty {
         self.types.push(MaybeStable::Rustc(ty));
         stable_mir::ty::Ty(id)
     }
+
+    /// Convert a trait reference built by a user of the stable API, unless trait selection
+    /// cannot handle it: each parameter of the trait needs an argument of the right kind, and
+    /// the arguments must be fully monomorphic.
+    fn selectable_trait_ref(
+        &mut self,
+        trait_ref: &stable_mir::ty::TraitRef,
+    ) -> Option<ty::TraitRef<'tcx>> {
+        let def_id = self[trait_ref.def_id.0];
+        let args = trait_ref.args.internal(self);
+        let generics = self.tcx.generics_of(def_id);
+        let args_match = generics.count() == args.len()
+            && args.iter().enumerate().all(|(index, arg)| {
+                matches!(
+                    (&generics.param_at(index, self.tcx).kind, arg.unpack()),
+                    (ty::GenericParamDefKind::Lifetime, ty::GenericArgKind::Lifetime(_))
+                        | (ty::GenericParamDefKind::Type { .. }, ty::GenericArgKind::Type(_))
+                        | (ty::GenericParamDefKind::Const { .. }, ty::GenericArgKind::Const(_))
+                )
+            });
+        let monomorphic = !args.has_non_region_param() && !args.has_escaping_bound_vars();
+        (args_match && monomorphic).then(|| ty::TraitRef::new(self.tcx, def_id, args))
+    }
 }
 
 /// Build a stable mir crate from a given crate number.
@@ -227,6 +587,37 @@ pub(crate) trait Stable<'tcx> {
     fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T;
 }
 
+impl<'tcx> Stable<'tcx> for mir::Body<'tcx> {
+    type T = stable_mir::mir::Body;
+
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        stable_mir::mir::Body {
+            blocks: self
+                .basic_blocks
+                .iter()
+                .map(|block| stable_mir::mir::BasicBlock {
+                    terminator: block.terminator().stable(tables),
+                    statements: block
+                        .statements
+                        .iter()
+                        .map(|statement| statement.stable(tables))
+                        .collect(),
+                })
+                .collect(),
+            locals: self
+                .local_decls
+                .iter()
+                .map(|decl| stable_mir::mir::LocalDecl {
+                    ty: tables.intern_ty(decl.ty),
+                    span: decl.source_info.span.stable(tables),
+                    mutability: decl.mutability.stable(tables),
+                })
+                .collect(),
+            arg_count: self.arg_count,
+        }
+    }
+}
+
 impl<'tcx> Stable<'tcx> for mir::Statement<'tcx> {
     type T = stable_mir::mir::Statement;
     fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
@@ -585,10 +976,30 @@ fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
 
 impl<'tcx> Stable<'tcx> for mir::Place<'tcx> {
     type T = stable_mir::mir::Place;
-    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
         stable_mir::mir::Place {
             local: self.local.as_usize(),
-            projection: format!("{:?}", self.projection),
+            projection: self.projection.iter().map(|elem| elem.stable(tables)).collect(),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::PlaceElem<'tcx> {
+    type T = stable_mir::mir::ProjectionElem;
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        use mir::ProjectionElem::*;
+        use stable_mir::mir::ProjectionElem as StableElem;
+        match *self {
+            Deref => StableElem::Deref,
+            Field(idx, ty) => StableElem::Field(idx.as_usize(), tables.intern_ty(ty)),
+            Index(local) => StableElem::Index(local.as_usize()),
+            ConstantIndex { offset, min_length, from_end } => {
+                StableElem::ConstantIndex { offset, min_length, from_end }
+            }
+            Subslice { from, to, from_end } => StableElem::Subslice { from, to, from_end },
+            Downcast(_, idx) => StableElem::Downcast(idx.stable(tables)),
+            OpaqueCast(ty) => StableElem::OpaqueCast(tables.intern_ty(ty)),
+            Subtype(ty) => StableElem::Subtype(tables.intern_ty(ty)),
         }
     }
 }
@@ -1602,6 +2013,84 @@ fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
     }
 }
 
+impl<'tcx> Stable<'tcx> for ast::Attribute {
+    type T = stable_mir::item::Attribute;
+
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        let path = match &self.kind {
+            ast::AttrKind::Normal(normal) => {
+                normal.item.path.segments.iter().map(|segment| segment.ident.to_string()).collect()
+            }
+            ast::AttrKind::DocComment(..) => vec![],
+        };
+        stable_mir::item::Attribute {
+            path,
+            value: pprust::attribute_to_string(self),
+            span: self.span.stable(tables),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for ty::Visibility<DefId> {
+    type T = stable_mir::item::Visibility;
+
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            ty::Visibility::Public => stable_mir::item::Visibility::Public,
+            ty::Visibility::Restricted(module) => {
+                stable_mir::item::Visibility::Restricted(tables.create_def_id(*module))
+            }
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for hir::definitions::DefPath {
+    type T = stable_mir::item::DefPath;
+
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        stable_mir::item::DefPath {
+            krate: self.krate.as_usize(),
+            data: self.data.iter().map(|data| data.stable(tables)).collect(),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for hir::definitions::DisambiguatedDefPathData {
+    type T = stable_mir::item::DisambiguatedDefPathData;
+
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        stable_mir::item::DisambiguatedDefPathData {
+            data: self.data.stable(tables),
+            disambiguator: self.disambiguator,
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for hir::definitions::DefPathData {
+    type T = stable_mir::item::DefPathData;
+
+    fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
+        use hir::definitions::DefPathData;
+        use stable_mir::item::DefPathData as StableData;
+        match self {
+            DefPathData::CrateRoot => StableData::CrateRoot,
+            DefPathData::Impl => StableData::Impl,
+            DefPathData::ForeignMod => StableData::ForeignMod,
+            DefPathData::Use => StableData::Use,
+            DefPathData::GlobalAsm => StableData::GlobalAsm,
+            DefPathData::TypeNs(name) => StableData::TypeNs(name.to_string()),
+            DefPathData::ValueNs(name) => StableData::ValueNs(name.to_string()),
+            DefPathData::MacroNs(name) => StableData::MacroNs(name.to_string()),
+            DefPathData::LifetimeNs(name) => StableData::LifetimeNs(name.to_string()),
+            DefPathData::ClosureExpr => StableData::ClosureExpr,
+            DefPathData::Ctor => StableData::Ctor,
+            DefPathData::AnonConst => StableData::AnonConst,
+            DefPathData::ImplTrait => StableData::ImplTrait,
+            DefPathData::ImplTraitAssocTy => StableData::ImplTraitAssocTy,
+        }
+    }
+}
+
 impl<'tcx> Stable<'tcx> for DefKind {
     type T = stable_mir::DefKind;
 
@@ -1610,3 +2099,63 @@ fn stable(&self, _: &mut Tables<'tcx>) -> Self::T {
         opaque(self)
     }
 }
+
+impl<'tcx> Stable<'tcx> for ty::Instance<'tcx> {
+    type T = stable_mir::mir::mono::Instance;
+
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        use stable_mir::mir::mono::InstanceKind;
+        let kind = match self.def {
+            ty::InstanceDef::Item(..) => InstanceKind::Item,
+            ty::InstanceDef::Intrinsic(..) => InstanceKind::Intrinsic,
+            ty::InstanceDef::Virtual(_, idx) => InstanceKind::Virtual { idx },
+            ty::InstanceDef::DropGlue(..) => InstanceKind::DropGlue,
+            ty::InstanceDef::VTableShim(..)
+            | ty::InstanceDef::ReifyShim(..)
+            | ty::InstanceDef::FnPtrAddrShim(..)
+            | ty::InstanceDef::ClosureOnceShim { .. }
+            | ty::InstanceDef::ThreadLocalShim(..)
+            | ty::InstanceDef::CloneShim(..)
+            | ty::InstanceDef::FnPtrShim(..) => InstanceKind::Shim,
+        };
+        stable_mir::mir::mono::Instance { def: tables.instance_def(*self), kind }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::mono::MonoItem<'tcx> {
+    type T = stable_mir::mir::mono::MonoItem;
+
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        use stable_mir::mir::mono::MonoItem as StableMonoItem;
+        match self {
+            mir::mono::MonoItem::Fn(instance) => StableMonoItem::Fn(instance.stable(tables)),
+            mir::mono::MonoItem::Static(def_id) => {
+                StableMonoItem::Static(tables.static_def(*def_id))
+            }
+            mir::mono::MonoItem::GlobalAsm(item_id) => StableMonoItem::GlobalAsm(opaque(item_id)),
+        }
+    }
+}
+
+impl<'tcx> Stable<'tcx> for mir::interpret::GlobalAlloc<'tcx> {
+    type T = GlobalAlloc;
+
+    fn stable(&self, tables: &mut Tables<'tcx>) -> Self::T {
+        match self {
+            mir::interpret::GlobalAlloc::Function(instance) => {
+                GlobalAlloc::Function(instance.stable(tables))
+            }
+            mir::interpret::GlobalAlloc::VTable(ty, trait_ref) => GlobalAlloc::VTable(
+                tables.intern_ty(*ty),
+                trait_ref.as_ref().map(|trait_ref| trait_ref.stable(tables)),
+            ),
+            mir::interpret::GlobalAlloc::Static(def_id) => {
+                GlobalAlloc::Static(tables.static_def(*def_id))
+            }
+            mir::interpret::GlobalAlloc::Memory(alloc) => {
+                let range = alloc_range(rustc_target::abi::Size::ZERO, alloc.inner().size());
+                GlobalAlloc::Memory(alloc::allocation_filter(alloc.inner(), range, tables))
+            }
+        }
+    }
+}
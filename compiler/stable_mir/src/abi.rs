@@ -0,0 +1,340 @@
+//! Layout and calling convention information computed by the compiler.
+//!
+//! These types mirror the layout and function ABI computed for a specific target.
+
+use std::ops::RangeInclusive;
+
+use crate::mir::VariantIdx;
+use crate::ty::{Align, Size, Ty};
+use crate::Opaque;
+
+/// The layout of a type, i.e., how values of this type are laid out in memory.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LayoutShape {
+    /// Says where the fields are located within the layout.
+    pub fields: FieldsShape,
+
+    /// Encodes information about multi-variant layouts.
+    /// Even with `Multiple` variants, a layout still has its own fields! Those are then
+    /// shared between all variants. One of them will be the discriminant.
+    pub variants: VariantsShape,
+
+    /// Defines how this data is passed between functions, and the valid range of its values.
+    pub abi: ValueAbi,
+
+    /// The leaf scalar with the largest number of invalid values, if it exists.
+    pub largest_niche: Option<Niche>,
+
+    /// The ABI mandated alignment in bytes.
+    pub abi_align: Align,
+
+    /// The size of this layout in bytes.
+    pub size: Size,
+}
+
+impl LayoutShape {
+    /// Returns `true` if this layout corresponds to an unsized type.
+    pub fn is_unsized(&self) -> bool {
+        matches!(self.abi, ValueAbi::Aggregate { sized: false })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FieldsShape {
+    /// Scalar primitives and `!`, which never have fields.
+    Primitive,
+
+    /// All fields start at no offset. The `usize` is the field count.
+    Union(usize),
+
+    /// Array/vector-like placement, with all fields of identical types.
+    Array { stride: Size, count: u64 },
+
+    /// Struct-like placement, with precomputed offsets.
+    ///
+    /// Fields are guaranteed to not overlap, but note that gaps
+    /// before, between and after all the fields are NOT always
+    /// padding, and as such their contents may not be discarded.
+    /// For example, enum variants leave a gap at the start,
+    /// where the discriminant field in the enum layout goes.
+    Arbitrary {
+        /// Offsets for the first byte of each field,
+        /// ordered to match the source definition order.
+        /// This vector does not go in increasing order.
+        offsets: Vec<Size>,
+    },
+}
+
+impl FieldsShape {
+    /// Returns the field indices sorted by their offsets in memory.
+    pub fn fields_by_offset_order(&self) -> Vec<usize> {
+        match self {
+            FieldsShape::Primitive => vec![],
+            FieldsShape::Union(count) => (0..*count).collect(),
+            FieldsShape::Array { count, .. } => (0..*count as usize).collect(),
+            FieldsShape::Arbitrary { offsets } => {
+                let mut indices: Vec<usize> = (0..offsets.len()).collect();
+                indices.sort_by_key(|idx| offsets[*idx]);
+                indices
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VariantsShape {
+    /// Single enum variants, structs/tuples, unions, and all non-ADTs.
+    Single { index: VariantIdx },
+
+    /// Enum-likes with more than one inhabited variant: each variant comes with
+    /// a *discriminant* (usually the same as the variant index but the user can
+    /// assign explicit discriminant values). That discriminant is encoded
+    /// as a *tag* on the machine. The layout of each variant is
+    /// a struct, and they all have space reserved for the tag.
+    /// For enums, the tag is the sole field of the layout.
+    Multiple {
+        tag: Scalar,
+        tag_encoding: TagEncoding,
+        tag_field: usize,
+        variants: Vec<LayoutShape>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TagEncoding {
+    /// The tag directly stores the discriminant, but possibly with a smaller layout
+    /// (so converting the tag to the discriminant can require sign extension).
+    Direct,
+
+    /// Niche (values invalid for a type) encoding the discriminant:
+    /// Discriminant and variant index coincide.
+    /// The variant `untagged_variant` contains a niche at an arbitrary
+    /// offset (field `tag_field` of the enum), which for a variant with
+    /// discriminant `d` is set to
+    /// `(d - niche_variants.start).wrapping_add(niche_start)`.
+    ///
+    /// For example, `Option<(usize, &T)>`  is represented such that
+    /// `None` has a null pointer for the second tuple field, and
+    /// `Some` is the identity function (with a non-null reference).
+    Niche {
+        untagged_variant: VariantIdx,
+        niche_variants: RangeInclusive<VariantIdx>,
+        niche_start: u128,
+    },
+}
+
+/// Describes how values of the type are passed by target ABIs,
+/// in terms of categories of C types there are ABI rules for.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValueAbi {
+    Uninhabited,
+    Scalar(Scalar),
+    ScalarPair(Scalar, Scalar),
+    Vector {
+        element: Scalar,
+        count: u64,
+    },
+    Aggregate {
+        /// If true, the size is exact, otherwise it's only a lower bound.
+        sized: bool,
+    },
+}
+
+/// Information about one scalar component of a Rust type.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Scalar {
+    Initialized {
+        /// The primitive type used to represent this value.
+        value: Primitive,
+        /// The range that represents valid values.
+        /// The range must be valid for the `primitive.size()`.
+        valid_range: WrappingRange,
+    },
+    Union {
+        /// Unions never have niches, so there is no `valid_range`.
+        /// Even for unions, we need to use the correct registers for the kind of
+        /// values inside the union, so we keep the `Primitive` type around.
+        /// It is also used to compute the size of the scalar.
+        value: Primitive,
+    },
+}
+
+/// Fundamental unit of memory access and layout.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Primitive {
+    /// The `signed` field is the signedness of the `Integer` type.
+    Int { length: IntegerLength, signed: bool },
+    F32,
+    F64,
+    Pointer(AddressSpace),
+}
+
+/// Integers, also used for enum discriminants.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IntegerLength {
+    I8,
+    I16,
+    I32,
+    I64,
+    I128,
+}
+
+/// An identifier that specifies the address space that some operation
+/// should operate on. Special address spaces have an effect on code generation,
+/// depending on the target and the address spaces it implements.
+pub type AddressSpace = u32;
+
+/// Inclusive wrap-around range of valid values (bitwise representation), that is, if
+/// start > end, it represents `start..=MAX`, followed by `0..=end`.
+///
+/// That is, for an i8 primitive, a range of `254..=2` means following
+/// sequence:
+///
+/// ```text
+/// 254 (-2), 255 (-1), 0, 1, 2
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WrappingRange {
+    pub start: u128,
+    pub end: u128,
+}
+
+impl WrappingRange {
+    /// Returns `true` if `v` is contained in the range.
+    pub fn contains(&self, v: u128) -> bool {
+        if self.start <= self.end {
+            self.start <= v && v <= self.end
+        } else {
+            self.start <= v || v <= self.end
+        }
+    }
+}
+
+/// A scalar with invalid values that can be used to encode the discriminant of an enum.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Niche {
+    pub offset: Size,
+    pub value: Primitive,
+    pub valid_range: WrappingRange,
+}
+
+/// Information about how to pass arguments to, and return values from, a function.
+#[derive(Clone, Debug)]
+pub struct FnAbi {
+    /// The type, layout and passing mode of each argument.
+    pub args: Vec<ArgAbi>,
+
+    /// The type, layout and passing mode of the return value.
+    pub ret: ArgAbi,
+
+    /// The count of non-variadic arguments.
+    ///
+    /// Should only be different from `args.len()` when a function is a C variadic function.
+    pub fixed_count: u32,
+
+    /// The ABI convention.
+    pub conv: CallConvention,
+
+    /// Whether this is a variadic C function.
+    pub c_variadic: bool,
+
+    /// Whether this function may unwind.
+    pub can_unwind: bool,
+}
+
+/// Information about how to pass an argument to, or return a value from, a function.
+#[derive(Clone, Debug)]
+pub struct ArgAbi {
+    pub ty: Ty,
+    pub layout: LayoutShape,
+    pub mode: PassMode,
+}
+
+/// How a function argument should be passed in to the target function.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PassMode {
+    /// Ignore the argument.
+    ///
+    /// The argument is either uninhabited or a ZST.
+    Ignore,
+    /// Pass the argument directly.
+    ///
+    /// The argument has a layout abi of `Scalar` or `Vector`.
+    Direct(ArgAttributes),
+    /// Pass a pair's elements directly in two arguments.
+    ///
+    /// The argument has a layout abi of `ScalarPair`.
+    Pair(ArgAttributes, ArgAttributes),
+    /// Pass the argument after casting it. The cast target is kept opaque since it is tightly
+    /// coupled to the code generation backend.
+    Cast { pad_i32: bool, cast: Opaque },
+    /// Pass the argument indirectly via a hidden pointer.
+    /// The `meta_attrs` value, if any, is for the metadata (vtable or length) of an unsized
+    /// argument. `on_stack` defines that the value should be passed at a fixed stack offset in
+    /// accordance to the ABI rather than passed using a pointer.
+    Indirect { attrs: ArgAttributes, meta_attrs: Option<ArgAttributes>, on_stack: bool },
+}
+
+/// Attributes that describe how an argument is passed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ArgAttributes {
+    pub regular: Vec<ArgAttribute>,
+    pub arg_ext: ArgExtension,
+    /// The minimum size of the pointee, guaranteed to be valid for the duration of the whole call.
+    pub pointee_size: Size,
+    pub pointee_align: Option<Align>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArgAttribute {
+    NoAlias,
+    NoCapture,
+    NonNull,
+    ReadOnly,
+    InReg,
+    NoUndef,
+}
+
+/// Sometimes an ABI requires small integers to be extended to a full or partial register.
+/// This enum defines if this extension should be zero-extension or sign-extension when necessary.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArgExtension {
+    None,
+    Zext,
+    Sext,
+}
+
+/// The calling convention used by a function.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CallConvention {
+    C,
+    Rust,
+
+    Cold,
+    PreserveMost,
+    PreserveAll,
+
+    // Target-specific calling conventions.
+    ArmAapcs,
+    CCmseNonSecureCall,
+
+    Msp430Intr,
+
+    PtxKernel,
+
+    X86Fastcall,
+    X86Intr,
+    X86Stdcall,
+    X86ThisCall,
+    X86VectorCall,
+
+    X86_64SysV,
+    X86_64Win64,
+
+    AmdGpuKernel,
+    AvrInterrupt,
+    AvrNonBlockingInterrupt,
+
+    RiscvInterruptMachine,
+    RiscvInterruptSupervisor,
+}
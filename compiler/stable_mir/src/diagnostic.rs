@@ -0,0 +1,68 @@
+//! Report diagnostics about the code being compiled.
+//!
+//! Diagnostics are emitted by the compiler, so they are rendered the same way as the compiler's
+//! own warnings and errors. Note that emitting an error causes the compilation to fail.
+
+use crate::ty::Span;
+use crate::with;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Level {
+    Error,
+    Warning,
+    Note,
+    Help,
+}
+
+#[derive(Clone, Debug)]
+pub struct Diagnostic {
+    pub level: Level,
+    pub message: String,
+    /// The primary span of the diagnostic.
+    pub span: Span,
+    /// Secondary spans with a label that explains their relevance.
+    pub labels: Vec<(Span, String)>,
+    pub notes: Vec<String>,
+    pub help: Vec<String>,
+}
+
+impl Diagnostic {
+    pub fn new(level: Level, span: Span, message: impl Into<String>) -> Diagnostic {
+        Diagnostic {
+            level,
+            message: message.into(),
+            span,
+            labels: vec![],
+            notes: vec![],
+            help: vec![],
+        }
+    }
+
+    pub fn error(span: Span, message: impl Into<String>) -> Diagnostic {
+        Diagnostic::new(Level::Error, span, message)
+    }
+
+    pub fn warning(span: Span, message: impl Into<String>) -> Diagnostic {
+        Diagnostic::new(Level::Warning, span, message)
+    }
+
+    pub fn span_label(mut self, span: Span, label: impl Into<String>) -> Diagnostic {
+        self.labels.push((span, label.into()));
+        self
+    }
+
+    pub fn note(mut self, note: impl Into<String>) -> Diagnostic {
+        self.notes.push(note.into());
+        self
+    }
+
+    pub fn help(mut self, help: impl Into<String>) -> Diagnostic {
+        self.help.push(help.into());
+        self
+    }
+
+    /// Report this diagnostic to the user.
+    pub fn emit(&self) {
+        with(|cx| cx.emit_diagnostic(self))
+    }
+}
@@ -0,0 +1,73 @@
+//! Metadata of definitions, such as their attributes, visibility and definition path.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use crate::ty::Span;
+use crate::{CrateNum, DefId, Symbol};
+
+/// An attribute attached to a definition, after `#[cfg_attr]` has been expanded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Attribute {
+    /// The path of the attribute, e.g. `["mytool", "foo"]` for `#[mytool::foo(bar)]`.
+    /// This is empty for sugared doc comments, such as `/// Some docs`.
+    pub path: Vec<Symbol>,
+    /// The attribute as it would be written in the source, e.g. `#[mytool::foo(bar)]`.
+    pub value: String,
+    pub span: Span,
+}
+
+impl Attribute {
+    /// Check whether the path of this attribute is equal to `path`, e.g. `&["mytool", "foo"]`.
+    pub fn path_matches(&self, path: &[&str]) -> bool {
+        self.path.iter().eq(path.iter())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Visibility {
+    /// Visible everywhere, including in other crates.
+    Public,
+    /// Visible only in the given module and its descendants.
+    Restricted(DefId),
+}
+
+/// The path from the crate root to a definition.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DefPath {
+    pub krate: CrateNum,
+    pub data: Vec<DisambiguatedDefPathData>,
+}
+
+/// A segment of a [DefPath]. The disambiguator distinguishes segments that would otherwise be
+/// equal, such as two closures defined in the same function.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct DisambiguatedDefPathData {
+    pub data: DefPathData,
+    pub disambiguator: u32,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum DefPathData {
+    CrateRoot,
+    Impl,
+    ForeignMod,
+    Use,
+    GlobalAsm,
+    /// Something in the type namespace, such as a module, a type or a trait.
+    TypeNs(Symbol),
+    /// Something in the value namespace, such as a function, a constant or a static.
+    ValueNs(Symbol),
+    MacroNs(Symbol),
+    LifetimeNs(Symbol),
+    ClosureExpr,
+    Ctor,
+    AnonConst,
+    ImplTrait,
+    ImplTraitAssocTy,
+}
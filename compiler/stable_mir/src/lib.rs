@@ -20,16 +20,32 @@
 use std::cell::Cell;
 use std::fmt;
 use std::fmt::Debug;
+use std::io;
 
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use self::abi::{FnAbi, LayoutShape};
+use self::diagnostic::Diagnostic;
+use self::item::{Attribute, DefPath, Visibility};
+use self::mir::alloc::{GlobalAlloc, Value};
+use self::mir::mono::{Instance, InstanceDef, MonoItem, StaticDef};
+use self::mir::VariantIdx;
 use self::ty::{
-    GenericPredicates, Generics, ImplDef, ImplTrait, Span, TraitDecl, TraitDef, Ty, TyKind,
+    AdtDef, Allocation, Const, Filename, FnDef, GenericArgs, GenericPredicates, Generics, ImplDef,
+    ImplTrait, LineInfo, MacroExpansion, Span, TraitDecl, TraitDef, TraitRef, Ty, TyKind,
 };
 
 #[macro_use]
 extern crate scoped_tls;
 
+pub mod abi;
+pub mod diagnostic;
 pub mod fold;
+pub mod item;
 pub mod mir;
+#[cfg(feature = "serde")]
+pub mod serialize;
 pub mod ty;
 pub mod visitor;
 
@@ -41,6 +57,7 @@
 
 /// A unique identification number for each item accessible for the current compilation unit.
 #[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct DefId(pub usize);
 
 impl Debug for DefId {
@@ -52,8 +69,16 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
     }
 }
 
+impl DefId {
+    /// Return the path of this definition, as printed by the compiler, e.g. `std::vec::Vec`.
+    pub fn name(&self) -> Symbol {
+        with(|cx| cx.name_of_def_id(*self))
+    }
+}
+
 /// A unique identification number for each provenance
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct AllocId(pub usize);
 
 /// A list of crate items.
@@ -79,8 +104,27 @@ pub enum CompilerError<T> {
     Skipped,
 }
 
+/// An error returned by a compiler query, such as a layout that could not be computed.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Error(String);
+
+impl Error {
+    pub fn new(msg: String) -> Self {
+        Error(msg)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for Error {}
+
 /// Holds information about a crate.
 #[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Crate {
     pub id: CrateNum,
     pub name: Symbol,
@@ -89,10 +133,21 @@ pub struct Crate {
 
 pub type DefKind = Opaque;
 
+/// The kind of a crate item that can be evaluated or called.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ItemKind {
+    /// A function, a closure, or the constructor of a tuple struct or variant.
+    Fn,
+    Static,
+    Const,
+}
+
 /// Holds information about an item in the crate.
 /// For now, it only stores the item DefId. Use functions inside `rustc_internal` module to
 /// use this item.
 #[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct CrateItem(pub DefId);
 
 impl CrateItem {
@@ -111,6 +166,51 @@ pub fn name(&self) -> String {
     pub fn kind(&self) -> DefKind {
         with(|cx| cx.def_kind(self.0))
     }
+
+    pub fn item_kind(&self) -> ItemKind {
+        with(|cx| cx.item_kind(self.0))
+    }
+
+    /// Retrieve all the attributes of this item, including tool attributes and doc comments.
+    pub fn attrs(&self) -> Vec<Attribute> {
+        with(|cx| cx.attrs(self.0))
+    }
+
+    /// Retrieve the attributes of this item whose path matches `path`, e.g. `&["mytool", "foo"]`.
+    pub fn attrs_by_path(&self, path: &[&str]) -> Vec<Attribute> {
+        self.attrs().into_iter().filter(|attr| attr.path_matches(path)).collect()
+    }
+
+    /// Retrieve the documentation of this item, with the comment markers and the common
+    /// indentation removed. This includes `#[doc]` attributes enabled by `#[cfg_attr]`.
+    pub fn doc_comments(&self) -> Option<String> {
+        with(|cx| cx.doc_comments(self.0))
+    }
+
+    pub fn visibility(&self) -> Visibility {
+        with(|cx| cx.visibility(self.0))
+    }
+
+    pub fn def_path(&self) -> DefPath {
+        with(|cx| cx.def_path(self.0))
+    }
+
+    pub fn generics_of(&self) -> Generics {
+        with(|cx| cx.generics_of(self.0))
+    }
+
+    pub fn predicates_of(&self) -> GenericPredicates {
+        with(|cx| cx.predicates_of(self.0))
+    }
+
+    pub fn explicit_predicates_of(&self) -> GenericPredicates {
+        with(|cx| cx.explicit_predicates_of(self.0))
+    }
+
+    /// Write the MIR of this item in the same textual format as `-Zunpretty=mir`.
+    pub fn emit_mir<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        self.body().dump(w, &self.name())
+    }
 }
 
 /// Return the function where execution starts if the current
@@ -156,6 +256,15 @@ pub fn trait_impl(trait_impl: &ImplDef) -> ImplTrait {
     with(|cx| cx.trait_impl(trait_impl))
 }
 
+/// Return all mono items that are reachable from [`entry_fn`], including the entry function
+/// itself. This is empty if the crate does not define an entry function.
+pub fn reachable_mono_items() -> Vec<MonoItem> {
+    entry_fn()
+        .and_then(|entry| Instance::mono(&entry))
+        .map(|entry| entry.reachable_items())
+        .unwrap_or_default()
+}
+
 pub trait Context {
     fn entry_fn(&mut self) -> Option<CrateItem>;
     /// Retrieve all items of the local crate that have a MIR associated with them.
@@ -182,6 +291,33 @@ pub trait Context {
     /// Prints a human readable form of `Span`
     fn print_span(&self, span: Span) -> String;
 
+    /// Return the name of the file that contains the given span.
+    fn get_filename(&self, span: Span) -> Filename;
+
+    /// Return the lines and columns where the given span starts and ends.
+    fn get_lines(&self, span: Span) -> LineInfo;
+
+    /// Return the source code covered by a span, if it is available.
+    fn span_snippet(&self, span: Span) -> Option<String>;
+
+    /// Return the macro expansions that produced a span, starting with the innermost one.
+    fn macro_backtrace(&mut self, span: Span) -> Vec<MacroExpansion>;
+
+    /// Report a diagnostic through the compiler.
+    fn emit_diagnostic(&self, diagnostic: &Diagnostic);
+
+    /// Retrieve the attributes of a definition.
+    fn attrs(&mut self, def_id: DefId) -> Vec<Attribute>;
+
+    /// Retrieve the documentation of a definition, if it has any.
+    fn doc_comments(&self, def_id: DefId) -> Option<String>;
+
+    /// Get the visibility of a definition.
+    fn visibility(&mut self, def_id: DefId) -> Visibility;
+
+    /// Get the path from the crate root to a definition.
+    fn def_path(&mut self, def_id: DefId) -> DefPath;
+
     /// Prints the kind of given `DefId`
     fn def_kind(&mut self, def_id: DefId) -> DefKind;
 
@@ -193,6 +329,78 @@ pub trait Context {
 
     /// Create a new `Ty` from scratch without information from rustc.
     fn mk_ty(&mut self, kind: TyKind) -> Ty;
+
+    /// Get the instance of a non-generic item, if it does not require generic arguments.
+    fn mono_instance(&mut self, item: DefId) -> Option<Instance>;
+
+    /// Resolve a function definition with the given generic arguments into an instance.
+    fn resolve_instance(&mut self, def: FnDef, args: &GenericArgs) -> Option<Instance>;
+
+    /// Get the trait that declares the given associated item, if any.
+    fn trait_of_item(&mut self, def_id: DefId) -> Option<TraitDef>;
+
+    /// Check whether a trait reference must hold, ignoring lifetime constraints.
+    fn trait_ref_holds(&mut self, trait_ref: &TraitRef) -> bool;
+
+    /// Find the user-written impl that satisfies the given trait reference.
+    fn resolve_impl(&mut self, trait_ref: &TraitRef) -> Option<ImplDef>;
+
+    /// Resolve the drop glue instance for the given type.
+    fn resolve_drop_in_place(&mut self, ty: Ty) -> Instance;
+
+    /// Get the monomorphized body of an instance, if it has one.
+    fn instance_body(&mut self, instance: InstanceDef) -> Option<mir::Body>;
+
+    /// Get the monomorphized type of an instance.
+    fn instance_ty(&mut self, instance: InstanceDef) -> Ty;
+
+    /// Get the generic arguments of an instance.
+    fn instance_args(&mut self, instance: InstanceDef) -> GenericArgs;
+
+    /// Get the definition an instance was generated from.
+    fn instance_def_id(&mut self, instance: InstanceDef) -> DefId;
+
+    /// Prints a human readable form of an instance.
+    fn instance_name(&self, instance: InstanceDef) -> String;
+
+    /// Get the symbol name of an instance.
+    fn instance_mangled_name(&self, instance: InstanceDef) -> String;
+
+    /// Collect all mono items that are transitively used by the given instance.
+    fn reachable_mono_items(&mut self, root: Instance) -> Vec<MonoItem>;
+
+    /// Get the layout of a type.
+    fn ty_layout(&mut self, ty: Ty) -> Result<LayoutShape, Error>;
+
+    /// Get the ABI used to call an instance.
+    fn instance_abi(&mut self, def: InstanceDef) -> Result<FnAbi, Error>;
+
+    /// Prints a type the same way the compiler does in MIR dumps.
+    fn ty_pretty(&mut self, ty: Ty) -> String;
+
+    /// Prints the value of a constant the same way the compiler does in MIR dumps.
+    fn const_literal(&mut self, cnst: &Const) -> String;
+
+    /// Prints the path to an ADT variant, including its generic arguments.
+    fn variant_path(&mut self, def: AdtDef, idx: VariantIdx, args: &GenericArgs) -> String;
+
+    /// Get the kind of a crate item.
+    fn item_kind(&mut self, def: DefId) -> ItemKind;
+
+    /// Get the type of an item, such as a static or a constant.
+    fn def_ty(&mut self, def: DefId) -> Ty;
+
+    /// Evaluate a constant that has not been evaluated yet.
+    fn eval_const(&mut self, cnst: &Const) -> Result<Allocation, Error>;
+
+    /// Evaluate the initializer of a static.
+    fn eval_static_initializer(&mut self, def: StaticDef) -> Result<Allocation, Error>;
+
+    /// Decode the contents of an allocation as a value of the given type.
+    fn decode_allocation(&mut self, alloc: &Allocation, ty: Ty) -> Result<Value, Error>;
+
+    /// Retrieve the global allocation identified by the given id.
+    fn global_alloc(&mut self, id: AllocId) -> GlobalAlloc;
 }
 
 // A thread local variable that stores a pointer to the tables mapping between TyCtxt
@@ -201,13 +409,19 @@ pub trait Context {
 
 pub fn run(mut context: impl Context, f: impl FnOnce()) {
     assert!(!TLV.is_set());
-    fn g<'a>(mut context: &mut (dyn Context + 'a), f: impl FnOnce()) {
-        let ptr: *mut () = &mut context as *mut &mut _ as _;
-        TLV.set(&Cell::new(ptr), || {
-            f();
-        });
-    }
-    g(&mut context, f);
+    run_scoped(&mut context, f);
+}
+
+/// Sets `context` as the current context while `f` runs, like [run], but borrows the context.
+///
+/// Unlike [run], this can be called while another context is set, e.g., from a compiler query
+/// that got invoked through the stable MIR APIs. The previous context is restored once `f`
+/// returns. Values obtained from one context must not be used with another.
+pub fn run_scoped(mut context: &mut dyn Context, f: impl FnOnce()) {
+    let ptr: *mut () = &mut context as *mut &mut _ as _;
+    TLV.set(&Cell::new(ptr), || {
+        f();
+    });
 }
 
 /// Loads the current context and calls a function with it.
@@ -222,7 +436,8 @@ pub fn with<R>(f: impl FnOnce(&mut dyn Context) -> R) -> R {
 }
 
 /// A type that provides internal information but that can still be used for debug purpose.
-#[derive(Clone)]
+#[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Opaque(String);
 
 impl std::fmt::Display for Opaque {
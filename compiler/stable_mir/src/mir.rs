@@ -1,3 +1,8 @@
+pub mod alloc;
 mod body;
+pub mod dataflow;
+pub mod mono;
+mod pretty;
+pub mod visit;
 
 pub use body::*;
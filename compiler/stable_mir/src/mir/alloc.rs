@@ -0,0 +1,54 @@
+//! This module provides methods to retrieve allocation information, such as static variables.
+use crate::mir::mono::{Instance, StaticDef};
+use crate::ty::{Allocation, Binder, ExistentialTraitRef, Ty};
+use crate::{with, AllocId};
+
+/// An allocation in the compiler's global memory, which can be the target of a pointer.
+#[derive(Debug, Clone)]
+pub enum GlobalAlloc {
+    /// The alloc ID is used as a function pointer.
+    Function(Instance),
+    /// This alloc ID points to a symbolic (not-reified) vtable.
+    /// The `None` trait ref is used to represent auto traits.
+    VTable(Ty, Option<Binder<ExistentialTraitRef>>),
+    /// The alloc ID points to a "lazy" static variable that did not get computed (yet).
+    /// This is also used to break the cycle in recursive statics.
+    Static(StaticDef),
+    /// The alloc ID points to memory.
+    Memory(Allocation),
+}
+
+impl From<AllocId> for GlobalAlloc {
+    fn from(value: AllocId) -> Self {
+        with(|cx| cx.global_alloc(value))
+    }
+}
+
+/// The structured contents of an allocation, decoded according to its type.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Bool(bool),
+    Char(char),
+    /// A signed integer, sign extended to 128 bits.
+    Int(i128),
+    /// An unsigned integer, zero extended to 128 bits.
+    Uint(u128),
+    F32(f32),
+    F64(f64),
+    /// The contents of a `&str`.
+    Str(String),
+    /// A thin pointer. `alloc` is `None` for pointers that are just an integer address.
+    Pointer {
+        alloc: Option<AllocId>,
+        offset: u64,
+    },
+    Tuple(Vec<Value>),
+    Array(Vec<Value>),
+    /// A struct, enum or union value, with the index of its active variant.
+    Adt {
+        variant: usize,
+        fields: Vec<Value>,
+    },
+    /// A value of a zero-sized type, such as a function item.
+    ZeroSized,
+}
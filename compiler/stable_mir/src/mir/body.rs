@@ -1,32 +1,91 @@
+use crate::mir::pretty::function_body;
 use crate::ty::{AdtDef, ClosureDef, Const, GeneratorDef, GenericArgs, Movability, Region};
 use crate::Opaque;
 use crate::{ty::Ty, Span};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::io;
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Body {
     pub blocks: Vec<BasicBlock>,
+
+    /// Declarations of locals. The first local is the return value, followed by `arg_count`
+    /// locals for the function arguments, followed by any user-declared variables and
+    /// temporaries.
     pub locals: Vec<LocalDecl>,
+
+    /// The number of arguments this function takes.
+    pub arg_count: usize,
+}
+
+impl Body {
+    /// Return the declaration of the local that holds the return value.
+    pub fn ret_local(&self) -> &LocalDecl {
+        &self.locals[RETURN_LOCAL]
+    }
+
+    /// Return the declarations of the locals that hold the function arguments.
+    pub fn arg_locals(&self) -> &[LocalDecl] {
+        &self.locals[1..][..self.arg_count]
+    }
+
+    /// Return the declarations of the user variables and temporaries of this body.
+    pub fn inner_locals(&self) -> &[LocalDecl] {
+        &self.locals[self.arg_count + 1..]
+    }
+
+    /// Write this body in the same textual format as `-Zunpretty=mir`, using `fn_name` as the
+    /// name of the function in the signature.
+    pub fn dump<W: io::Write>(&self, w: &mut W, fn_name: &str) -> io::Result<()> {
+        function_body(w, self, fn_name)
+    }
+
+    /// Declare a new local, e.g. to hold a temporary value of some instrumentation code, and
+    /// return its index.
+    pub fn new_local(&mut self, ty: Ty, span: Span, mutability: Mutability) -> Local {
+        self.locals.push(LocalDecl { ty, span, mutability });
+        self.locals.len() - 1
+    }
+
+    /// Append a new basic block to this body and return its index.
+    pub fn new_block(&mut self, block: BasicBlock) -> BasicBlockIdx {
+        self.blocks.push(block);
+        self.blocks.len() - 1
+    }
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct LocalDecl {
     pub ty: Ty,
     pub span: Span,
+    pub mutability: Mutability,
 }
 
+/// The index of a basic block in a body.
+pub type BasicBlockIdx = usize;
+
+/// The block where the execution of a body starts.
+pub const START_BLOCK: BasicBlockIdx = 0;
+
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct BasicBlock {
     pub statements: Vec<Statement>,
     pub terminator: Terminator,
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Terminator {
     pub kind: TerminatorKind,
     pub span: Span,
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum TerminatorKind {
     Goto {
         target: usize,
@@ -70,7 +129,48 @@ pub enum TerminatorKind {
     },
 }
 
-#[derive(Clone, Debug)]
+impl TerminatorKind {
+    /// Return the blocks that control flow may continue to after this terminator, including
+    /// the cleanup block that is executed if it unwinds.
+    pub fn successors(&self) -> Vec<BasicBlockIdx> {
+        use TerminatorKind::*;
+        match self {
+            Goto { target } => vec![*target],
+            SwitchInt { targets, otherwise, .. } => {
+                targets.iter().map(|target| target.target).chain(Some(*otherwise)).collect()
+            }
+            Resume | Abort | Return | Unreachable | GeneratorDrop => vec![],
+            Drop { target, unwind, .. } | Assert { target, unwind, .. } => {
+                Some(*target).into_iter().chain(unwind.cleanup()).collect()
+            }
+            Call { target, unwind, .. } => target.iter().copied().chain(unwind.cleanup()).collect(),
+            InlineAsm { destination, unwind, .. } => {
+                destination.iter().copied().chain(unwind.cleanup()).collect()
+            }
+        }
+    }
+
+    /// Return what happens if this terminator unwinds, if it can unwind at all.
+    pub fn unwind(&self) -> Option<&UnwindAction> {
+        use TerminatorKind::*;
+        match self {
+            Drop { unwind, .. }
+            | Call { unwind, .. }
+            | Assert { unwind, .. }
+            | InlineAsm { unwind, .. } => Some(unwind),
+            Goto { .. }
+            | SwitchInt { .. }
+            | Resume
+            | Abort
+            | Return
+            | Unreachable
+            | GeneratorDrop => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct InlineAsmOperand {
     pub in_value: Option<Operand>,
     pub out_place: Option<Place>,
@@ -80,6 +180,7 @@ pub struct InlineAsmOperand {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum UnwindAction {
     Continue,
     Unreachable,
@@ -87,7 +188,18 @@ pub enum UnwindAction {
     Cleanup(usize),
 }
 
+impl UnwindAction {
+    /// Return the block that is executed when unwinding, if any.
+    pub fn cleanup(&self) -> Option<BasicBlockIdx> {
+        match self {
+            UnwindAction::Cleanup(block) => Some(*block),
+            UnwindAction::Continue | UnwindAction::Unreachable | UnwindAction::Terminate => None,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum AssertMessage {
     BoundsCheck { len: Operand, index: Operand },
     Overflow(BinOp, Operand, Operand),
@@ -100,6 +212,7 @@ pub enum AssertMessage {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum BinOp {
     Add,
     AddUnchecked,
@@ -126,18 +239,21 @@ pub enum BinOp {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum UnOp {
     Not,
     Neg,
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum GeneratorKind {
     Async(AsyncGeneratorKind),
     Gen,
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum AsyncGeneratorKind {
     Block,
     Closure,
@@ -152,6 +268,7 @@ pub enum AsyncGeneratorKind {
 
 /// The FakeReadCause describes the type of pattern why a FakeRead statement exists.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum FakeReadCause {
     ForMatchGuard,
     ForMatchedPlace(LocalDefId),
@@ -162,6 +279,7 @@ pub enum FakeReadCause {
 
 /// Describes what kind of retag is to be performed
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum RetagKind {
     FnEntry,
     TwoPhase,
@@ -170,6 +288,7 @@ pub enum RetagKind {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Variance {
     Covariant,
     Invariant,
@@ -178,6 +297,7 @@ pub enum Variance {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct CopyNonOverlapping {
     pub src: Operand,
     pub dst: Operand,
@@ -185,18 +305,21 @@ pub struct CopyNonOverlapping {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum NonDivergingIntrinsic {
     Assume(Operand),
     CopyNonOverlapping(CopyNonOverlapping),
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Statement {
     pub kind: StatementKind,
     pub span: Span,
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum StatementKind {
     Assign(Place, Rvalue),
     FakeRead(FakeReadCause, Place),
@@ -214,6 +337,7 @@ pub enum StatementKind {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Rvalue {
     /// Creates a pointer with the indicated mutability to the place.
     ///
@@ -326,6 +450,7 @@ pub enum Rvalue {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum AggregateKind {
     Array(Ty),
     Tuple,
@@ -335,6 +460,7 @@ pub enum AggregateKind {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Operand {
     Copy(Place),
     Move(Place),
@@ -342,12 +468,54 @@ pub enum Operand {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Place {
     pub local: Local,
-    pub projection: String,
+    /// Projections applied to the local, in the order they are applied.
+    pub projection: Vec<ProjectionElem>,
+}
+
+impl Place {
+    /// Return whether this place goes through a dereference, i.e., whether it refers to memory
+    /// that is not owned by its local.
+    pub fn is_indirect(&self) -> bool {
+        self.projection.iter().any(|elem| matches!(elem, ProjectionElem::Deref))
+    }
+}
+
+/// A single step of a place projection, such as a dereference or a field access.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum ProjectionElem {
+    /// Dereference the place.
+    Deref,
+
+    /// Access a field of a struct, union, tuple or closure, together with the type of the field.
+    Field(FieldIdx, Ty),
+
+    /// Index into a slice or array with the value stored in the given local.
+    Index(Local),
+
+    /// Index into a slice or array with a constant offset. If `from_end` is true, the offset is
+    /// counted from the end of the slice, which must be at least `min_length` long.
+    ConstantIndex { offset: u64, min_length: u64, from_end: bool },
+
+    /// Select the elements `from..to` of a slice or array. If `from_end` is true, `to` is
+    /// counted from the end of the slice.
+    Subslice { from: u64, to: u64, from_end: bool },
+
+    /// Reinterpret the place as the given variant of an enum.
+    Downcast(VariantIdx),
+
+    /// Reveal the hidden type of an opaque type.
+    OpaqueCast(Ty),
+
+    /// Change the type of the place to a subtype of its current type.
+    Subtype(Ty),
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct UserTypeProjection {
     pub base: UserTypeAnnotationIndex,
     pub projection: String,
@@ -355,6 +523,9 @@ pub struct UserTypeProjection {
 
 pub type Local = usize;
 
+/// The local that holds the return value of a function.
+pub const RETURN_LOCAL: Local = 0;
+
 type FieldIdx = usize;
 
 /// The source-order index of a variant in a type.
@@ -363,6 +534,7 @@ pub struct UserTypeProjection {
 type UserTypeAnnotationIndex = usize;
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Constant {
     pub span: Span,
     pub user_ty: Option<UserTypeAnnotationIndex>,
@@ -370,12 +542,14 @@ pub struct Constant {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct SwitchTarget {
     pub value: u128,
     pub target: usize,
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum BorrowKind {
     /// Data must be immutable and is aliasable.
     Shared,
@@ -393,6 +567,7 @@ pub enum BorrowKind {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum MutBorrowKind {
     Default,
     TwoPhaseBorrow,
@@ -400,18 +575,21 @@ pub enum MutBorrowKind {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Mutability {
     Not,
     Mut,
 }
 
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Safety {
     Unsafe,
     Normal,
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum PointerCoercion {
     /// Go from a fn-item type to a fn-pointer type.
     ReifyFnPointer,
@@ -439,6 +617,7 @@ pub enum PointerCoercion {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum CastKind {
     PointerExposeAddress,
     PointerFromExposedAddress,
@@ -454,6 +633,7 @@ pub enum CastKind {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum NullOp {
     /// Returns the size of a value of that type.
     SizeOf,
@@ -0,0 +1,509 @@
+//! # Dataflow analyses over the Stable MIR
+//!
+//! This module provides a generic engine that computes the fixpoint of a dataflow analysis over
+//! a [Body], modeled after `rustc_mir_dataflow`.
+//!
+//! An analysis is defined by implementing [Analysis], or [GenKillAnalysis] for the common case
+//! of analyses whose transfer functions only add ("gen") or remove ("kill") locals from a set.
+//! Calling [Analysis::iterate_to_fixpoint] then computes the state at the entry of every basic
+//! block, and the returned [Results] can be queried for the state at any [Location].
+//!
+//! For example, to compute which locals are live before a statement:
+//!
+//! ```ignore(needs-extern-crate)
+//! let results = MaybeLiveLocals.iterate_to_fixpoint(&body);
+//! let live = results.state_before(&body, location);
+//! ```
+//!
+//! The analyses in [impls] cover some of the questions that are commonly asked about locals.
+
+use std::collections::VecDeque;
+use std::fmt;
+
+use crate::mir::visit::Location;
+use crate::mir::{
+    BasicBlockIdx, Body, Local, Place, Statement, Terminator, TerminatorKind, START_BLOCK,
+};
+
+pub mod impls;
+
+/// A fixed-size set of indices, such as [Local]s, backed by a bit vector.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct BitSet {
+    domain_size: usize,
+    words: Vec<u64>,
+}
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+impl BitSet {
+    /// Create a set that may hold the indices `0..domain_size`, with no element.
+    pub fn new_empty(domain_size: usize) -> BitSet {
+        BitSet { domain_size, words: vec![0; domain_size.div_ceil(WORD_BITS)] }
+    }
+
+    /// Create a set that holds all the indices `0..domain_size`.
+    pub fn new_filled(domain_size: usize) -> BitSet {
+        let mut set = BitSet { domain_size, words: vec![!0; domain_size.div_ceil(WORD_BITS)] };
+        set.clear_excess_bits();
+        set
+    }
+
+    /// The number of indices this set may hold.
+    pub fn domain_size(&self) -> usize {
+        self.domain_size
+    }
+
+    pub fn contains(&self, elem: usize) -> bool {
+        let (word, mask) = self.word_and_mask(elem);
+        self.words[word] & mask != 0
+    }
+
+    /// Insert `elem`, and return whether the set changed.
+    pub fn insert(&mut self, elem: usize) -> bool {
+        let (word, mask) = self.word_and_mask(elem);
+        let old = self.words[word];
+        self.words[word] |= mask;
+        old != self.words[word]
+    }
+
+    /// Remove `elem`, and return whether the set changed.
+    pub fn remove(&mut self, elem: usize) -> bool {
+        let (word, mask) = self.word_and_mask(elem);
+        let old = self.words[word];
+        self.words[word] &= !mask;
+        old != self.words[word]
+    }
+
+    /// Add all the elements of `other` to this set, and return whether the set changed.
+    pub fn union(&mut self, other: &BitSet) -> bool {
+        self.combine(other, |a, b| a | b)
+    }
+
+    /// Remove all the elements of `other` from this set, and return whether the set changed.
+    pub fn subtract(&mut self, other: &BitSet) -> bool {
+        self.combine(other, |a, b| a & !b)
+    }
+
+    /// Keep only the elements that are also in `other`, and return whether the set changed.
+    pub fn intersect(&mut self, other: &BitSet) -> bool {
+        self.combine(other, |a, b| a & b)
+    }
+
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|word| *word == 0)
+    }
+
+    /// The number of elements in the set.
+    pub fn count(&self) -> usize {
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    /// Iterate over the elements of the set, in increasing order.
+    pub fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.domain_size).filter(|elem| self.contains(*elem))
+    }
+
+    fn word_and_mask(&self, elem: usize) -> (usize, u64) {
+        assert!(elem < self.domain_size, "index {elem} out of bounds of {}", self.domain_size);
+        (elem / WORD_BITS, 1 << (elem % WORD_BITS))
+    }
+
+    fn combine(&mut self, other: &BitSet, op: impl Fn(u64, u64) -> u64) -> bool {
+        assert_eq!(self.domain_size, other.domain_size);
+        let mut changed = false;
+        for (word, other) in self.words.iter_mut().zip(&other.words) {
+            let new = op(*word, *other);
+            changed |= new != *word;
+            *word = new;
+        }
+        changed
+    }
+
+    fn clear_excess_bits(&mut self) {
+        let excess = self.domain_size % WORD_BITS;
+        if excess != 0 {
+            let last = self.words.len() - 1;
+            self.words[last] &= (1 << excess) - 1;
+        }
+    }
+}
+
+impl fmt::Debug for BitSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.iter()).finish()
+    }
+}
+
+/// The domain of a dataflow analysis, where the states of the predecessors of a block are
+/// combined with a join operation.
+pub trait JoinSemiLattice: Clone + PartialEq {
+    /// Compute the least upper bound of `self` and `other`, store it in `self`, and return
+    /// whether `self` changed.
+    fn join(&mut self, other: &Self) -> bool;
+}
+
+impl JoinSemiLattice for BitSet {
+    fn join(&mut self, other: &Self) -> bool {
+        self.union(other)
+    }
+}
+
+impl JoinSemiLattice for bool {
+    fn join(&mut self, other: &Self) -> bool {
+        let changed = !*self && *other;
+        *self |= *other;
+        changed
+    }
+}
+
+/// The direction in which the effects of statements and terminators are propagated.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Direction {
+    /// From the entry block to the exit blocks, in the order of execution.
+    Forward,
+    /// From the exit blocks to the entry block, against the order of execution.
+    Backward,
+}
+
+/// A dataflow analysis.
+///
+/// The effects of a basic block are applied statement by statement, in the order given by
+/// [Analysis::DIRECTION]. The effect of a `Call` that assigns its return value is split in two:
+/// [Analysis::apply_terminator_effect] is applied on every edge, while
+/// [Analysis::apply_call_return_effect] is only applied on the edge to the return block.
+pub trait Analysis {
+    /// The state of the analysis at a given location.
+    type Domain: JoinSemiLattice;
+
+    const DIRECTION: Direction;
+
+    /// The initial state of every basic block, before any effect has been propagated.
+    fn bottom_value(&self, body: &Body) -> Self::Domain;
+
+    /// Update the state at the entry of the start block, e.g. to initialize the arguments.
+    ///
+    /// This is only called for forward analyses.
+    fn initialize_start_block(&self, body: &Body, state: &mut Self::Domain);
+
+    fn apply_statement_effect(
+        &self,
+        state: &mut Self::Domain,
+        statement: &Statement,
+        location: Location,
+    );
+
+    fn apply_terminator_effect(
+        &self,
+        state: &mut Self::Domain,
+        terminator: &Terminator,
+        location: Location,
+    );
+
+    /// Apply the effect of a successful `Call` that writes its result to `destination`.
+    fn apply_call_return_effect(
+        &self,
+        _state: &mut Self::Domain,
+        _block: BasicBlockIdx,
+        _destination: &Place,
+    ) {
+    }
+
+    /// Run the analysis over `body` until the state of every block stops changing.
+    fn iterate_to_fixpoint(self, body: &Body) -> Results<Self>
+    where
+        Self: Sized,
+    {
+        let entry_sets = match Self::DIRECTION {
+            Direction::Forward => iterate_forward(&self, body),
+            Direction::Backward => iterate_backward(&self, body),
+        };
+        Results { analysis: self, entry_sets }
+    }
+}
+
+/// A set that an analysis can add ("gen") and remove ("kill") elements from.
+pub trait GenKill<T> {
+    fn gen(&mut self, elem: T);
+
+    fn kill(&mut self, elem: T);
+
+    fn gen_all(&mut self, elems: impl IntoIterator<Item = T>) {
+        for elem in elems {
+            self.gen(elem);
+        }
+    }
+
+    fn kill_all(&mut self, elems: impl IntoIterator<Item = T>) {
+        for elem in elems {
+            self.kill(elem);
+        }
+    }
+}
+
+impl GenKill<Local> for BitSet {
+    fn gen(&mut self, elem: Local) {
+        self.insert(elem);
+    }
+
+    fn kill(&mut self, elem: Local) {
+        self.remove(elem);
+    }
+}
+
+/// A dataflow analysis whose state is a set of locals, and whose effects can only add or remove
+/// locals from that set. Every such analysis is also an [Analysis] with a [BitSet] domain, which
+/// is empty at the start.
+pub trait GenKillAnalysis {
+    const DIRECTION: Direction;
+
+    /// Add the locals that are in the set when the body starts executing.
+    fn initialize_start_block(&self, _body: &Body, _state: &mut BitSet) {}
+
+    fn statement_effect(
+        &self,
+        trans: &mut impl GenKill<Local>,
+        statement: &Statement,
+        location: Location,
+    );
+
+    fn terminator_effect(
+        &self,
+        trans: &mut impl GenKill<Local>,
+        terminator: &Terminator,
+        location: Location,
+    );
+
+    /// See [Analysis::apply_call_return_effect].
+    fn call_return_effect(
+        &self,
+        _trans: &mut impl GenKill<Local>,
+        _block: BasicBlockIdx,
+        _destination: &Place,
+    ) {
+    }
+}
+
+impl<A: GenKillAnalysis> Analysis for A {
+    type Domain = BitSet;
+
+    const DIRECTION: Direction = <A as GenKillAnalysis>::DIRECTION;
+
+    fn bottom_value(&self, body: &Body) -> BitSet {
+        BitSet::new_empty(body.locals.len())
+    }
+
+    fn initialize_start_block(&self, body: &Body, state: &mut BitSet) {
+        GenKillAnalysis::initialize_start_block(self, body, state)
+    }
+
+    fn apply_statement_effect(
+        &self,
+        state: &mut BitSet,
+        statement: &Statement,
+        location: Location,
+    ) {
+        self.statement_effect(state, statement, location)
+    }
+
+    fn apply_terminator_effect(
+        &self,
+        state: &mut BitSet,
+        terminator: &Terminator,
+        location: Location,
+    ) {
+        self.terminator_effect(state, terminator, location)
+    }
+
+    fn apply_call_return_effect(
+        &self,
+        state: &mut BitSet,
+        block: BasicBlockIdx,
+        destination: &Place,
+    ) {
+        self.call_return_effect(state, block, destination)
+    }
+}
+
+/// The fixpoint of a dataflow analysis over a body.
+pub struct Results<A: Analysis> {
+    pub analysis: A,
+    entry_sets: Vec<A::Domain>,
+}
+
+impl<A: Analysis> Results<A> {
+    /// Return the state at the entry of `block` in the direction of the analysis. For backward
+    /// analyses, this is the state after its terminator has executed.
+    pub fn entry_set_for_block(&self, block: BasicBlockIdx) -> &A::Domain {
+        &self.entry_sets[block]
+    }
+
+    /// Return the state right before the statement or terminator at `location` executes.
+    ///
+    /// `body` must be the body that the analysis was run on.
+    pub fn state_before(&self, body: &Body, location: Location) -> A::Domain {
+        self.state_at(body, location, false)
+    }
+
+    /// Return the state right after the statement or terminator at `location` executes. For a
+    /// `Call`, this does not include the effect of writing its return value.
+    ///
+    /// `body` must be the body that the analysis was run on.
+    pub fn state_after(&self, body: &Body, location: Location) -> A::Domain {
+        self.state_at(body, location, true)
+    }
+
+    fn state_at(&self, body: &Body, location: Location, after: bool) -> A::Domain {
+        let Location { block, statement_index } = location;
+        let data = &body.blocks[block];
+        assert!(statement_index <= data.statements.len(), "invalid location {location:?}");
+        let mut state = self.entry_sets[block].clone();
+        match A::DIRECTION {
+            Direction::Forward => {
+                let end = statement_index + usize::from(after);
+                for (index, statement) in data.statements.iter().enumerate().take(end) {
+                    let location = Location { block, statement_index: index };
+                    self.analysis.apply_statement_effect(&mut state, statement, location);
+                }
+                if end > data.statements.len() {
+                    self.analysis.apply_terminator_effect(&mut state, &data.terminator, location);
+                }
+            }
+            Direction::Backward => {
+                let start = statement_index + usize::from(after);
+                apply_block_backward(&self.analysis, &mut state, block, body, start);
+            }
+        }
+        state
+    }
+}
+
+/// A queue of basic blocks that contains every block at most once.
+struct WorkQueue {
+    queue: VecDeque<BasicBlockIdx>,
+    queued: BitSet,
+}
+
+impl WorkQueue {
+    fn new(blocks: impl Iterator<Item = BasicBlockIdx>, size: usize) -> WorkQueue {
+        WorkQueue { queue: blocks.collect(), queued: BitSet::new_filled(size) }
+    }
+
+    fn insert(&mut self, block: BasicBlockIdx) {
+        if self.queued.insert(block) {
+            self.queue.push_back(block);
+        }
+    }
+
+    fn pop(&mut self) -> Option<BasicBlockIdx> {
+        let block = self.queue.pop_front()?;
+        self.queued.remove(block);
+        Some(block)
+    }
+}
+
+fn iterate_forward<A: Analysis>(analysis: &A, body: &Body) -> Vec<A::Domain> {
+    let num_blocks = body.blocks.len();
+    let mut entry_sets = vec![analysis.bottom_value(body); num_blocks];
+    if num_blocks == 0 {
+        return entry_sets;
+    }
+    analysis.initialize_start_block(body, &mut entry_sets[START_BLOCK]);
+
+    let mut queue = WorkQueue::new(0..num_blocks, num_blocks);
+    while let Some(block) = queue.pop() {
+        let data = &body.blocks[block];
+        let mut state = entry_sets[block].clone();
+        for (index, statement) in data.statements.iter().enumerate() {
+            let location = Location { block, statement_index: index };
+            analysis.apply_statement_effect(&mut state, statement, location);
+        }
+        let location = Location { block, statement_index: data.statements.len() };
+        analysis.apply_terminator_effect(&mut state, &data.terminator, location);
+
+        let mut propagate = |target: BasicBlockIdx, state: &A::Domain| {
+            if entry_sets[target].join(state) {
+                queue.insert(target);
+            }
+        };
+        match &data.terminator.kind {
+            TerminatorKind::Call { destination, target, unwind, .. } => {
+                if let Some(cleanup) = unwind.cleanup() {
+                    propagate(cleanup, &state);
+                }
+                if let Some(target) = target {
+                    analysis.apply_call_return_effect(&mut state, block, destination);
+                    propagate(*target, &state);
+                }
+            }
+            kind => {
+                for target in kind.successors() {
+                    propagate(target, &state);
+                }
+            }
+        }
+    }
+    entry_sets
+}
+
+fn iterate_backward<A: Analysis>(analysis: &A, body: &Body) -> Vec<A::Domain> {
+    let num_blocks = body.blocks.len();
+    let mut entry_sets = vec![analysis.bottom_value(body); num_blocks];
+
+    let mut predecessors = vec![vec![]; num_blocks];
+    for (block, data) in body.blocks.iter().enumerate() {
+        for target in data.terminator.kind.successors() {
+            if !predecessors[target].contains(&block) {
+                predecessors[target].push(block);
+            }
+        }
+    }
+
+    let mut queue = WorkQueue::new((0..num_blocks).rev(), num_blocks);
+    while let Some(block) = queue.pop() {
+        let mut state = entry_sets[block].clone();
+        apply_block_backward(analysis, &mut state, block, body, 0);
+
+        for &pred in &predecessors[block] {
+            let changed = match &body.blocks[pred].terminator.kind {
+                TerminatorKind::Call { destination, target: Some(target), .. }
+                    if *target == block =>
+                {
+                    let mut returned = state.clone();
+                    analysis.apply_call_return_effect(&mut returned, pred, destination);
+                    entry_sets[pred].join(&returned)
+                }
+                _ => entry_sets[pred].join(&state),
+            };
+            if changed {
+                queue.insert(pred);
+            }
+        }
+    }
+    entry_sets
+}
+
+/// Apply the effects of the terminator of `block`, and of its statements starting from the one
+/// at index `start`, in reverse order.
+fn apply_block_backward<A: Analysis>(
+    analysis: &A,
+    state: &mut A::Domain,
+    block: BasicBlockIdx,
+    body: &Body,
+    start: usize,
+) {
+    let data = &body.blocks[block];
+    let num_statements = data.statements.len();
+    if start <= num_statements {
+        let location = Location { block, statement_index: num_statements };
+        analysis.apply_terminator_effect(state, &data.terminator, location);
+    }
+    for (index, statement) in data.statements.iter().enumerate().skip(start).rev() {
+        let location = Location { block, statement_index: index };
+        analysis.apply_statement_effect(state, statement, location);
+    }
+}
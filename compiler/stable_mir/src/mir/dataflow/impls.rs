@@ -0,0 +1,355 @@
+//! Dataflow analyses over the locals of a body.
+
+use crate::mir::dataflow::{BitSet, Direction, GenKill, GenKillAnalysis};
+use crate::mir::visit::Location;
+use crate::mir::{
+    AssertMessage, BasicBlockIdx, Body, BorrowKind, CopyNonOverlapping, InlineAsmOperand, Local,
+    Mutability, NonDivergingIntrinsic, Operand, Place, ProjectionElem, Rvalue, Statement,
+    StatementKind, Terminator, TerminatorKind, RETURN_LOCAL,
+};
+
+/// A live-variable analysis: a local is in the set if its current value may be read later.
+///
+/// A reference to a local only counts as a use where the reference is created, so this analysis
+/// should usually be combined with [MaybeBorrowedLocals]. Assigning to a field of a local is not
+/// a definition of the local, and assigning through a dereference is a use of the pointer.
+#[derive(Clone, Copy, Debug)]
+pub struct MaybeLiveLocals;
+
+impl GenKillAnalysis for MaybeLiveLocals {
+    const DIRECTION: Direction = Direction::Backward;
+
+    fn statement_effect(
+        &self,
+        trans: &mut impl GenKill<Local>,
+        statement: &Statement,
+        _location: Location,
+    ) {
+        let mut liveness = Liveness(trans);
+        match &statement.kind {
+            StatementKind::Assign(place, rvalue) => {
+                liveness.def(place);
+                for_each_place_in_rvalue(rvalue, |place| liveness.use_place(place));
+            }
+            StatementKind::Deinit(place) => liveness.def(place),
+            StatementKind::SetDiscriminant { place, .. } => {
+                // Setting the discriminant neither reads nor overwrites the whole place.
+                if place.is_indirect() {
+                    liveness.use_place(place);
+                } else {
+                    liveness.use_projection(place);
+                }
+            }
+            StatementKind::FakeRead(_, place)
+            | StatementKind::Retag(_, place)
+            | StatementKind::PlaceMention(place) => liveness.use_place(place),
+            StatementKind::Intrinsic(intrinsic) => read_places(intrinsic_operands(intrinsic))
+                .for_each(|place| liveness.use_place(place)),
+            StatementKind::AscribeUserType { .. }
+            | StatementKind::StorageLive(_)
+            | StatementKind::StorageDead(_)
+            | StatementKind::Coverage(_)
+            | StatementKind::ConstEvalCounter
+            | StatementKind::Nop => {}
+        }
+    }
+
+    fn terminator_effect(
+        &self,
+        trans: &mut impl GenKill<Local>,
+        terminator: &Terminator,
+        _location: Location,
+    ) {
+        let mut liveness = Liveness(trans);
+        match &terminator.kind {
+            TerminatorKind::Return => liveness.0.gen(RETURN_LOCAL),
+            TerminatorKind::Call { destination, .. } => {
+                // The destination is only defined if the call returns, which is handled by
+                // `call_return_effect`. Writing through a pointer is still a use of the pointer.
+                if destination.is_indirect() {
+                    liveness.use_place(destination);
+                } else {
+                    liveness.use_projection(destination);
+                }
+                read_places(terminator_operands(&terminator.kind))
+                    .for_each(|place| liveness.use_place(place));
+            }
+            TerminatorKind::InlineAsm { operands, .. } => {
+                for InlineAsmOperand { out_place, .. } in operands {
+                    if let Some(place) = out_place {
+                        liveness.def(place);
+                    }
+                }
+                read_places(terminator_operands(&terminator.kind))
+                    .for_each(|place| liveness.use_place(place));
+            }
+            TerminatorKind::Drop { place, .. } => liveness.use_place(place),
+            kind => {
+                read_places(terminator_operands(kind)).for_each(|place| liveness.use_place(place))
+            }
+        }
+    }
+
+    fn call_return_effect(
+        &self,
+        trans: &mut impl GenKill<Local>,
+        _block: BasicBlockIdx,
+        destination: &Place,
+    ) {
+        if destination.projection.is_empty() {
+            trans.kill(destination.local);
+        }
+    }
+}
+
+/// The transfer function of [MaybeLiveLocals].
+struct Liveness<'a, T>(&'a mut T);
+
+impl<T: GenKill<Local>> Liveness<'_, T> {
+    /// A place that is written to. This kills the local if the whole local is overwritten.
+    fn def(&mut self, place: &Place) {
+        if place.is_indirect() {
+            self.0.gen(place.local);
+        } else if place.projection.is_empty() {
+            self.0.kill(place.local);
+        }
+        self.use_projection(place);
+    }
+
+    /// A place that is read from.
+    fn use_place(&mut self, place: &Place) {
+        self.0.gen(place.local);
+        self.use_projection(place);
+    }
+
+    /// The locals used to index into a place.
+    fn use_projection(&mut self, place: &Place) {
+        for elem in &place.projection {
+            if let ProjectionElem::Index(local) = elem {
+                self.0.gen(*local);
+            }
+        }
+    }
+}
+
+/// An analysis of the locals that may be initialized: a local is in the set if it may have been
+/// assigned to, without being moved out of, dropped, deinitialized or marked as dead since then.
+///
+/// Function arguments are initialized when the body starts. Assigning to a field of a local or
+/// mutably borrowing it counts as initializing the local, while moving out of a field does not
+/// count as moving out of the local.
+#[derive(Clone, Copy, Debug)]
+pub struct MaybeInitializedLocals;
+
+impl GenKillAnalysis for MaybeInitializedLocals {
+    const DIRECTION: Direction = Direction::Forward;
+
+    fn initialize_start_block(&self, body: &Body, state: &mut BitSet) {
+        for arg in 1..=body.arg_count {
+            state.insert(arg);
+        }
+    }
+
+    fn statement_effect(
+        &self,
+        trans: &mut impl GenKill<Local>,
+        statement: &Statement,
+        _location: Location,
+    ) {
+        match &statement.kind {
+            StatementKind::Assign(place, rvalue) => {
+                // The operands are read before the destination is written.
+                trans.kill_all(moved_locals(rvalue_operands(rvalue)));
+                match rvalue {
+                    Rvalue::Ref(_, BorrowKind::Mut { .. }, borrowed)
+                    | Rvalue::AddressOf(Mutability::Mut, borrowed) => trans.gen(borrowed.local),
+                    _ => {}
+                }
+                trans.gen(place.local);
+            }
+            StatementKind::Deinit(place) if place.projection.is_empty() => trans.kill(place.local),
+            StatementKind::Deinit(place) | StatementKind::SetDiscriminant { place, .. } => {
+                trans.gen(place.local)
+            }
+            StatementKind::StorageDead(local) => trans.kill(*local),
+            StatementKind::Intrinsic(intrinsic) => {
+                trans.kill_all(moved_locals(intrinsic_operands(intrinsic)))
+            }
+            StatementKind::FakeRead(..)
+            | StatementKind::Retag(..)
+            | StatementKind::PlaceMention(_)
+            | StatementKind::AscribeUserType { .. }
+            | StatementKind::StorageLive(_)
+            | StatementKind::Coverage(_)
+            | StatementKind::ConstEvalCounter
+            | StatementKind::Nop => {}
+        }
+    }
+
+    fn terminator_effect(
+        &self,
+        trans: &mut impl GenKill<Local>,
+        terminator: &Terminator,
+        _location: Location,
+    ) {
+        trans.kill_all(moved_locals(terminator_operands(&terminator.kind)));
+        // The destination of a call is handled by `call_return_effect`.
+        match &terminator.kind {
+            // Dropping a field does not deinitialize the whole local.
+            TerminatorKind::Drop { place, .. } if place.projection.is_empty() => {
+                trans.kill(place.local)
+            }
+            TerminatorKind::InlineAsm { operands, .. } => {
+                for InlineAsmOperand { out_place, .. } in operands {
+                    if let Some(place) = out_place {
+                        trans.gen(place.local);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn call_return_effect(
+        &self,
+        trans: &mut impl GenKill<Local>,
+        _block: BasicBlockIdx,
+        destination: &Place,
+    ) {
+        trans.gen(destination.local);
+    }
+}
+
+/// An analysis of the locals that a pointer or reference may point to: a local is in the set if
+/// it may have been borrowed, or had its address taken, since its storage became live.
+///
+/// Dropping a local counts as mutably borrowing it, since its destructor receives a reference
+/// that it may leak.
+#[derive(Clone, Copy, Debug)]
+pub struct MaybeBorrowedLocals;
+
+impl GenKillAnalysis for MaybeBorrowedLocals {
+    const DIRECTION: Direction = Direction::Forward;
+
+    fn statement_effect(
+        &self,
+        trans: &mut impl GenKill<Local>,
+        statement: &Statement,
+        _location: Location,
+    ) {
+        match &statement.kind {
+            StatementKind::Assign(
+                _,
+                Rvalue::Ref(_, _, borrowed) | Rvalue::AddressOf(_, borrowed),
+            ) if !borrowed.is_indirect() => trans.gen(borrowed.local),
+            // Any pointer to the local is invalid once its storage is dead.
+            StatementKind::StorageDead(local) => trans.kill(*local),
+            _ => {}
+        }
+    }
+
+    fn terminator_effect(
+        &self,
+        trans: &mut impl GenKill<Local>,
+        terminator: &Terminator,
+        _location: Location,
+    ) {
+        if let TerminatorKind::Drop { place, .. } = &terminator.kind {
+            if !place.is_indirect() {
+                trans.gen(place.local);
+            }
+        }
+    }
+}
+
+/// Call `f` with the places that are read or borrowed by `rvalue`.
+fn for_each_place_in_rvalue(rvalue: &Rvalue, mut f: impl FnMut(&Place)) {
+    match rvalue {
+        Rvalue::AddressOf(_, place)
+        | Rvalue::CopyForDeref(place)
+        | Rvalue::Discriminant(place)
+        | Rvalue::Len(place)
+        | Rvalue::Ref(_, _, place) => f(place),
+        _ => read_places(rvalue_operands(rvalue)).for_each(f),
+    }
+}
+
+/// Return the places that are read by `operands`.
+fn read_places(operands: Vec<&Operand>) -> impl Iterator<Item = &Place> {
+    operands.into_iter().filter_map(|operand| match operand {
+        Operand::Copy(place) | Operand::Move(place) => Some(place),
+        Operand::Constant(_) => None,
+    })
+}
+
+/// Return the locals that are moved out of, as a whole, by `operands`.
+fn moved_locals(operands: Vec<&Operand>) -> impl Iterator<Item = Local> + '_ {
+    operands.into_iter().filter_map(|operand| match operand {
+        Operand::Move(place) if place.projection.is_empty() => Some(place.local),
+        _ => None,
+    })
+}
+
+/// Return the operands of an intrinsic.
+fn intrinsic_operands(intrinsic: &NonDivergingIntrinsic) -> Vec<&Operand> {
+    match intrinsic {
+        NonDivergingIntrinsic::Assume(operand) => vec![operand],
+        NonDivergingIntrinsic::CopyNonOverlapping(CopyNonOverlapping { src, dst, count }) => {
+            vec![src, dst, count]
+        }
+    }
+}
+
+/// Return the operands of an rvalue.
+fn rvalue_operands(rvalue: &Rvalue) -> Vec<&Operand> {
+    match rvalue {
+        Rvalue::Aggregate(_, operands) => operands.iter().collect(),
+        Rvalue::BinaryOp(_, lhs, rhs) | Rvalue::CheckedBinaryOp(_, lhs, rhs) => vec![lhs, rhs],
+        Rvalue::Cast(_, operand, _)
+        | Rvalue::ShallowInitBox(operand, _)
+        | Rvalue::Repeat(operand, _)
+        | Rvalue::UnaryOp(_, operand)
+        | Rvalue::Use(operand) => vec![operand],
+        Rvalue::AddressOf(..)
+        | Rvalue::CopyForDeref(_)
+        | Rvalue::Discriminant(_)
+        | Rvalue::Len(_)
+        | Rvalue::Ref(..)
+        | Rvalue::ThreadLocalRef(_)
+        | Rvalue::NullaryOp(..) => vec![],
+    }
+}
+
+/// Return the operands of a terminator, including the operands of its assert message. This does
+/// not include the places that are dropped or written to.
+fn terminator_operands(kind: &TerminatorKind) -> Vec<&Operand> {
+    match kind {
+        TerminatorKind::SwitchInt { discr, .. } => vec![discr],
+        TerminatorKind::Call { func, args, .. } => Some(func).into_iter().chain(args).collect(),
+        TerminatorKind::Assert { cond, msg, .. } => {
+            let mut operands = vec![cond];
+            match msg {
+                AssertMessage::BoundsCheck { len: lhs, index: rhs }
+                | AssertMessage::Overflow(_, lhs, rhs)
+                | AssertMessage::MisalignedPointerDereference { required: lhs, found: rhs } => {
+                    operands.extend([lhs, rhs])
+                }
+                AssertMessage::OverflowNeg(operand)
+                | AssertMessage::DivisionByZero(operand)
+                | AssertMessage::RemainderByZero(operand) => operands.push(operand),
+                AssertMessage::ResumedAfterReturn(_) | AssertMessage::ResumedAfterPanic(_) => {}
+            }
+            operands
+        }
+        TerminatorKind::InlineAsm { operands, .. } => {
+            operands.iter().filter_map(|operand| operand.in_value.as_ref()).collect()
+        }
+        TerminatorKind::Goto { .. }
+        | TerminatorKind::Resume
+        | TerminatorKind::Abort
+        | TerminatorKind::Return
+        | TerminatorKind::Unreachable
+        | TerminatorKind::Drop { .. }
+        | TerminatorKind::GeneratorDrop => vec![],
+    }
+}
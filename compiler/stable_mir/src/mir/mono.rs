@@ -0,0 +1,142 @@
+use crate::abi::FnAbi;
+use crate::mir::Body;
+use crate::ty::{Allocation, FnDef, GenericArgs, Ty};
+use crate::{with, CrateItem, DefId, Error, ItemKind, Opaque};
+use std::io;
+
+/// An item that needs to be generated during code generation, with all its generic parameters
+/// substituted by concrete types.
+#[derive(Clone, Debug)]
+pub enum MonoItem {
+    Fn(Instance),
+    Static(StaticDef),
+    GlobalAsm(Opaque),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct StaticDef(pub DefId);
+
+impl StaticDef {
+    /// Get the type of this static.
+    pub fn ty(&self) -> Ty {
+        with(|cx| cx.def_ty(self.0))
+    }
+
+    /// Evaluate the initial value of this static.
+    pub fn eval_initializer(&self) -> Result<Allocation, Error> {
+        with(|cx| cx.eval_static_initializer(*self))
+    }
+}
+
+impl TryFrom<CrateItem> for StaticDef {
+    type Error = Error;
+
+    fn try_from(value: CrateItem) -> Result<Self, Self::Error> {
+        if value.item_kind() == ItemKind::Static {
+            Ok(StaticDef(value.0))
+        } else {
+            Err(Error::new(format!("Expected a static item, but found: {value:?}")))
+        }
+    }
+}
+
+/// A function definition together with the concrete generic arguments it was instantiated with.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Instance {
+    /// The type of instance.
+    pub kind: InstanceKind,
+    /// An ID used to get the instance definition from the compiler.
+    /// Do not use this field directly.
+    pub def: InstanceDef,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum InstanceKind {
+    /// A user defined item.
+    Item,
+    /// A compiler intrinsic function.
+    Intrinsic,
+    /// A virtual function call through a vtable, where `idx` is the index of the method in the
+    /// vtable.
+    Virtual { idx: usize },
+    /// The glue that drops a value of a given type.
+    DropGlue,
+    /// Any other compiler generated shim, such as vtable shims, reify shims, `Clone` shims and
+    /// closure adapters.
+    Shim,
+}
+
+impl Instance {
+    /// Build the instance of a non-generic item.
+    /// Return `None` if the item requires generic arguments to be instantiated.
+    pub fn mono(item: &CrateItem) -> Option<Instance> {
+        with(|cx| cx.mono_instance(item.0))
+    }
+
+    /// Resolve a function definition and its generic arguments to the instance that is invoked
+    /// at runtime. Return `None` if the arguments are too generic to resolve the call.
+    pub fn resolve(def: FnDef, args: &GenericArgs) -> Option<Instance> {
+        with(|cx| cx.resolve_instance(def, args))
+    }
+
+    /// Resolve the drop glue for the given type.
+    pub fn resolve_drop_in_place(ty: Ty) -> Instance {
+        with(|cx| cx.resolve_drop_in_place(ty))
+    }
+
+    /// Get the monomorphized body of this instance, if it has one.
+    /// Intrinsics, virtual calls and foreign items do not have a body.
+    pub fn body(&self) -> Option<Body> {
+        with(|cx| cx.instance_body(self.def))
+    }
+
+    /// Get the type of this instance, with all generic arguments substituted.
+    pub fn ty(&self) -> Ty {
+        with(|cx| cx.instance_ty(self.def))
+    }
+
+    /// Get the generic arguments this instance was instantiated with.
+    pub fn args(&self) -> GenericArgs {
+        with(|cx| cx.instance_args(self.def))
+    }
+
+    /// Get the definition this instance was generated from.
+    pub fn def_id(&self) -> DefId {
+        with(|cx| cx.instance_def_id(self.def))
+    }
+
+    /// A human readable name of this instance, including its generic arguments.
+    pub fn name(&self) -> String {
+        with(|cx| cx.instance_name(self.def))
+    }
+
+    /// Compute how arguments are passed to, and values returned from, this instance.
+    pub fn fn_abi(&self) -> Result<FnAbi, Error> {
+        with(|cx| cx.instance_abi(self.def))
+    }
+
+    /// Write the monomorphized MIR of this instance in the same textual format as
+    /// `-Zunpretty=mir`. Instances without a body are skipped.
+    pub fn emit_mir<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        match self.body() {
+            Some(body) => body.dump(w, &self.name()),
+            None => Ok(()),
+        }
+    }
+
+    /// The symbol name of this instance.
+    pub fn mangled_name(&self) -> String {
+        with(|cx| cx.instance_mangled_name(self.def))
+    }
+
+    /// Collect every mono item that is transitively used by this instance, including the
+    /// instance itself, the same way the compiler collects items for code generation.
+    ///
+    /// The order of the returned items is unspecified.
+    pub fn reachable_items(&self) -> Vec<MonoItem> {
+        with(|cx| cx.reachable_mono_items(*self))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct InstanceDef(pub usize);
@@ -0,0 +1,405 @@
+//! Textual representation of stable MIR bodies.
+//!
+//! The output follows the format of `-Zunpretty=mir` as closely as the stable representation
+//! allows. Source scopes and debug information are not part of a stable [`Body`], so they are
+//! never printed, and aggregates are always printed using the tuple constructor syntax.
+
+use std::fmt::{self, Write as _};
+use std::io;
+
+use crate::mir::{
+    AggregateKind, AssertMessage, BasicBlock, BinOp, Body, BorrowKind, Constant, GeneratorKind,
+    Local, Mutability, NonDivergingIntrinsic, NullOp, Operand, Place, ProjectionElem, RetagKind,
+    Rvalue, Statement, StatementKind, Terminator, TerminatorKind, UnwindAction,
+};
+use crate::ty::{Const, RigidTy, TyKind};
+use crate::with;
+
+const INDENT: &str = "    ";
+
+pub(crate) fn function_body<W: io::Write>(w: &mut W, body: &Body, name: &str) -> io::Result<()> {
+    let args = body
+        .arg_locals()
+        .iter()
+        .enumerate()
+        .map(|(index, decl)| format!("{}: {}", local(index + 1), decl.ty))
+        .collect::<Vec<_>>()
+        .join(", ");
+    writeln!(w, "fn {name}({args}) -> {} {{", body.ret_local().ty)?;
+
+    let mut has_locals = false;
+    for (index, decl) in body.locals.iter().enumerate() {
+        if index > 0 && index <= body.arg_count {
+            continue;
+        }
+        let mutability = match decl.mutability {
+            Mutability::Mut => "mut ",
+            Mutability::Not => "",
+        };
+        writeln!(w, "{INDENT}let {mutability}{}: {};", local(index), decl.ty)?;
+        has_locals = true;
+    }
+    if has_locals {
+        writeln!(w)?;
+    }
+
+    for (index, block) in body.blocks.iter().enumerate() {
+        if index > 0 {
+            writeln!(w)?;
+        }
+        basic_block(w, index, block)?;
+    }
+    writeln!(w, "}}")
+}
+
+fn basic_block<W: io::Write>(w: &mut W, index: usize, block: &BasicBlock) -> io::Result<()> {
+    writeln!(w, "{INDENT}bb{index}: {{")?;
+    for statement in &block.statements {
+        writeln!(w, "{INDENT}{INDENT}{};", pretty_statement(statement))?;
+    }
+    writeln!(w, "{INDENT}{INDENT}{};", pretty_terminator(&block.terminator))?;
+    writeln!(w, "{INDENT}}}")
+}
+
+fn pretty_statement(statement: &Statement) -> String {
+    match &statement.kind {
+        StatementKind::Assign(place, rvalue) => {
+            format!("{} = {}", pretty_place(place), pretty_rvalue(rvalue))
+        }
+        StatementKind::FakeRead(cause, place) => {
+            format!("FakeRead({cause:?}, {})", pretty_place(place))
+        }
+        StatementKind::SetDiscriminant { place, variant_index } => {
+            format!("discriminant({}) = {variant_index}", pretty_place(place))
+        }
+        StatementKind::Deinit(place) => format!("Deinit({})", pretty_place(place)),
+        StatementKind::StorageLive(index) => format!("StorageLive({})", local(*index)),
+        StatementKind::StorageDead(index) => format!("StorageDead({})", local(*index)),
+        StatementKind::Retag(kind, place) => {
+            let kind = match kind {
+                RetagKind::FnEntry => "[fn entry] ",
+                RetagKind::TwoPhase => "[2phase] ",
+                RetagKind::Raw => "[raw] ",
+                RetagKind::Default => "",
+            };
+            format!("Retag({kind}{})", pretty_place(place))
+        }
+        StatementKind::PlaceMention(place) => format!("PlaceMention({})", pretty_place(place)),
+        StatementKind::AscribeUserType { place, projections, variance } => {
+            format!("AscribeUserType({}, {variance:?}, {projections:?})", pretty_place(place))
+        }
+        StatementKind::Coverage(coverage) => format!("Coverage::{coverage}"),
+        StatementKind::Intrinsic(NonDivergingIntrinsic::Assume(op)) => {
+            format!("assume({})", pretty_operand(op))
+        }
+        StatementKind::Intrinsic(NonDivergingIntrinsic::CopyNonOverlapping(copy)) => format!(
+            "copy_nonoverlapping(dst = {}, src = {}, count = {})",
+            pretty_operand(&copy.dst),
+            pretty_operand(&copy.src),
+            pretty_operand(&copy.count)
+        ),
+        StatementKind::ConstEvalCounter => "ConstEvalCounter".to_string(),
+        StatementKind::Nop => "nop".to_string(),
+    }
+}
+
+fn pretty_terminator(terminator: &Terminator) -> String {
+    let mut out = String::new();
+    pretty_terminator_head(&mut out, &terminator.kind).unwrap();
+
+    // Pairs of successor labels and blocks, in the same order as `-Zunpretty=mir` prints them.
+    let mut successors: Vec<(String, usize)> = vec![];
+    let mut unwind = None;
+    match &terminator.kind {
+        TerminatorKind::Resume
+        | TerminatorKind::Abort
+        | TerminatorKind::Return
+        | TerminatorKind::Unreachable
+        | TerminatorKind::GeneratorDrop => {}
+        TerminatorKind::Goto { target } => successors.push((String::new(), *target)),
+        TerminatorKind::SwitchInt { targets, otherwise, .. } => {
+            successors
+                .extend(targets.iter().map(|target| (target.value.to_string(), target.target)));
+            successors.push(("otherwise".to_string(), *otherwise));
+        }
+        TerminatorKind::Drop { target, unwind: action, .. } => {
+            successors.push(("return".to_string(), *target));
+            unwind = Some(action);
+        }
+        TerminatorKind::Call { target, unwind: action, .. } => {
+            successors.extend(target.map(|target| ("return".to_string(), target)));
+            unwind = Some(action);
+        }
+        TerminatorKind::Assert { target, unwind: action, .. } => {
+            successors.push(("success".to_string(), *target));
+            unwind = Some(action);
+        }
+        TerminatorKind::InlineAsm { destination, unwind: action, .. } => {
+            successors.extend(destination.map(|target| ("return".to_string(), target)));
+            unwind = Some(action);
+        }
+    }
+    let unwind = unwind.and_then(|action| match action {
+        UnwindAction::Continue => Some("unwind continue".to_string()),
+        UnwindAction::Unreachable => Some("unwind unreachable".to_string()),
+        UnwindAction::Terminate => Some("unwind terminate".to_string()),
+        UnwindAction::Cleanup(target) => {
+            successors.push(("unwind".to_string(), *target));
+            None
+        }
+    });
+
+    match (successors.as_slice(), unwind) {
+        ([], None) => {}
+        ([], Some(unwind)) => write!(out, " -> {unwind}").unwrap(),
+        ([(_, target)], None) => write!(out, " -> bb{target}").unwrap(),
+        (successors, unwind) => {
+            let mut labels: Vec<String> =
+                successors.iter().map(|(label, target)| format!("{label}: bb{target}")).collect();
+            labels.extend(unwind);
+            write!(out, " -> [{}]", labels.join(", ")).unwrap();
+        }
+    }
+    out
+}
+
+fn pretty_terminator_head<W: fmt::Write>(out: &mut W, kind: &TerminatorKind) -> fmt::Result {
+    match kind {
+        TerminatorKind::Goto { .. } => write!(out, "goto"),
+        TerminatorKind::SwitchInt { discr, .. } => {
+            write!(out, "switchInt({})", pretty_operand(discr))
+        }
+        TerminatorKind::Resume => write!(out, "resume"),
+        TerminatorKind::Abort => write!(out, "abort"),
+        TerminatorKind::Return => write!(out, "return"),
+        TerminatorKind::Unreachable => write!(out, "unreachable"),
+        TerminatorKind::GeneratorDrop => write!(out, "generator_drop"),
+        TerminatorKind::Drop { place, .. } => write!(out, "drop({})", pretty_place(place)),
+        TerminatorKind::Call { func, args, destination, .. } => {
+            let args = args.iter().map(pretty_operand).collect::<Vec<_>>().join(", ");
+            write!(out, "{} = {}({args})", pretty_place(destination), pretty_operand(func))
+        }
+        TerminatorKind::Assert { cond, expected, msg, .. } => {
+            let negate = if *expected { "" } else { "!" };
+            write!(out, "assert({negate}{}, {})", pretty_operand(cond), pretty_assert_message(msg))
+        }
+        TerminatorKind::InlineAsm { template, operands, options, .. } => {
+            write!(out, "asm!(\"{template}\"")?;
+            for operand in operands {
+                write!(out, ", {}", operand.raw_rpr)?;
+            }
+            write!(out, ", options({options}))")
+        }
+    }
+}
+
+fn pretty_assert_message(msg: &AssertMessage) -> String {
+    match msg {
+        AssertMessage::BoundsCheck { len, index } => format!(
+            "\"index out of bounds: the length is {{}} but the index is {{}}\", {}, {}",
+            pretty_operand(len),
+            pretty_operand(index)
+        ),
+        AssertMessage::Overflow(BinOp::Shl, _, r) => format!(
+            "\"attempt to shift left by `{{}}`, which would overflow\", {}",
+            pretty_operand(r)
+        ),
+        AssertMessage::Overflow(BinOp::Shr, _, r) => format!(
+            "\"attempt to shift right by `{{}}`, which would overflow\", {}",
+            pretty_operand(r)
+        ),
+        AssertMessage::Overflow(BinOp::Rem, l, r) => format!(
+            "\"attempt to compute the remainder of `{{}} % {{}}`, which would overflow\", {}, {}",
+            pretty_operand(l),
+            pretty_operand(r)
+        ),
+        AssertMessage::Overflow(op, l, r) => {
+            let op = match op {
+                BinOp::Add => "+",
+                BinOp::Sub => "-",
+                BinOp::Mul => "*",
+                BinOp::Div => "/",
+                op => unreachable!("`{op:?}` cannot overflow"),
+            };
+            format!(
+                "\"attempt to compute `{{}} {op} {{}}`, which would overflow\", {}, {}",
+                pretty_operand(l),
+                pretty_operand(r)
+            )
+        }
+        AssertMessage::OverflowNeg(op) => {
+            format!("\"attempt to negate `{{}}`, which would overflow\", {}", pretty_operand(op))
+        }
+        AssertMessage::DivisionByZero(op) => {
+            format!("\"attempt to divide `{{}}` by zero\", {}", pretty_operand(op))
+        }
+        AssertMessage::RemainderByZero(op) => format!(
+            "\"attempt to calculate the remainder of `{{}}` with a divisor of zero\", {}",
+            pretty_operand(op)
+        ),
+        AssertMessage::MisalignedPointerDereference { required, found } => format!(
+            "\"misaligned pointer dereference: address must be a multiple of {{}} but is {{}}\", {}, {}",
+            pretty_operand(required),
+            pretty_operand(found)
+        ),
+        AssertMessage::ResumedAfterReturn(GeneratorKind::Gen) => {
+            "\"generator resumed after completion\"".to_string()
+        }
+        AssertMessage::ResumedAfterReturn(GeneratorKind::Async(_)) => {
+            "\"`async fn` resumed after completion\"".to_string()
+        }
+        AssertMessage::ResumedAfterPanic(GeneratorKind::Gen) => {
+            "\"generator resumed after panicking\"".to_string()
+        }
+        AssertMessage::ResumedAfterPanic(GeneratorKind::Async(_)) => {
+            "\"`async fn` resumed after panicking\"".to_string()
+        }
+    }
+}
+
+fn pretty_rvalue(rvalue: &Rvalue) -> String {
+    match rvalue {
+        Rvalue::AddressOf(mutability, place) => {
+            let mutability = match mutability {
+                Mutability::Mut => "mut",
+                Mutability::Not => "const",
+            };
+            format!("&raw {mutability} {}", pretty_place(place))
+        }
+        Rvalue::Aggregate(kind, operands) => pretty_aggregate(kind, operands),
+        Rvalue::BinaryOp(op, l, r) => {
+            format!("{op:?}({}, {})", pretty_operand(l), pretty_operand(r))
+        }
+        Rvalue::Cast(kind, op, ty) => format!("{} as {ty} ({kind:?})", pretty_operand(op)),
+        Rvalue::CheckedBinaryOp(op, l, r) => {
+            format!("Checked{op:?}({}, {})", pretty_operand(l), pretty_operand(r))
+        }
+        Rvalue::CopyForDeref(place) => format!("deref_copy {}", pretty_place(place)),
+        Rvalue::Discriminant(place) => format!("discriminant({})", pretty_place(place)),
+        Rvalue::Len(place) => format!("Len({})", pretty_place(place)),
+        Rvalue::Ref(_, kind, place) => {
+            let kind = match kind {
+                BorrowKind::Shared => "",
+                BorrowKind::Shallow => "shallow ",
+                BorrowKind::Mut { .. } => "mut ",
+            };
+            format!("&{kind}{}", pretty_place(place))
+        }
+        Rvalue::Repeat(op, count) => format!("[{}; {count}]", pretty_operand(op)),
+        Rvalue::ShallowInitBox(op, ty) => format!("ShallowInitBox({}, {ty})", pretty_operand(op)),
+        Rvalue::ThreadLocalRef(item) => format!("&/*tls*/ {}", item.name()),
+        Rvalue::NullaryOp(NullOp::SizeOf, ty) => format!("SizeOf({ty})"),
+        Rvalue::NullaryOp(NullOp::AlignOf, ty) => format!("AlignOf({ty})"),
+        Rvalue::NullaryOp(NullOp::OffsetOf(fields), ty) => format!("OffsetOf({ty}, {fields:?})"),
+        Rvalue::UnaryOp(op, operand) => format!("{op:?}({})", pretty_operand(operand)),
+        Rvalue::Use(operand) => pretty_operand(operand),
+    }
+}
+
+fn pretty_aggregate(kind: &AggregateKind, operands: &[Operand]) -> String {
+    let fields: Vec<String> = operands.iter().map(pretty_operand).collect();
+    match kind {
+        AggregateKind::Array(_) => format!("[{}]", fields.join(", ")),
+        AggregateKind::Tuple if fields.len() == 1 => format!("({},)", fields[0]),
+        AggregateKind::Tuple => format!("({})", fields.join(", ")),
+        AggregateKind::Adt(def, variant, args, ..) => {
+            let name = with(|cx| cx.variant_path(*def, *variant, args));
+            if fields.is_empty() {
+                name
+            } else {
+                format!("{name}({})", fields.join(", "))
+            }
+        }
+        AggregateKind::Closure(def, _) => {
+            let span = with(|cx| {
+                let span = cx.span_of_an_item(def.0);
+                cx.print_span(span)
+            });
+            pretty_captures(&format!("{{closure@{span}}}"), &fields)
+        }
+        AggregateKind::Generator(def, ..) => {
+            let span = with(|cx| {
+                let span = cx.span_of_an_item(def.0);
+                cx.print_span(span)
+            });
+            pretty_captures(&format!("{{generator@{span}}}"), &fields)
+        }
+    }
+}
+
+fn pretty_captures(name: &str, fields: &[String]) -> String {
+    if fields.is_empty() {
+        return name.to_string();
+    }
+    let fields: Vec<String> =
+        fields.iter().enumerate().map(|(index, field)| format!("{index}: {field}")).collect();
+    format!("{name} {{ {} }}", fields.join(", "))
+}
+
+fn pretty_operand(operand: &Operand) -> String {
+    match operand {
+        Operand::Copy(place) => pretty_place(place),
+        Operand::Move(place) => format!("move {}", pretty_place(place)),
+        Operand::Constant(constant) => pretty_constant(constant),
+    }
+}
+
+fn pretty_constant(constant: &Constant) -> String {
+    pretty_const(&constant.literal)
+}
+
+fn pretty_const(cnst: &Const) -> String {
+    // Function items are printed as a path to the function, without the `const` marker.
+    if matches!(cnst.ty.kind(), TyKind::RigidTy(RigidTy::FnDef(..))) {
+        cnst.to_string()
+    } else {
+        format!("const {cnst}")
+    }
+}
+
+fn pretty_place(place: &Place) -> String {
+    let mut out = String::new();
+    for elem in place.projection.iter().rev() {
+        match elem {
+            ProjectionElem::OpaqueCast(_)
+            | ProjectionElem::Subtype(_)
+            | ProjectionElem::Downcast(_)
+            | ProjectionElem::Field(..) => out.push('('),
+            ProjectionElem::Deref => out.push_str("(*"),
+            ProjectionElem::Index(_)
+            | ProjectionElem::ConstantIndex { .. }
+            | ProjectionElem::Subslice { .. } => {}
+        }
+    }
+    out.push_str(&local(place.local));
+    for elem in &place.projection {
+        match elem {
+            ProjectionElem::OpaqueCast(ty) => write!(out, " as {ty})"),
+            ProjectionElem::Subtype(ty) => write!(out, " as subtype {ty})"),
+            ProjectionElem::Downcast(index) => write!(out, " as variant#{index})"),
+            ProjectionElem::Deref => write!(out, ")"),
+            ProjectionElem::Field(field, ty) => write!(out, ".{field}: {ty})"),
+            ProjectionElem::Index(index) => write!(out, "[{}]", local(*index)),
+            ProjectionElem::ConstantIndex { offset, min_length, from_end: false } => {
+                write!(out, "[{offset} of {min_length}]")
+            }
+            ProjectionElem::ConstantIndex { offset, min_length, from_end: true } => {
+                write!(out, "[-{offset} of {min_length}]")
+            }
+            ProjectionElem::Subslice { from, to: 0, from_end: true } => write!(out, "[{from}:]"),
+            ProjectionElem::Subslice { from: 0, to, from_end: true } => write!(out, "[:-{to}]"),
+            ProjectionElem::Subslice { from, to, from_end: true } => {
+                write!(out, "[{from}:-{to}]")
+            }
+            ProjectionElem::Subslice { from, to, from_end: false } => {
+                write!(out, "[{from}..{to}]")
+            }
+        }
+        .unwrap();
+    }
+    out
+}
+
+fn local(index: Local) -> String {
+    format!("_{index}")
+}
@@ -0,0 +1,301 @@
+//! # The Stable MIR Visitor
+//!
+//! ## Overview
+//!
+//! We currently only support a mutable visitor, [MutVisitor], which can be used to instrument or
+//! otherwise rewrite a [Body] in place. Every `visit_*` method has a default implementation that
+//! calls the matching `super_*` method, which in turn walks all the sub-components of the
+//! visited construct. When overriding a `visit_*` method, call the `super_*` method to keep
+//! visiting the components of the construct.
+//!
+//! For example, to replace all the uses of a local:
+//!
+//! ```ignore(needs-extern-crate)
+//! struct RenameLocal { from: Local, to: Local }
+//!
+//! impl MutVisitor for RenameLocal {
+//!     fn visit_local(&mut self, local: &mut Local, _location: Location) {
+//!         if *local == self.from {
+//!             *local = self.to;
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! Note that the `super_*` methods are not meant to be overridden.
+
+use crate::mir::*;
+use crate::ty::{Const, GenericArgs, Region, Ty};
+use crate::Span;
+
+/// The position of a statement or terminator inside a body.
+///
+/// The terminator of a basic block is located right after its last statement, i.e., its
+/// `statement_index` is equal to the number of statements in the block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Location {
+    pub block: BasicBlockIdx,
+    pub statement_index: usize,
+}
+
+impl Location {
+    /// The location of the first statement of the entry block.
+    pub const START: Location = Location { block: START_BLOCK, statement_index: 0 };
+}
+
+pub trait MutVisitor {
+    fn visit_body(&mut self, body: &mut Body) {
+        self.super_body(body)
+    }
+
+    fn visit_basic_block(&mut self, block: &mut BasicBlock, idx: BasicBlockIdx) {
+        self.super_basic_block(block, idx)
+    }
+
+    fn visit_local_decl(&mut self, local: Local, decl: &mut LocalDecl) {
+        self.super_local_decl(local, decl)
+    }
+
+    fn visit_statement(&mut self, stmt: &mut Statement, location: Location) {
+        self.super_statement(stmt, location)
+    }
+
+    fn visit_terminator(&mut self, term: &mut Terminator, location: Location) {
+        self.super_terminator(term, location)
+    }
+
+    fn visit_rvalue(&mut self, rvalue: &mut Rvalue, location: Location) {
+        self.super_rvalue(rvalue, location)
+    }
+
+    fn visit_operand(&mut self, operand: &mut Operand, location: Location) {
+        self.super_operand(operand, location)
+    }
+
+    fn visit_place(&mut self, place: &mut Place, location: Location) {
+        self.super_place(place, location)
+    }
+
+    fn visit_projection_elem(&mut self, elem: &mut ProjectionElem, location: Location) {
+        self.super_projection_elem(elem, location)
+    }
+
+    fn visit_local(&mut self, _local: &mut Local, _location: Location) {}
+
+    fn visit_constant(&mut self, constant: &mut Constant, location: Location) {
+        self.super_constant(constant, location)
+    }
+
+    fn visit_assert_msg(&mut self, msg: &mut AssertMessage, location: Location) {
+        self.super_assert_msg(msg, location)
+    }
+
+    fn visit_ty(&mut self, _ty: &mut Ty, _location: Location) {}
+
+    fn visit_const(&mut self, _constant: &mut Const, _location: Location) {}
+
+    fn visit_region(&mut self, _region: &mut Region, _location: Location) {}
+
+    fn visit_args(&mut self, _args: &mut GenericArgs, _location: Location) {}
+
+    fn visit_span(&mut self, _span: &mut Span) {}
+
+    fn super_body(&mut self, body: &mut Body) {
+        for (idx, block) in body.blocks.iter_mut().enumerate() {
+            self.visit_basic_block(block, idx);
+        }
+        for (local, decl) in body.locals.iter_mut().enumerate() {
+            self.visit_local_decl(local, decl);
+        }
+    }
+
+    fn super_basic_block(&mut self, block: &mut BasicBlock, idx: BasicBlockIdx) {
+        let BasicBlock { statements, terminator } = block;
+        for (statement_index, stmt) in statements.iter_mut().enumerate() {
+            self.visit_statement(stmt, Location { block: idx, statement_index });
+        }
+        let location = Location { block: idx, statement_index: statements.len() };
+        self.visit_terminator(terminator, location);
+    }
+
+    fn super_local_decl(&mut self, _local: Local, decl: &mut LocalDecl) {
+        let LocalDecl { ty, span, mutability: _ } = decl;
+        self.visit_ty(ty, Location::START);
+        self.visit_span(span);
+    }
+
+    fn super_statement(&mut self, stmt: &mut Statement, location: Location) {
+        let Statement { kind, span } = stmt;
+        self.visit_span(span);
+        match kind {
+            StatementKind::Assign(place, rvalue) => {
+                self.visit_place(place, location);
+                self.visit_rvalue(rvalue, location);
+            }
+            StatementKind::FakeRead(_, place)
+            | StatementKind::SetDiscriminant { place, .. }
+            | StatementKind::Deinit(place)
+            | StatementKind::Retag(_, place)
+            | StatementKind::PlaceMention(place)
+            | StatementKind::AscribeUserType { place, .. } => {
+                self.visit_place(place, location);
+            }
+            StatementKind::StorageLive(local) | StatementKind::StorageDead(local) => {
+                self.visit_local(local, location);
+            }
+            StatementKind::Intrinsic(NonDivergingIntrinsic::Assume(operand)) => {
+                self.visit_operand(operand, location);
+            }
+            StatementKind::Intrinsic(NonDivergingIntrinsic::CopyNonOverlapping(copy)) => {
+                let CopyNonOverlapping { src, dst, count } = copy;
+                self.visit_operand(src, location);
+                self.visit_operand(dst, location);
+                self.visit_operand(count, location);
+            }
+            StatementKind::Coverage(_) | StatementKind::ConstEvalCounter | StatementKind::Nop => {}
+        }
+    }
+
+    fn super_terminator(&mut self, term: &mut Terminator, location: Location) {
+        let Terminator { kind, span } = term;
+        self.visit_span(span);
+        match kind {
+            TerminatorKind::Goto { .. }
+            | TerminatorKind::Resume
+            | TerminatorKind::Abort
+            | TerminatorKind::Return
+            | TerminatorKind::Unreachable
+            | TerminatorKind::GeneratorDrop => {}
+            TerminatorKind::SwitchInt { discr, .. } => {
+                self.visit_operand(discr, location);
+            }
+            TerminatorKind::Drop { place, .. } => {
+                self.visit_place(place, location);
+            }
+            TerminatorKind::Call { func, args, destination, .. } => {
+                self.visit_operand(func, location);
+                for arg in args {
+                    self.visit_operand(arg, location);
+                }
+                self.visit_place(destination, location);
+            }
+            TerminatorKind::Assert { cond, msg, .. } => {
+                self.visit_operand(cond, location);
+                self.visit_assert_msg(msg, location);
+            }
+            TerminatorKind::InlineAsm { operands, .. } => {
+                for InlineAsmOperand { in_value, out_place, raw_rpr: _ } in operands {
+                    if let Some(operand) = in_value {
+                        self.visit_operand(operand, location);
+                    }
+                    if let Some(place) = out_place {
+                        self.visit_place(place, location);
+                    }
+                }
+            }
+        }
+    }
+
+    fn super_rvalue(&mut self, rvalue: &mut Rvalue, location: Location) {
+        match rvalue {
+            Rvalue::AddressOf(_, place)
+            | Rvalue::CopyForDeref(place)
+            | Rvalue::Discriminant(place)
+            | Rvalue::Len(place) => {
+                self.visit_place(place, location);
+            }
+            Rvalue::Ref(region, _, place) => {
+                self.visit_region(region, location);
+                self.visit_place(place, location);
+            }
+            Rvalue::Aggregate(kind, operands) => {
+                match kind {
+                    AggregateKind::Array(ty) => self.visit_ty(ty, location),
+                    AggregateKind::Tuple => {}
+                    AggregateKind::Adt(_, _, args, _, _)
+                    | AggregateKind::Closure(_, args)
+                    | AggregateKind::Generator(_, args, _) => self.visit_args(args, location),
+                }
+                for operand in operands {
+                    self.visit_operand(operand, location);
+                }
+            }
+            Rvalue::BinaryOp(_, lhs, rhs) | Rvalue::CheckedBinaryOp(_, lhs, rhs) => {
+                self.visit_operand(lhs, location);
+                self.visit_operand(rhs, location);
+            }
+            Rvalue::Cast(_, operand, ty) | Rvalue::ShallowInitBox(operand, ty) => {
+                self.visit_operand(operand, location);
+                self.visit_ty(ty, location);
+            }
+            Rvalue::Repeat(operand, count) => {
+                self.visit_operand(operand, location);
+                self.visit_const(count, location);
+            }
+            Rvalue::ThreadLocalRef(_) => {}
+            Rvalue::NullaryOp(_, ty) => {
+                self.visit_ty(ty, location);
+            }
+            Rvalue::UnaryOp(_, operand) | Rvalue::Use(operand) => {
+                self.visit_operand(operand, location);
+            }
+        }
+    }
+
+    fn super_operand(&mut self, operand: &mut Operand, location: Location) {
+        match operand {
+            Operand::Copy(place) | Operand::Move(place) => self.visit_place(place, location),
+            Operand::Constant(constant) => self.visit_constant(constant, location),
+        }
+    }
+
+    fn super_place(&mut self, place: &mut Place, location: Location) {
+        let Place { local, projection } = place;
+        self.visit_local(local, location);
+        for elem in projection {
+            self.visit_projection_elem(elem, location);
+        }
+    }
+
+    fn super_projection_elem(&mut self, elem: &mut ProjectionElem, location: Location) {
+        match elem {
+            ProjectionElem::Deref
+            | ProjectionElem::ConstantIndex { .. }
+            | ProjectionElem::Subslice { .. }
+            | ProjectionElem::Downcast(_) => {}
+            ProjectionElem::Field(_, ty)
+            | ProjectionElem::OpaqueCast(ty)
+            | ProjectionElem::Subtype(ty) => self.visit_ty(ty, location),
+            ProjectionElem::Index(local) => self.visit_local(local, location),
+        }
+    }
+
+    fn super_constant(&mut self, constant: &mut Constant, location: Location) {
+        let Constant { span, user_ty: _, literal } = constant;
+        self.visit_span(span);
+        self.visit_const(literal, location);
+    }
+
+    fn super_assert_msg(&mut self, msg: &mut AssertMessage, location: Location) {
+        match msg {
+            AssertMessage::BoundsCheck { len, index } => {
+                self.visit_operand(len, location);
+                self.visit_operand(index, location);
+            }
+            AssertMessage::Overflow(_, lhs, rhs) => {
+                self.visit_operand(lhs, location);
+                self.visit_operand(rhs, location);
+            }
+            AssertMessage::OverflowNeg(operand)
+            | AssertMessage::DivisionByZero(operand)
+            | AssertMessage::RemainderByZero(operand) => {
+                self.visit_operand(operand, location);
+            }
+            AssertMessage::ResumedAfterReturn(_) | AssertMessage::ResumedAfterPanic(_) => {}
+            AssertMessage::MisalignedPointerDereference { required, found } => {
+                self.visit_operand(required, location);
+                self.visit_operand(found, location);
+            }
+        }
+    }
+}
@@ -0,0 +1,153 @@
+//! Serialization of the stable IR, which allows tools to run their analyses out-of-process.
+//! This module is only available with the `serde` feature.
+//!
+//! All the types that make up the bodies of the local crate implement [Serialize] and
+//! [Deserialize], so a [CrateDump] can be read back with [CrateDump::read]. However,
+//! [Ty], [Span] and definitions are serialized as indices that are only meaningful while the
+//! compiler that created them is running. For that reason, [CrateDump] also records the kind
+//! of every type and the location of every span that is reachable from the dumped bodies.
+//!
+//! Allocation identifiers and definition identifiers are not resolved by the dump.
+
+use std::collections::HashSet;
+use std::io;
+use std::ops::ControlFlow;
+
+use serde::{Deserialize, Serialize};
+
+use crate::mir::visit::{Location, MutVisitor};
+use crate::mir::Body;
+use crate::ty::{Const, GenericArgs, Region, Span, Ty, TyKind};
+use crate::visitor::{Visitable, Visitor};
+use crate::{local_crate, with, Crate, CrateItem, ItemKind};
+
+/// The encoding used to write a [CrateDump].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DumpFormat {
+    /// Human readable JSON.
+    Json,
+    /// A compact binary encoding, using [bincode](https://docs.rs/bincode/1).
+    Binary,
+}
+
+/// The stable IR of all the local items that have a body.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct CrateDump {
+    pub krate: Crate,
+    pub items: Vec<ItemDump>,
+    /// The kind of every type used in the bodies, including the types nested in other types.
+    pub types: Vec<TyDump>,
+    /// The location of every span used in the bodies.
+    pub spans: Vec<SpanDump>,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ItemDump {
+    pub item: CrateItem,
+    pub name: String,
+    pub kind: ItemKind,
+    pub span: Span,
+    pub body: Body,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TyDump {
+    pub ty: Ty,
+    /// The type as printed by the compiler, e.g. `std::option::Option<u8>`.
+    pub name: String,
+    pub kind: TyKind,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SpanDump {
+    pub span: Span,
+    /// The span as printed in diagnostics, e.g. `src/lib.rs:10:5: 10:12`.
+    pub location: String,
+}
+
+impl CrateDump {
+    /// Collect the bodies of all the local items, together with the types and spans they use.
+    pub fn local_crate() -> CrateDump {
+        let mut collector = Collector::default();
+        let items = crate::all_local_items()
+            .into_iter()
+            .map(|item| {
+                let mut body = item.body();
+                let mut span = item.span();
+                collector.visit_body(&mut body);
+                collector.visit_span(&mut span);
+                ItemDump { name: item.name(), kind: item.item_kind(), span, body, item }
+            })
+            .collect();
+        CrateDump { krate: local_crate(), items, types: collector.types, spans: collector.spans }
+    }
+
+    /// Write this dump to `writer` using the given format.
+    pub fn write<W: io::Write>(&self, writer: W, format: DumpFormat) -> io::Result<()> {
+        match format {
+            DumpFormat::Json => serde_json::to_writer_pretty(writer, self).map_err(io::Error::from),
+            DumpFormat::Binary => bincode::serialize_into(writer, self)
+                .map_err(|err| io::Error::new(io::ErrorKind::Other, err)),
+        }
+    }
+
+    /// Read a dump that was written with the given format. This does not require a compiler to
+    /// be running.
+    pub fn read<R: io::Read>(reader: R, format: DumpFormat) -> io::Result<CrateDump> {
+        match format {
+            DumpFormat::Json => serde_json::from_reader(reader).map_err(io::Error::from),
+            DumpFormat::Binary => bincode::deserialize_from(reader)
+                .map_err(|err| io::Error::new(io::ErrorKind::Other, err)),
+        }
+    }
+}
+
+/// Collect the types and spans that are reachable from a body.
+#[derive(Default)]
+struct Collector {
+    types: Vec<TyDump>,
+    seen_types: HashSet<usize>,
+    spans: Vec<SpanDump>,
+    seen_spans: HashSet<usize>,
+}
+
+impl Visitor for Collector {
+    type Break = ();
+
+    fn visit_ty(&mut self, ty: &Ty) -> ControlFlow<Self::Break> {
+        if !self.seen_types.insert(ty.0) {
+            return ControlFlow::Continue(());
+        }
+        self.types.push(TyDump { ty: *ty, name: ty.to_string(), kind: ty.kind() });
+        ty.super_visit(self)
+    }
+}
+
+impl MutVisitor for Collector {
+    fn visit_ty(&mut self, ty: &mut Ty, _location: Location) {
+        let _ = ty.visit(self);
+    }
+
+    fn visit_const(&mut self, constant: &mut Const, _location: Location) {
+        let _ = constant.visit(self);
+    }
+
+    fn visit_region(&mut self, region: &mut Region, _location: Location) {
+        let _ = region.visit(self);
+    }
+
+    fn visit_args(&mut self, args: &mut GenericArgs, _location: Location) {
+        let _ = args.visit(self);
+    }
+
+    fn visit_span(&mut self, span: &mut Span) {
+        if self.seen_spans.insert(span.0) {
+            let location = with(|cx| cx.print_span(*span));
+            self.spans.push(SpanDump { span: *span, location });
+        }
+    }
+}
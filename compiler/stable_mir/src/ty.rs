@@ -3,10 +3,15 @@
     mir::{Body, Mutability},
     with, AllocId, DefId, Symbol,
 };
-use crate::Opaque;
-use std::fmt::{self, Debug, Formatter};
+use crate::abi::LayoutShape;
+use crate::mir::alloc::Value;
+use crate::{CrateItem, Error, ItemKind, Opaque};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+use std::fmt::{self, Debug, Display, Formatter};
 
 #[derive(Copy, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Ty(pub usize);
 
 impl Debug for Ty {
@@ -15,10 +20,23 @@ fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
     }
 }
 
+/// Prints the type the same way the compiler prints it in MIR dumps, e.g. `std::option::Option<u8>`.
+impl Display for Ty {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", with(|cx| cx.ty_pretty(*self)))
+    }
+}
+
 impl Ty {
     pub fn kind(&self) -> TyKind {
         with(|context| context.ty_kind(*self))
     }
+
+    /// Compute the layout of this type for the current target.
+    /// Types that are too generic, or too big, do not have a layout.
+    pub fn layout(&self) -> Result<LayoutShape, Error> {
+        with(|context| context.ty_layout(*self))
+    }
 }
 
 impl From<TyKind> for Ty {
@@ -28,19 +46,44 @@ fn from(value: TyKind) -> Self {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Const {
     pub literal: ConstantKind,
     pub ty: Ty,
 }
 
+/// Prints the value of the constant, e.g. `10_u32` or `"hello"`.
+impl Display for Const {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", with(|cx| cx.const_literal(self)))
+    }
+}
+
+impl Const {
+    /// Evaluate this constant to the allocation that holds its value.
+    pub fn eval(&self) -> Result<Allocation, Error> {
+        match &self.literal {
+            ConstantKind::Allocated(alloc) => Ok(alloc.clone()),
+            ConstantKind::Unevaluated(_) | ConstantKind::Param(_) => with(|cx| cx.eval_const(self)),
+        }
+    }
+
+    /// Evaluate this constant and decode its value according to its type.
+    pub fn decode(&self) -> Result<Value, Error> {
+        self.eval()?.decode(self.ty)
+    }
+}
+
 type Ident = Opaque;
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Region {
     pub kind: RegionKind,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum RegionKind {
     ReEarlyBound(EarlyBoundRegion),
     ReLateBound(DebruijnIndex, BoundRegion),
@@ -52,6 +95,7 @@ pub enum RegionKind {
 pub(crate) type DebruijnIndex = u32;
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct EarlyBoundRegion {
     pub def_id: RegionDef,
     pub index: u32,
@@ -61,6 +105,7 @@ pub struct EarlyBoundRegion {
 pub(crate) type BoundVar = u32;
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct BoundRegion {
     pub var: BoundVar,
     pub kind: BoundRegionKind,
@@ -69,12 +114,14 @@ pub struct BoundRegion {
 pub(crate) type UniverseIndex = u32;
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Placeholder<T> {
     pub universe: UniverseIndex,
     pub bound: T,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Span(pub usize);
 
 impl Debug for Span {
@@ -86,7 +133,63 @@ fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
     }
 }
 
+impl Span {
+    /// Return the name of the file that contains this span, as displayed in diagnostics.
+    pub fn get_filename(&self) -> Filename {
+        with(|cx| cx.get_filename(*self))
+    }
+
+    /// Return the lines and columns where this span starts and ends.
+    pub fn get_lines(&self) -> LineInfo {
+        with(|cx| cx.get_lines(*self))
+    }
+
+    /// Return the source code covered by this span, if it is available.
+    pub fn snippet(&self) -> Option<String> {
+        with(|cx| cx.span_snippet(*self))
+    }
+
+    /// Return the chain of macro expansions and desugarings that produced this span, starting
+    /// with the innermost one. This is empty if the span comes directly from the source code.
+    pub fn macro_backtrace(&self) -> Vec<MacroExpansion> {
+        with(|cx| cx.macro_backtrace(*self))
+    }
+
+    /// Prints the span the same way it is printed in diagnostics, e.g. `src/lib.rs:10:5: 10:12`.
+    pub fn diagnostic(&self) -> String {
+        with(|cx| cx.print_span(*self))
+    }
+}
+
+pub type Filename = String;
+
+/// The location of a span in its source file. Lines and columns start at 1.
+///
+/// All the fields are 0 for spans that do not point to any source code.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct LineInfo {
+    pub start_line: usize,
+    pub start_col: usize,
+    pub end_line: usize,
+    pub end_col: usize,
+}
+
+/// A step of the macro backtrace of a span.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct MacroExpansion {
+    /// The expansion as described in diagnostics, e.g. `vec!` or `#[derive(Clone)]`.
+    /// Compiler desugarings are described as `desugaring of ...`.
+    pub name: String,
+    /// Where the macro was invoked.
+    pub call_site: Span,
+    /// Where the macro was defined.
+    pub def_site: Span,
+}
+
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum TyKind {
     RigidTy(RigidTy),
     Alias(AliasKind, AliasTy),
@@ -95,6 +198,7 @@ pub enum TyKind {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum RigidTy {
     Bool,
     Char,
@@ -118,6 +222,7 @@ pub enum RigidTy {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum IntTy {
     Isize,
     I8,
@@ -128,6 +233,7 @@ pub enum IntTy {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum UintTy {
     Usize,
     U8,
@@ -138,63 +244,114 @@ pub enum UintTy {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum FloatTy {
     F32,
     F64,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Movability {
     Static,
     Movable,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ForeignDef(pub DefId);
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct FnDef(pub DefId);
 
 impl FnDef {
     pub fn body(&self) -> Body {
         with(|ctx| ctx.mir_body(self.0))
     }
+
+    /// Get the trait that declares this function, if this is a trait method or the
+    /// implementation of one.
+    ///
+    /// Use [Instance::resolve](crate::mir::mono::Instance::resolve) to find which implementation
+    /// a call to a trait method dispatches to.
+    pub fn trait_of(&self) -> Option<TraitDef> {
+        with(|cx| cx.trait_of_item(self.0))
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ClosureDef(pub DefId);
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct GeneratorDef(pub DefId);
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ParamDef(pub DefId);
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct BrNamedDef(pub DefId);
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct AdtDef(pub DefId);
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct AliasDef(pub DefId);
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct TraitDef(pub DefId);
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct GenericDef(pub DefId);
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ConstDef(pub DefId);
 
+impl ConstDef {
+    /// Get the type of this constant item.
+    pub fn ty(&self) -> Ty {
+        with(|cx| cx.def_ty(self.0))
+    }
+
+    /// Evaluate the value of this constant item.
+    /// Fail if the constant depends on generic parameters.
+    pub fn eval(&self) -> Result<Allocation, Error> {
+        let uneval = UnevaluatedConst { def: *self, args: GenericArgs(vec![]), promoted: None };
+        Const { literal: ConstantKind::Unevaluated(uneval), ty: self.ty() }.eval()
+    }
+}
+
+impl TryFrom<CrateItem> for ConstDef {
+    type Error = Error;
+
+    fn try_from(value: CrateItem) -> Result<Self, Self::Error> {
+        if value.item_kind() == ItemKind::Const {
+            Ok(ConstDef(value.0))
+        } else {
+            Err(Error::new(format!("Expected a constant item, but found: {value:?}")))
+        }
+    }
+}
+
 #[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ImplDef(pub DefId);
 
 #[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct RegionDef(pub DefId);
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct GenericArgs(pub Vec<GenericArgKind>);
 
 impl std::ops::Index<ParamTy> for GenericArgs {
@@ -214,6 +371,7 @@ fn index(&self, index: ParamConst) -> &Self::Output {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum GenericArgKind {
     Lifetime(Region),
     Type(Ty),
@@ -243,12 +401,14 @@ pub fn expect_const(&self) -> &Const {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum TermKind {
     Type(Ty),
     Const(Const),
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum AliasKind {
     Projection,
     Inherent,
@@ -257,6 +417,7 @@ pub enum AliasKind {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct AliasTy {
     pub def_id: AliasDef,
     pub args: GenericArgs,
@@ -265,6 +426,7 @@ pub struct AliasTy {
 pub type PolyFnSig = Binder<FnSig>;
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct FnSig {
     pub inputs_and_output: Vec<Ty>,
     pub c_variadic: bool,
@@ -273,6 +435,7 @@ pub struct FnSig {
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum Abi {
     Rust,
     C { unwind: bool },
@@ -304,17 +467,20 @@ pub enum Abi {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Binder<T> {
     pub value: T,
     pub bound_vars: Vec<BoundVariableKind>,
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct EarlyBinder<T> {
     pub value: T,
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum BoundVariableKind {
     Ty(BoundTyKind),
     Region(BoundRegionKind),
@@ -322,12 +488,14 @@ pub enum BoundVariableKind {
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum BoundTyKind {
     Anon,
     Param(ParamDef, String),
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum BoundRegionKind {
     BrAnon,
     BrNamed(BrNamedDef, String),
@@ -335,12 +503,14 @@ pub enum BoundRegionKind {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum DynKind {
     Dyn,
     DynStar,
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ExistentialPredicate {
     Trait(ExistentialTraitRef),
     Projection(ExistentialProjection),
@@ -348,12 +518,14 @@ pub enum ExistentialPredicate {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ExistentialTraitRef {
     pub def_id: TraitDef,
     pub generic_args: GenericArgs,
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ExistentialProjection {
     pub def_id: TraitDef,
     pub generic_args: GenericArgs,
@@ -361,12 +533,14 @@ pub struct ExistentialProjection {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ParamTy {
     pub index: u32,
     pub name: String,
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct BoundTy {
     pub var: usize,
     pub kind: BoundTyKind,
@@ -376,6 +550,7 @@ pub struct BoundTy {
 pub type Size = usize;
 
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Prov(pub AllocId);
 pub type Align = u64;
 pub type Promoted = u32;
@@ -383,6 +558,7 @@ pub struct BoundTy {
 
 /// Stores the provenance information of pointers stored in memory.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ProvenanceMap {
     /// Provenance in this map applies from the given offset for an entire pointer-size worth of
     /// bytes. Two entries in this map are always at least a pointer size apart.
@@ -390,6 +566,7 @@ pub struct ProvenanceMap {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Allocation {
     pub bytes: Bytes,
     pub provenance: ProvenanceMap,
@@ -397,7 +574,25 @@ pub struct Allocation {
     pub mutability: Mutability,
 }
 
+impl Allocation {
+    /// Get the bytes of this allocation.
+    /// Fail if any of the bytes is uninitialized.
+    pub fn raw_bytes(&self) -> Result<Vec<u8>, Error> {
+        self.bytes
+            .iter()
+            .copied()
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| Error::new("Found uninitialized bytes".to_string()))
+    }
+
+    /// Decode the contents of this allocation as a value of the given type.
+    pub fn decode(&self, ty: Ty) -> Result<Value, Error> {
+        with(|cx| cx.decode_allocation(self, ty))
+    }
+}
+
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ConstantKind {
     Allocated(Allocation),
     Unevaluated(UnevaluatedConst),
@@ -405,12 +600,14 @@ pub enum ConstantKind {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ParamConst {
     pub index: u32,
     pub name: String,
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct UnevaluatedConst {
     pub def: ConstDef,
     pub args: GenericArgs,
@@ -418,6 +615,7 @@ pub struct UnevaluatedConst {
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum TraitSpecializationKind {
     None,
     Marker,
@@ -425,6 +623,7 @@ pub enum TraitSpecializationKind {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct TraitDecl {
     pub def_id: TraitDef,
     pub unsafety: Safety,
@@ -456,12 +655,51 @@ pub fn explicit_predicates_of(&self) -> GenericPredicates {
 pub type ImplTrait = EarlyBinder<TraitRef>;
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct TraitRef {
     pub def_id: TraitDef,
     pub args: GenericArgs,
 }
 
+impl TraitRef {
+    /// Build a reference to the trait `def_id` for `self_ty`, where `args` are the remaining
+    /// generic arguments of the trait.
+    pub fn new(def_id: TraitDef, self_ty: Ty, args: &[GenericArgKind]) -> TraitRef {
+        let args = std::iter::once(GenericArgKind::Type(self_ty)).chain(args.iter().cloned());
+        TraitRef { def_id, args: GenericArgs(args.collect()) }
+    }
+
+    /// The type that implements the trait, which is always the first generic argument.
+    pub fn self_ty(&self) -> Ty {
+        let GenericArgKind::Type(self_ty) = self.args.0[0] else {
+            panic!("Self must be a type, but found: {:?}", self.args.0[0])
+        };
+        self_ty
+    }
+
+    /// Check whether the trait is known to be implemented for these generic arguments, ignoring
+    /// lifetime constraints.
+    ///
+    /// The obligation is evaluated without any where clauses in scope, so generic parameters are
+    /// not assumed to implement any trait. Return `false` if the number or the kinds of the
+    /// generic arguments do not match the ones of the trait.
+    pub fn is_implemented(&self) -> bool {
+        with(|cx| cx.trait_ref_holds(self))
+    }
+
+    /// Find the user-written impl that satisfies this trait reference.
+    ///
+    /// Return `None` if the trait is not implemented, if the implementation is ambiguous, or if
+    /// it is provided by the compiler, e.g., `Copy` for integers or `Fn` for closures. This is
+    /// also the case if the generic arguments do not match the ones of the trait, or refer to
+    /// generic parameters.
+    pub fn resolve_impl(&self) -> Option<ImplDef> {
+        with(|cx| cx.resolve_impl(self))
+    }
+}
+
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Generics {
     pub parent: Option<GenericDef>,
     pub parent_count: usize,
@@ -473,6 +711,7 @@ pub struct Generics {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum GenericParamDefKind {
     Lifetime,
     Type { has_default: bool, synthetic: bool },
@@ -480,6 +719,7 @@ pub enum GenericParamDefKind {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct GenericParamDef {
     pub name: super::Symbol,
     pub def_id: GenericDef,
@@ -494,6 +734,7 @@ pub struct GenericPredicates {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum PredicateKind {
     Clause(ClauseKind),
     ObjectSafe(TraitDef),
@@ -506,6 +747,7 @@ pub enum PredicateKind {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ClauseKind {
     Trait(TraitPredicate),
     RegionOutlives(RegionOutlivesPredicate),
@@ -517,6 +759,7 @@ pub enum ClauseKind {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ClosureKind {
     Fn,
     FnMut,
@@ -524,42 +767,49 @@ pub enum ClosureKind {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct SubtypePredicate {
     pub a: Ty,
     pub b: Ty,
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct CoercePredicate {
     pub a: Ty,
     pub b: Ty,
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum AliasRelationDirection {
     Equate,
     Subtype,
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct TraitPredicate {
     pub trait_ref: TraitRef,
     pub polarity: ImplPolarity,
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct OutlivesPredicate<A, B>(pub A, pub B);
 
 pub type RegionOutlivesPredicate = OutlivesPredicate<Region, Region>;
 pub type TypeOutlivesPredicate = OutlivesPredicate<Ty, Region>;
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct ProjectionPredicate {
     pub projection_ty: AliasTy,
     pub term: TermKind,
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub enum ImplPolarity {
     Positive,
     Negative,
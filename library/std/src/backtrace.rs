@@ -93,6 +93,8 @@
 use crate::ffi::c_void;
 use crate::fmt;
 use crate::panic::UnwindSafe;
+use crate::path::PathBuf;
+use crate::string::String;
 use crate::sync::atomic::{AtomicUsize, Ordering::Relaxed};
 use crate::sync::LazyLock;
 use crate::sys_common::backtrace::{lock, output_filename};
@@ -160,7 +162,11 @@ enum RawFrame {
     Fake,
 }
 
-struct BacktraceSymbol {
+/// A symbol of a backtrace frame, describing a function and its source location.
+///
+/// A single frame may have several symbols if functions were inlined into each other.
+#[unstable(feature = "backtrace_frames", issue = "79676")]
+pub struct BacktraceSymbol {
     name: Option<Vec<u8>>,
     filename: Option<BytesOrWide>,
     lineno: Option<u32>,
@@ -208,6 +214,7 @@ fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
     }
 }
 
+#[unstable(feature = "backtrace_frames", issue = "79676")]
 impl fmt::Debug for BacktraceSymbol {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
         // FIXME: improve formatting: https://github.com/rust-lang/rust/issues/65280
@@ -371,6 +378,10 @@ pub fn status(&self) -> BacktraceStatus {
 
 impl<'a> Backtrace {
     /// Returns an iterator over the backtrace frames.
+    ///
+    /// Capturing a backtrace only records the instruction pointers of its frames; the symbols
+    /// of all frames are resolved the first time this method is called or the backtrace is
+    /// formatted, and then reused.
     #[must_use]
     #[unstable(feature = "backtrace_frames", issue = "79676")]
     pub fn frames(&'a self) -> &'a [BacktraceFrame] {
@@ -378,6 +389,97 @@ pub fn frames(&'a self) -> &'a [BacktraceFrame] {
     }
 }
 
+impl BacktraceFrame {
+    /// Returns the instruction pointer of this frame.
+    ///
+    /// This is normally the next instruction to execute in the frame, which is usually the
+    /// instruction after the call for all but the innermost frame.
+    #[must_use]
+    #[unstable(feature = "backtrace_frames", issue = "79676")]
+    pub fn ip(&self) -> *mut c_void {
+        self.frame.ip()
+    }
+
+    /// Returns the starting address of the function this frame is executing, if it can be
+    /// determined, or the instruction pointer otherwise.
+    #[must_use]
+    #[unstable(feature = "backtrace_frames", issue = "79676")]
+    pub fn symbol_address(&self) -> *mut c_void {
+        self.frame.symbol_address()
+    }
+
+    /// Returns the symbols this frame resolved to.
+    ///
+    /// This is empty if no symbol information could be found for the frame, and may contain
+    /// several symbols if inlined functions were found at the frame's instruction pointer,
+    /// starting with the innermost one.
+    #[must_use]
+    #[unstable(feature = "backtrace_frames", issue = "79676")]
+    pub fn symbols(&self) -> &[BacktraceSymbol] {
+        &self.symbols
+    }
+}
+
+impl BacktraceSymbol {
+    /// Returns the demangled name of this symbol, without the trailing hash of Rust symbols,
+    /// as printed in backtraces.
+    #[must_use]
+    #[unstable(feature = "backtrace_frames", issue = "79676")]
+    pub fn name(&self) -> Option<String> {
+        self.name.as_ref().map(|b| format!("{:#}", backtrace_rs::SymbolName::new(b)))
+    }
+
+    /// Returns the raw, possibly mangled, name of this symbol.
+    #[must_use]
+    #[unstable(feature = "backtrace_frames", issue = "79676")]
+    pub fn raw_name(&self) -> Option<&[u8]> {
+        self.name.as_deref()
+    }
+
+    /// Returns the path of the source file containing this symbol, as recorded in the debug
+    /// information.
+    #[must_use]
+    #[unstable(feature = "backtrace_frames", issue = "79676")]
+    pub fn filename(&self) -> Option<PathBuf> {
+        self.filename.as_ref().map(BytesOrWide::to_path_buf)
+    }
+
+    /// Returns the line number of this symbol in its source file.
+    #[must_use]
+    #[unstable(feature = "backtrace_frames", issue = "79676")]
+    pub fn lineno(&self) -> Option<u32> {
+        self.lineno
+    }
+
+    /// Returns the column number of this symbol in its source file.
+    #[must_use]
+    #[unstable(feature = "backtrace_frames", issue = "79676")]
+    pub fn colno(&self) -> Option<u32> {
+        self.colno
+    }
+}
+
+impl BytesOrWide {
+    fn to_path_buf(&self) -> PathBuf {
+        match self {
+            #[cfg(unix)]
+            BytesOrWide::Bytes(b) => {
+                use crate::os::unix::ffi::OsStrExt;
+                PathBuf::from(crate::ffi::OsStr::from_bytes(b))
+            }
+            #[cfg(not(unix))]
+            BytesOrWide::Bytes(b) => PathBuf::from(String::from_utf8_lossy(b).into_owned()),
+            #[cfg(windows)]
+            BytesOrWide::Wide(w) => {
+                use crate::os::windows::ffi::OsStringExt;
+                PathBuf::from(crate::ffi::OsString::from_wide(w))
+            }
+            #[cfg(not(windows))]
+            BytesOrWide::Wide(w) => PathBuf::from(String::from_utf16_lossy(w)),
+        }
+    }
+}
+
 #[stable(feature = "backtrace", since = "1.65.0")]
 impl fmt::Display for Backtrace {
     fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -470,4 +572,12 @@ fn ip(&self) -> *mut c_void {
             RawFrame::Fake => crate::ptr::invalid_mut(1),
         }
     }
+
+    fn symbol_address(&self) -> *mut c_void {
+        match self {
+            RawFrame::Actual(frame) => frame.symbol_address(),
+            #[cfg(test)]
+            RawFrame::Fake => crate::ptr::invalid_mut(1),
+        }
+    }
 }
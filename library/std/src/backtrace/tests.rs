@@ -93,6 +93,52 @@ fn test_frames() {
     assert!(iter.all(|(f, e)| format!("{f:#?}") == *e));
 }
 
+#[test]
+fn test_frame_symbols() {
+    let backtrace = Backtrace {
+        inner: Inner::Captured(LazyLock::preinit(Capture {
+            actual_start: 1,
+            frames: generate_fake_frames(),
+        })),
+    };
+
+    let frames = backtrace.frames();
+    assert_eq!(frames.len(), 3);
+    assert!(!frames[0].ip().is_null());
+
+    let symbols = frames[2].symbols();
+    assert_eq!(symbols.len(), 2);
+    assert_eq!(symbols[0].name().as_deref(), Some("std::rt::lang_start_internal"));
+    assert_eq!(symbols[0].raw_name(), Some(&b"std::rt::lang_start_internal"[..]));
+    assert_eq!(symbols[0].filename(), Some(PathBuf::from("rust/rt.rs")));
+    assert_eq!(symbols[0].lineno(), Some(300));
+    assert_eq!(symbols[0].colno(), Some(5));
+    assert_eq!(symbols[1].colno(), None);
+
+    let symbol = &frames[1].symbols()[0];
+    assert_eq!(symbol.name().as_deref(), Some("__rust_maybe_catch_panic"));
+    assert_eq!(symbol.filename(), None);
+    assert_eq!(symbol.lineno(), None);
+}
+
+#[test]
+fn test_frame_symbols_demangled() {
+    let frame = BacktraceFrame {
+        frame: RawFrame::Fake,
+        symbols: vec![BacktraceSymbol {
+            name: Some(b"_ZN3std2rt10lang_start17h0123456789abcdefE".to_vec()),
+            filename: Some(BytesOrWide::Wide("rust/rt.rs".encode_utf16().collect())),
+            lineno: None,
+            colno: None,
+        }],
+    };
+
+    let symbol = &frame.symbols()[0];
+    assert_eq!(symbol.name().as_deref(), Some("std::rt::lang_start"));
+    assert_eq!(symbol.raw_name(), Some(&b"_ZN3std2rt10lang_start17h0123456789abcdefE"[..]));
+    assert_eq!(symbol.filename(), Some(PathBuf::from("rust/rt.rs")));
+}
+
 #[test]
 fn backtrace_unwind_safe() {
     fn assert_unwind_safe<T: UnwindSafe + RefUnwindSafe>() {}
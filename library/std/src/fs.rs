@@ -223,6 +223,47 @@ pub struct DirBuilder {
     recursive: bool,
 }
 
+/// An open directory, relative to which files and directories can be opened, created, removed
+/// and inspected.
+///
+/// Paths passed to the methods of `Dir` are resolved relative to the directory itself rather
+/// than to the current working directory, so they keep referring to entries of the same
+/// directory even if it is renamed or moved, or if an ancestor is replaced by a symlink, after
+/// it was opened. Absolute paths are resolved as usual.
+///
+/// # Platform-specific behavior
+///
+/// On Unix this holds an open file descriptor for the directory, and the operations correspond
+/// to `openat`, `mkdirat`, `unlinkat`, `renameat` and `fstatat`. On other platforms the
+/// operations are currently performed on paths joined to the path the directory was opened
+/// with, and so do not provide the guarantees described above.
+///
+/// Note that this [may change in the future][changes].
+///
+/// [changes]: io#platform-specific-behavior
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(dirfd)]
+/// use std::fs::{Dir, OpenOptions};
+/// use std::io::Write;
+///
+/// fn main() -> std::io::Result<()> {
+///     let dir = Dir::open("/srv/files")?;
+///     dir.create_dir("uploads")?;
+///     let mut file =
+///         dir.open_file("uploads/foo.txt", OpenOptions::new().write(true).create_new(true))?;
+///     file.write_all(b"Hello, world!")?;
+///     Ok(())
+/// }
+/// ```
+#[unstable(feature = "dirfd", issue = "none")]
+#[derive(Debug)]
+pub struct Dir {
+    inner: fs_imp::Dir,
+}
+
 /// Read the entire contents of a file into a bytes vector.
 ///
 /// This is a convenience function for using [`File::open`] and [`read_to_end`]
@@ -706,6 +747,193 @@ pub fn set_times(&self, times: FileTimes) -> io::Result<()> {
     pub fn set_modified(&self, time: SystemTime) -> io::Result<()> {
         self.set_times(FileTimes::new().set_modified(time))
     }
+
+    /// Acquires an exclusive advisory lock on the file, blocking until the lock can be acquired.
+    ///
+    /// While the lock is held, no other file handle to this file can acquire a shared or an
+    /// exclusive lock. The lock is released when [`unlock`] is called, or when the last handle
+    /// that refers to the same open file description (e.g. a handle created by [`try_clone`]) is
+    /// closed.
+    ///
+    /// If this file handle, or a clone of it, already holds a lock, the exact behavior is
+    /// unspecified and platform dependent, including the possibility that it deadlocks. However,
+    /// if this method returns, then an exclusive lock is held.
+    ///
+    /// The lock is advisory: it only interacts with [`lock_shared`], [`try_lock`],
+    /// [`try_lock_shared`] and [`unlock`]. Whether it prevents other processes from reading or
+    /// writing the file is platform dependent.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to the `flock` function on Unix with the `LOCK_EX`
+    /// flag, and the `LockFileEx` function on Windows with the `LOCKFILE_EXCLUSIVE_LOCK` flag.
+    /// Note that, this [may change in the future][changes].
+    ///
+    /// On Windows, the lock is mandatory: while it is held, other handles cannot read or write
+    /// the file.
+    ///
+    /// [changes]: io#platform-specific-behavior
+    /// [`lock_shared`]: File::lock_shared
+    /// [`try_lock`]: File::try_lock
+    /// [`try_lock_shared`]: File::try_lock_shared
+    /// [`unlock`]: File::unlock
+    /// [`try_clone`]: File::try_clone
+    ///
+    /// # Errors
+    ///
+    /// This function returns an error with [`io::ErrorKind::Unsupported`] on platforms that do
+    /// not support file locking.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_lock)]
+    /// use std::fs::File;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let f = File::create("foo.lock")?;
+    ///     f.lock()?;
+    ///     // Only one process at a time can get here.
+    ///     f.unlock()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "file_lock", issue = "none")]
+    #[doc(alias = "flock")]
+    #[doc(alias = "LockFileEx")]
+    pub fn lock(&self) -> io::Result<()> {
+        self.inner.lock()
+    }
+
+    /// Acquires a shared advisory lock on the file, blocking until the lock can be acquired.
+    ///
+    /// Any number of file handles can hold a shared lock at the same time, but no other file
+    /// handle can acquire an exclusive lock while it is held. See [`lock`] for the details of
+    /// how locks are released and how they interact with other handles.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to the `flock` function on Unix with the `LOCK_SH`
+    /// flag, and the `LockFileEx` function on Windows. Note that, this
+    /// [may change in the future][changes].
+    ///
+    /// [changes]: io#platform-specific-behavior
+    /// [`lock`]: File::lock
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_lock)]
+    /// use std::fs::File;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let f = File::open("foo.txt")?;
+    ///     f.lock_shared()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "file_lock", issue = "none")]
+    pub fn lock_shared(&self) -> io::Result<()> {
+        self.inner.lock_shared()
+    }
+
+    /// Tries to acquire an exclusive advisory lock on the file, without blocking.
+    ///
+    /// Returns `Ok(false)` if another file handle holds a lock on the file, and `Ok(true)` if
+    /// the lock was acquired. See [`lock`] for the details of the lock.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to the `flock` function on Unix with the `LOCK_EX`
+    /// and `LOCK_NB` flags, and the `LockFileEx` function on Windows with the
+    /// `LOCKFILE_EXCLUSIVE_LOCK` and `LOCKFILE_FAIL_IMMEDIATELY` flags. Note that, this
+    /// [may change in the future][changes].
+    ///
+    /// [changes]: io#platform-specific-behavior
+    /// [`lock`]: File::lock
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_lock)]
+    /// use std::fs::File;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let f = File::create("foo.lock")?;
+    ///     if !f.try_lock()? {
+    ///         eprintln!("waiting for another process to release the lock");
+    ///         f.lock()?;
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "file_lock", issue = "none")]
+    pub fn try_lock(&self) -> io::Result<bool> {
+        self.inner.try_lock()
+    }
+
+    /// Tries to acquire a shared advisory lock on the file, without blocking.
+    ///
+    /// Returns `Ok(false)` if another file handle holds an exclusive lock on the file, and
+    /// `Ok(true)` if the lock was acquired. See [`lock_shared`] for the details of the lock.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to the `flock` function on Unix with the `LOCK_SH`
+    /// and `LOCK_NB` flags, and the `LockFileEx` function on Windows with the
+    /// `LOCKFILE_FAIL_IMMEDIATELY` flag. Note that, this [may change in the future][changes].
+    ///
+    /// [changes]: io#platform-specific-behavior
+    /// [`lock_shared`]: File::lock_shared
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_lock)]
+    /// use std::fs::File;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let f = File::open("foo.txt")?;
+    ///     if f.try_lock_shared()? {
+    ///         println!("nobody is writing to the file");
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "file_lock", issue = "none")]
+    pub fn try_lock_shared(&self) -> io::Result<bool> {
+        self.inner.try_lock_shared()
+    }
+
+    /// Releases the advisory lock held by this file handle.
+    ///
+    /// If this handle holds no lock, it is unspecified whether this function returns an error.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This function currently corresponds to the `flock` function on Unix with the `LOCK_UN`
+    /// flag, and the `UnlockFile` function on Windows. Note that, this
+    /// [may change in the future][changes].
+    ///
+    /// [changes]: io#platform-specific-behavior
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(file_lock)]
+    /// use std::fs::File;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let f = File::create("foo.lock")?;
+    ///     f.lock()?;
+    ///     f.unlock()?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "file_lock", issue = "none")]
+    pub fn unlock(&self) -> io::Result<()> {
+        self.inner.unlock()
+    }
 }
 
 // In addition to the `impl`s here, `File` also has `impl`s for
@@ -2584,6 +2812,238 @@ fn as_inner_mut(&mut self) -> &mut fs_imp::DirBuilder {
     }
 }
 
+impl Dir {
+    /// Opens the directory at `path`.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `path` does not exist, is not a directory or
+    /// cannot be opened due to insufficient permissions.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(dirfd)]
+    /// use std::fs::Dir;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let dir = Dir::open("/srv/files")?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "dirfd", issue = "none")]
+    pub fn open<P: AsRef<Path>>(path: P) -> io::Result<Dir> {
+        fs_imp::Dir::open(path.as_ref()).map(|inner| Dir { inner })
+    }
+
+    /// Opens the directory at `path`, relative to this directory.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(dirfd)]
+    /// use std::fs::Dir;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let dir = Dir::open("/srv/files")?;
+    ///     let uploads = dir.open_dir("uploads")?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "dirfd", issue = "none")]
+    pub fn open_dir<P: AsRef<Path>>(&self, path: P) -> io::Result<Dir> {
+        self.inner.open_dir(path.as_ref()).map(|inner| Dir { inner })
+    }
+
+    /// Opens the file at `path`, relative to this directory, with the options specified by
+    /// `opts`.
+    ///
+    /// This is the equivalent of [`OpenOptions::open`] for paths relative to this directory.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(dirfd)]
+    /// use std::fs::{Dir, OpenOptions};
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let dir = Dir::open("/srv/files")?;
+    ///     let file = dir.open_file("foo.txt", OpenOptions::new().read(true))?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "dirfd", issue = "none")]
+    pub fn open_file<P: AsRef<Path>>(&self, path: P, opts: &OpenOptions) -> io::Result<File> {
+        self.inner.open_file(path.as_ref(), &opts.0).map(|inner| File { inner })
+    }
+
+    /// Returns an iterator over the entries of the directory at `path`, relative to this
+    /// directory.
+    ///
+    /// Use `"."` as `path` to read the entries of this directory itself.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// [`DirEntry::path`] joins `path` with the name of the entry, so the paths of the returned
+    /// entries are relative to this directory rather than to the current working directory.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(dirfd)]
+    /// use std::fs::Dir;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let dir = Dir::open("/srv/files")?;
+    ///     for entry in dir.read_dir(".")? {
+    ///         println!("{:?}", entry?.file_name());
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "dirfd", issue = "none")]
+    pub fn read_dir<P: AsRef<Path>>(&self, path: P) -> io::Result<ReadDir> {
+        self.inner.read_dir(path.as_ref()).map(ReadDir)
+    }
+
+    /// Creates a new, empty directory at `path`, relative to this directory.
+    ///
+    /// This is the equivalent of [`create_dir`] for paths relative to this directory.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(dirfd)]
+    /// use std::fs::Dir;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let dir = Dir::open("/srv/files")?;
+    ///     dir.create_dir("uploads")?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "dirfd", issue = "none")]
+    pub fn create_dir<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.inner.create_dir(path.as_ref())
+    }
+
+    /// Removes the file at `path`, relative to this directory.
+    ///
+    /// This is the equivalent of [`remove_file`] for paths relative to this directory.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(dirfd)]
+    /// use std::fs::Dir;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let dir = Dir::open("/srv/files")?;
+    ///     dir.remove_file("foo.txt")?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "dirfd", issue = "none")]
+    pub fn remove_file<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.inner.remove_file(path.as_ref())
+    }
+
+    /// Removes the empty directory at `path`, relative to this directory.
+    ///
+    /// This is the equivalent of [`remove_dir`] for paths relative to this directory.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(dirfd)]
+    /// use std::fs::Dir;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let dir = Dir::open("/srv/files")?;
+    ///     dir.remove_dir("uploads")?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "dirfd", issue = "none")]
+    pub fn remove_dir<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.inner.remove_dir(path.as_ref())
+    }
+
+    /// Renames the file or directory at `from`, relative to this directory, to `to`, relative
+    /// to `to_dir`, replacing the original file if `to` already exists.
+    ///
+    /// This is the equivalent of [`rename`] for paths relative to directories.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(dirfd)]
+    /// use std::fs::Dir;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let uploads = Dir::open("/srv/files/uploads")?;
+    ///     let published = Dir::open("/srv/files/published")?;
+    ///     uploads.rename("foo.txt", &published, "bar.txt")?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "dirfd", issue = "none")]
+    pub fn rename<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        from: P,
+        to_dir: &Dir,
+        to: Q,
+    ) -> io::Result<()> {
+        self.inner.rename(from.as_ref(), &to_dir.inner, to.as_ref())
+    }
+
+    /// Queries the metadata of the file or directory at `path`, relative to this directory,
+    /// following symbolic links.
+    ///
+    /// This is the equivalent of [`metadata`] for paths relative to this directory.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(dirfd)]
+    /// use std::fs::Dir;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let dir = Dir::open("/srv/files")?;
+    ///     let len = dir.metadata("foo.txt")?.len();
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "dirfd", issue = "none")]
+    pub fn metadata<P: AsRef<Path>>(&self, path: P) -> io::Result<Metadata> {
+        self.inner.metadata(path.as_ref()).map(Metadata)
+    }
+
+    /// Queries the metadata of the file or directory at `path`, relative to this directory,
+    /// without following symbolic links.
+    ///
+    /// This is the equivalent of [`symlink_metadata`] for paths relative to this directory.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(dirfd)]
+    /// use std::fs::Dir;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let dir = Dir::open("/srv/files")?;
+    ///     if dir.symlink_metadata("foo.txt")?.is_symlink() {
+    ///         dir.remove_file("foo.txt")?;
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    #[unstable(feature = "dirfd", issue = "none")]
+    pub fn symlink_metadata<P: AsRef<Path>>(&self, path: P) -> io::Result<Metadata> {
+        self.inner.symlink_metadata(path.as_ref()).map(Metadata)
+    }
+}
+
 /// Returns `Ok(true)` if the path points at an existing entity.
 ///
 /// This function will traverse symbolic links to query information about the
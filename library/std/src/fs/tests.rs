@@ -1,7 +1,7 @@
 use crate::io::prelude::*;
 
 use crate::env;
-use crate::fs::{self, File, FileTimes, OpenOptions};
+use crate::fs::{self, Dir, File, FileTimes, OpenOptions};
 use crate::io::{BorrowedBuf, ErrorKind, SeekFrom};
 use crate::mem::MaybeUninit;
 use crate::path::Path;
@@ -1707,3 +1707,191 @@ fn test_file_times() {
         assert_eq!(metadata.created().unwrap(), created);
     }
 }
+
+// Keep in sync with the platforms `File::lock` is implemented on in `sys`.
+cfg_if::cfg_if! {
+    if #[cfg(any(
+        windows,
+        target_os = "android",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "fuchsia",
+        target_os = "hurd",
+        target_os = "illumos",
+        target_os = "linux",
+        target_os = "netbsd",
+        target_os = "openbsd",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "tvos",
+        target_os = "watchos",
+    ))] {
+        #[test]
+        fn file_lock_multiple_shared() {
+            let tmpdir = tmpdir();
+            let filename = &tmpdir.join("file_lock_multiple_shared_test.txt");
+            let f1 = check!(File::create(filename));
+            let f2 = check!(OpenOptions::new().write(true).open(filename));
+
+            // Shared locks can be held by several handles at the same time.
+            check!(f1.lock_shared());
+            check!(f2.lock_shared());
+            check!(f1.unlock());
+            check!(f2.unlock());
+            assert!(check!(f1.try_lock_shared()));
+            assert!(check!(f2.try_lock_shared()));
+        }
+
+        #[test]
+        fn file_lock_blocking() {
+            let tmpdir = tmpdir();
+            let filename = &tmpdir.join("file_lock_blocking_test.txt");
+            let f1 = check!(File::create(filename));
+            let f2 = check!(OpenOptions::new().write(true).open(filename));
+
+            // A shared lock prevents exclusive locks.
+            check!(f1.lock_shared());
+            assert!(!check!(f2.try_lock()));
+            check!(f1.unlock());
+
+            // An exclusive lock prevents all other locks.
+            check!(f1.lock());
+            assert!(!check!(f2.try_lock()));
+            assert!(!check!(f2.try_lock_shared()));
+            check!(f1.unlock());
+            assert!(check!(f2.try_lock()));
+        }
+
+        #[test]
+        fn file_lock_drop() {
+            let tmpdir = tmpdir();
+            let filename = &tmpdir.join("file_lock_drop_test.txt");
+            let f1 = check!(File::create(filename));
+            let f2 = check!(OpenOptions::new().write(true).open(filename));
+
+            // Closing the file releases its lock.
+            check!(f1.lock());
+            assert!(!check!(f2.try_lock_shared()));
+            drop(f1);
+            assert!(check!(f2.try_lock()));
+        }
+    }
+}
+
+#[test]
+fn dir_relative_operations() {
+    let tmpdir = tmpdir();
+    let dir = check!(Dir::open(tmpdir.path()));
+
+    check!(dir.create_dir("sub"));
+    assert!(tmpdir.join("sub").is_dir());
+    let mut f = check!(dir.open_file("sub/foo.txt", OpenOptions::new().write(true).create(true)));
+    check!(f.write_all(b"hello"));
+    drop(f);
+
+    assert_eq!(check!(dir.metadata("sub/foo.txt")).len(), 5);
+    assert!(check!(dir.symlink_metadata("sub")).is_dir());
+    let names: Vec<_> = check!(dir.read_dir("sub")).map(|e| check!(e).file_name()).collect();
+    assert_eq!(names, ["foo.txt"]);
+
+    // Entries are looked up relative to `dir`, not to the current directory, even where the
+    // current directory has an entry of the same relative path.
+    check!(fs::create_dir_all(tmpdir.join("elsewhere/sub/foo.txt")));
+    let cwd = check!(env::current_dir());
+    check!(env::set_current_dir(tmpdir.join("elsewhere")));
+    let entry = check!(check!(dir.read_dir("sub")).next().unwrap());
+    let metadata = entry.metadata();
+    let file_type = entry.file_type();
+    check!(env::set_current_dir(cwd));
+    let metadata = check!(metadata);
+    assert!(metadata.is_file());
+    assert_eq!(metadata.len(), 5);
+    assert!(check!(file_type).is_file());
+    check!(fs::remove_dir_all(tmpdir.join("elsewhere")));
+
+    let sub = check!(dir.open_dir("sub"));
+    check!(sub.rename("foo.txt", &dir, "bar.txt"));
+    assert!(!tmpdir.join("sub/foo.txt").exists());
+    let mut f = check!(dir.open_file("bar.txt", OpenOptions::new().read(true)));
+    let mut contents = String::new();
+    check!(f.read_to_string(&mut contents));
+    assert_eq!(contents, "hello");
+
+    check!(dir.remove_file("bar.txt"));
+    check!(dir.remove_dir("sub"));
+    assert!(!tmpdir.join("bar.txt").exists());
+    assert!(!tmpdir.join("sub").exists());
+    assert_eq!(dir.metadata("bar.txt").unwrap_err().kind(), ErrorKind::NotFound);
+}
+
+#[test]
+fn dir_open_not_a_directory() {
+    let tmpdir = tmpdir();
+    check!(File::create(tmpdir.join("file")));
+    assert!(Dir::open(tmpdir.join("file")).is_err());
+    let dir = check!(Dir::open(tmpdir.path()));
+    assert!(dir.open_dir("file").is_err());
+}
+
+#[test]
+#[cfg(unix)]
+fn dir_follows_renamed_directory() {
+    let tmpdir = tmpdir();
+    check!(fs::create_dir(tmpdir.join("a")));
+    let dir = check!(Dir::open(tmpdir.join("a")));
+
+    // Operations keep referring to the opened directory after it has been renamed, even if
+    // its old path is now a symlink to somewhere else.
+    check!(fs::rename(tmpdir.join("a"), tmpdir.join("b")));
+    check!(fs::create_dir(tmpdir.join("c")));
+    check!(symlink_dir(tmpdir.join("c"), tmpdir.join("a")));
+    check!(dir.open_file("foo.txt", OpenOptions::new().write(true).create_new(true)));
+    assert!(tmpdir.join("b/foo.txt").exists());
+    assert!(!tmpdir.join("c/foo.txt").exists());
+
+    check!(dir.remove_file("foo.txt"));
+    assert!(!tmpdir.join("b/foo.txt").exists());
+}
+
+#[test]
+#[cfg(unix)]
+fn dir_symlink_metadata() {
+    let tmpdir = tmpdir();
+    check!(File::create(tmpdir.join("file")));
+    check!(symlink_file("file", tmpdir.join("link")));
+    let dir = check!(Dir::open(tmpdir.path()));
+
+    assert!(check!(dir.metadata("link")).is_file());
+    assert!(check!(dir.symlink_metadata("link")).is_symlink());
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn memfd_seals() {
+    use crate::os::linux::fs::{LinuxFileExt, MemfdOptions, Seals};
+
+    let mut file = check!(MemfdOptions::new().allow_sealing(true).create("memfd_seals"));
+    check!(file.write_all(b"hello"));
+    assert_eq!(check!(file.get_seals()), Seals::empty());
+
+    check!(file.add_seals(Seals::SHRINK | Seals::GROW | Seals::WRITE));
+    let seals = check!(file.get_seals());
+    assert!(seals.contains(Seals::WRITE | Seals::GROW));
+    assert!(!seals.contains(Seals::SEAL));
+    assert_eq!(format!("{seals:?}"), "Seals(SHRINK | GROW | WRITE)");
+    assert!(file.write_all(b"world").is_err());
+    assert!(file.set_len(0).is_err());
+
+    let mut contents = String::new();
+    check!(file.seek(SeekFrom::Start(0)));
+    check!(file.read_to_string(&mut contents));
+    assert_eq!(contents, "hello");
+
+    check!(file.add_seals(Seals::SEAL));
+    assert!(file.add_seals(Seals::FUTURE_WRITE).is_err());
+
+    // Without sealing allowed, the file starts out sealed against further seals.
+    let file = check!(MemfdOptions::new().create("memfd_seals"));
+    assert_eq!(check!(file.get_seals()), Seals::SEAL);
+    assert!(file.add_seals(Seals::WRITE).is_err());
+}
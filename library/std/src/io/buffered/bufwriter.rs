@@ -244,6 +244,42 @@ fn drop(&mut self) {
         Ok(())
     }
 
+    /// Writes out the buffered data followed by `data`, leaving the buffer empty.
+    ///
+    /// Both are passed to the underlying writer in vectored writes, so that `data`
+    /// doesn't have to be copied into the buffer first, and is usually written
+    /// together with the buffered data in a single call.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub(crate) fn flush_buf_vectored(&mut self, mut data: &[u8]) -> io::Result<()> {
+        while !self.buf.is_empty() {
+            let bufs = [IoSlice::new(&self.buf), IoSlice::new(data)];
+            self.panicked = true;
+            let r = self.inner.write_vectored(&bufs);
+            self.panicked = false;
+
+            match r {
+                Ok(0) => {
+                    return Err(io::const_io_error!(
+                        ErrorKind::WriteZero,
+                        "failed to write the buffered data",
+                    ));
+                }
+                Ok(n) => {
+                    let from_buf = n.min(self.buf.len());
+                    self.buf.drain(..from_buf);
+                    data = &data[n - from_buf..];
+                }
+                Err(ref e) if e.is_interrupted() => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        self.panicked = true;
+        let r = self.inner.write_all(data);
+        self.panicked = false;
+        r
+    }
+
     /// Buffer some data without flushing it, regardless of the size of the
     /// data. Writes as much as possible without exceeding capacity. Returns
     /// the number of bytes written.
@@ -8,7 +8,7 @@
 use crate::fmt;
 use crate::io::{self, BorrowedCursor, IoSlice, IoSliceMut};
 use crate::iter::FusedIterator;
-use crate::net::{Shutdown, SocketAddr, ToSocketAddrs};
+use crate::net::{Ipv4Addr, Ipv6Addr, Shutdown, SocketAddr, ToSocketAddrs};
 use crate::sys_common::net as net_imp;
 use crate::sys_common::{AsInner, FromInner, IntoInner};
 use crate::time::Duration;
@@ -110,6 +110,43 @@ pub struct IntoIncoming {
     listener: TcpListener,
 }
 
+/// A TCP socket that has not yet been converted to a [`TcpStream`] or [`TcpListener`].
+///
+/// [`TcpStream::connect`] and [`TcpListener::bind`] create, configure and connect or bind a
+/// socket in a single step. Some socket options however only have an effect if they are set
+/// before the socket is bound or connected, such as `SO_REUSEADDR` or the size of the buffers
+/// used to negotiate the TCP window. A `TcpSocket` allows setting these options first, and is
+/// then turned into a [`TcpStream`] by [`connect`], or into a [`TcpListener`] by [`listen`].
+///
+/// Further platform-specific options can be set through extension traits such as
+/// [`TcpSocketExt`] on Linux.
+///
+/// [`connect`]: TcpSocket::connect
+/// [`listen`]: TcpSocket::listen
+/// [`TcpSocketExt`]: ../os/linux/net/trait.TcpSocketExt.html
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(tcp_socket)]
+/// use std::net::TcpSocket;
+///
+/// fn main() -> std::io::Result<()> {
+///     let socket = TcpSocket::new_v4()?;
+///     socket.set_reuseaddr(true)?;
+///     socket.set_recv_buffer_size(1 << 20)?;
+///     socket.bind("127.0.0.1:8080".parse().unwrap())?;
+///
+///     let listener = socket.listen(1024)?;
+///     for stream in listener.incoming() {
+///         // ...
+///     }
+///     Ok(())
+/// }
+/// ```
+#[unstable(feature = "tcp_socket", issue = "none")]
+pub struct TcpSocket(net_imp::TcpSocket);
+
 impl TcpStream {
     /// Opens a TCP connection to a remote host.
     ///
@@ -1069,3 +1106,274 @@ fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         self.0.fmt(f)
     }
 }
+
+impl TcpSocket {
+    /// Creates a new IPv4 TCP socket.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(tcp_socket)]
+    /// use std::net::TcpSocket;
+    ///
+    /// let socket = TcpSocket::new_v4().expect("couldn't create socket");
+    /// ```
+    #[unstable(feature = "tcp_socket", issue = "none")]
+    pub fn new_v4() -> io::Result<TcpSocket> {
+        let addr = SocketAddr::new(Ipv4Addr::UNSPECIFIED.into(), 0);
+        net_imp::TcpSocket::new(&addr).map(TcpSocket)
+    }
+
+    /// Creates a new IPv6 TCP socket.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(tcp_socket)]
+    /// use std::net::TcpSocket;
+    ///
+    /// let socket = TcpSocket::new_v6().expect("couldn't create socket");
+    /// ```
+    #[unstable(feature = "tcp_socket", issue = "none")]
+    pub fn new_v6() -> io::Result<TcpSocket> {
+        let addr = SocketAddr::new(Ipv6Addr::UNSPECIFIED.into(), 0);
+        net_imp::TcpSocket::new(&addr).map(TcpSocket)
+    }
+
+    /// Binds this socket to the specified address.
+    ///
+    /// Binding with a port number of 0 will request that the OS assigns a port
+    /// to this socket. The port allocated can be queried via the
+    /// [`TcpSocket::local_addr`] method.
+    ///
+    /// The address must belong to the same address family the socket was
+    /// created for.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(tcp_socket)]
+    /// use std::net::TcpSocket;
+    ///
+    /// let socket = TcpSocket::new_v4().expect("couldn't create socket");
+    /// socket.bind("127.0.0.1:0".parse().unwrap()).expect("couldn't bind to address");
+    /// ```
+    #[unstable(feature = "tcp_socket", issue = "none")]
+    pub fn bind(&self, addr: SocketAddr) -> io::Result<()> {
+        self.0.bind(&addr)
+    }
+
+    /// Opens a TCP connection to a remote host, turning this socket into a
+    /// [`TcpStream`].
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(tcp_socket)]
+    /// use std::net::TcpSocket;
+    ///
+    /// let socket = TcpSocket::new_v4().expect("couldn't create socket");
+    /// socket.set_keepalive(true).expect("set_keepalive call failed");
+    /// let stream = socket.connect("127.0.0.1:8080".parse().unwrap())
+    ///                    .expect("Couldn't connect to the server...");
+    /// ```
+    #[unstable(feature = "tcp_socket", issue = "none")]
+    pub fn connect(self, addr: SocketAddr) -> io::Result<TcpStream> {
+        self.0.connect(&addr).map(TcpStream)
+    }
+
+    /// Opens a TCP connection to a remote host with a timeout, turning this
+    /// socket into a [`TcpStream`].
+    ///
+    /// See [`TcpStream::connect_timeout`] for details on how the timeout is
+    /// handled.
+    #[unstable(feature = "tcp_socket", issue = "none")]
+    pub fn connect_timeout(self, addr: SocketAddr, timeout: Duration) -> io::Result<TcpStream> {
+        self.0.connect_timeout(&addr, timeout).map(TcpStream)
+    }
+
+    /// Starts listening for incoming connections, turning this socket into a
+    /// [`TcpListener`].
+    ///
+    /// The `backlog` argument is a hint for the maximum number of pending
+    /// connections that have not been accepted yet. The operating system may
+    /// silently cap it to a lower value.
+    ///
+    /// The socket should have been bound with [`TcpSocket::bind`] first,
+    /// otherwise the operating system either picks an address or returns an
+    /// error.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(tcp_socket)]
+    /// use std::net::TcpSocket;
+    ///
+    /// let socket = TcpSocket::new_v4().expect("couldn't create socket");
+    /// socket.bind("127.0.0.1:80".parse().unwrap()).expect("couldn't bind to address");
+    /// let listener = socket.listen(1024).expect("couldn't listen on socket");
+    /// ```
+    #[unstable(feature = "tcp_socket", issue = "none")]
+    pub fn listen(self, backlog: u32) -> io::Result<TcpListener> {
+        self.0.listen(backlog).map(TcpListener)
+    }
+
+    /// Returns the local socket address this socket is bound to.
+    #[unstable(feature = "tcp_socket", issue = "none")]
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.0.socket_addr()
+    }
+
+    /// Sets the value of the `SO_REUSEADDR` option on this socket.
+    ///
+    /// On platforms with Berkeley-derived sockets, this allows binding to an
+    /// address that is still held by a previous socket in the `TIME_WAIT`
+    /// state. [`TcpListener::bind`] sets this option on these platforms.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// On Windows, this option allows binding to an address that is in active
+    /// use by another socket, which is rarely what is wanted.
+    #[unstable(feature = "tcp_socket", issue = "none")]
+    pub fn set_reuseaddr(&self, reuseaddr: bool) -> io::Result<()> {
+        self.0.set_reuseaddr(reuseaddr)
+    }
+
+    /// Gets the value of the `SO_REUSEADDR` option on this socket.
+    ///
+    /// For more information about this option, see [`TcpSocket::set_reuseaddr`].
+    #[unstable(feature = "tcp_socket", issue = "none")]
+    pub fn reuseaddr(&self) -> io::Result<bool> {
+        self.0.reuseaddr()
+    }
+
+    /// Sets the value of the `SO_KEEPALIVE` option on this socket.
+    ///
+    /// If set, keepalive probes are sent on the connection after it has been
+    /// idle for some time, to detect peers that went away. The timing of the
+    /// probes is platform-specific and can be configured on Linux through
+    /// [`TcpSocketExt`].
+    ///
+    /// [`TcpSocketExt`]: ../os/linux/net/trait.TcpSocketExt.html
+    #[unstable(feature = "tcp_socket", issue = "none")]
+    pub fn set_keepalive(&self, keepalive: bool) -> io::Result<()> {
+        self.0.set_keepalive(keepalive)
+    }
+
+    /// Gets the value of the `SO_KEEPALIVE` option on this socket.
+    ///
+    /// For more information about this option, see [`TcpSocket::set_keepalive`].
+    #[unstable(feature = "tcp_socket", issue = "none")]
+    pub fn keepalive(&self) -> io::Result<bool> {
+        self.0.keepalive()
+    }
+
+    /// Sets the value of the `SO_SNDBUF` option on this socket.
+    ///
+    /// This is the size of the buffer the operating system uses for data
+    /// written to the socket that has not been acknowledged by the peer yet.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// The operating system may adjust the value. Linux for example doubles
+    /// it to make room for bookkeeping, and caps it to a system-wide limit.
+    #[unstable(feature = "tcp_socket", issue = "none")]
+    pub fn set_send_buffer_size(&self, size: u32) -> io::Result<()> {
+        self.0.set_send_buffer_size(size)
+    }
+
+    /// Gets the value of the `SO_SNDBUF` option on this socket.
+    ///
+    /// For more information about this option, see [`TcpSocket::set_send_buffer_size`].
+    #[unstable(feature = "tcp_socket", issue = "none")]
+    pub fn send_buffer_size(&self) -> io::Result<u32> {
+        self.0.send_buffer_size()
+    }
+
+    /// Sets the value of the `SO_RCVBUF` option on this socket.
+    ///
+    /// This is the size of the buffer the operating system uses for data
+    /// received on the socket that has not been read yet. It determines the
+    /// TCP window advertised to the peer, so it must be set before the socket
+    /// is connected or starts listening to have its full effect.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// The operating system may adjust the value. Linux for example doubles
+    /// it to make room for bookkeeping, and caps it to a system-wide limit.
+    #[unstable(feature = "tcp_socket", issue = "none")]
+    pub fn set_recv_buffer_size(&self, size: u32) -> io::Result<()> {
+        self.0.set_recv_buffer_size(size)
+    }
+
+    /// Gets the value of the `SO_RCVBUF` option on this socket.
+    ///
+    /// For more information about this option, see [`TcpSocket::set_recv_buffer_size`].
+    #[unstable(feature = "tcp_socket", issue = "none")]
+    pub fn recv_buffer_size(&self) -> io::Result<u32> {
+        self.0.recv_buffer_size()
+    }
+
+    /// Sets the value of the `TCP_NODELAY` option on this socket.
+    ///
+    /// For more information about this option, see [`TcpStream::set_nodelay`].
+    #[unstable(feature = "tcp_socket", issue = "none")]
+    pub fn set_nodelay(&self, nodelay: bool) -> io::Result<()> {
+        self.0.set_nodelay(nodelay)
+    }
+
+    /// Gets the value of the `TCP_NODELAY` option on this socket.
+    ///
+    /// For more information about this option, see [`TcpStream::set_nodelay`].
+    #[unstable(feature = "tcp_socket", issue = "none")]
+    pub fn nodelay(&self) -> io::Result<bool> {
+        self.0.nodelay()
+    }
+
+    /// Sets the value of the `IPV6_V6ONLY` option on this socket.
+    ///
+    /// If this is set to `true` then the socket is restricted to sending and
+    /// receiving IPv6 packets only. In this case two IPv4 and IPv6 applications
+    /// can bind the same port at the same time.
+    ///
+    /// If this is set to `false` then the socket can be used to send and
+    /// receive packets from an IPv4-mapped IPv6 address. Unlike
+    /// [`TcpListener::set_only_v6`], this can be set before the socket is
+    /// bound, which is when it takes effect.
+    #[unstable(feature = "tcp_socket", issue = "none")]
+    pub fn set_only_v6(&self, only_v6: bool) -> io::Result<()> {
+        self.0.set_only_v6(only_v6)
+    }
+
+    /// Gets the value of the `IPV6_V6ONLY` option on this socket.
+    ///
+    /// For more information about this option, see [`TcpSocket::set_only_v6`].
+    #[unstable(feature = "tcp_socket", issue = "none")]
+    pub fn only_v6(&self) -> io::Result<bool> {
+        self.0.only_v6()
+    }
+
+    /// Gets the value of the `SO_ERROR` option on this socket.
+    ///
+    /// This will retrieve the stored error in the underlying socket, clearing
+    /// the field in the process. This can be useful for checking errors between
+    /// calls.
+    #[unstable(feature = "tcp_socket", issue = "none")]
+    pub fn take_error(&self) -> io::Result<Option<io::Error>> {
+        self.0.take_error()
+    }
+}
+
+impl AsInner<net_imp::TcpSocket> for TcpSocket {
+    #[inline]
+    fn as_inner(&self) -> &net_imp::TcpSocket {
+        &self.0
+    }
+}
+
+#[unstable(feature = "tcp_socket", issue = "none")]
+impl fmt::Debug for TcpSocket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
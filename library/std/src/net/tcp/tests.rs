@@ -914,3 +914,54 @@ fn connect_timeout_valid() {
     let addr = listener.local_addr().unwrap();
     TcpStream::connect_timeout(&addr, Duration::from_secs(2)).unwrap();
 }
+
+#[test]
+#[cfg_attr(target_env = "sgx", ignore)] // FIXME: https://github.com/fortanix/rust-sgx/issues/31
+fn socket_listen_connect() {
+    each_ip(&mut |addr| {
+        let new_socket = || if addr.is_ipv4() { TcpSocket::new_v4() } else { TcpSocket::new_v6() };
+
+        let socket = t!(new_socket());
+        t!(socket.set_reuseaddr(true));
+        t!(socket.bind(addr));
+        assert_eq!(t!(socket.local_addr()), addr);
+        let listener = t!(socket.listen(16));
+
+        let _t = thread::spawn(move || {
+            let socket = t!(new_socket());
+            t!(socket.set_nodelay(true));
+            let mut stream = t!(socket.connect(addr));
+            assert_eq!(t!(stream.peer_addr()), addr);
+            assert_eq!(t!(stream.nodelay()), true);
+            t!(stream.write(&[99]));
+        });
+
+        let mut stream = t!(listener.accept()).0;
+        let mut buf = [0];
+        t!(stream.read(&mut buf));
+        assert!(buf[0] == 99);
+    })
+}
+
+#[test]
+#[cfg_attr(target_env = "sgx", ignore)] // FIXME: https://github.com/fortanix/rust-sgx/issues/31
+fn socket_options() {
+    let socket = t!(TcpSocket::new_v4());
+
+    t!(socket.set_reuseaddr(true));
+    assert_eq!(true, t!(socket.reuseaddr()));
+    t!(socket.set_reuseaddr(false));
+    assert_eq!(false, t!(socket.reuseaddr()));
+
+    t!(socket.set_keepalive(true));
+    assert_eq!(true, t!(socket.keepalive()));
+    t!(socket.set_keepalive(false));
+    assert_eq!(false, t!(socket.keepalive()));
+
+    // The operating system is free to adjust buffer sizes, so only check that
+    // they were changed in the right direction.
+    t!(socket.set_send_buffer_size(64 * 1024));
+    assert!(t!(socket.send_buffer_size()) >= 64 * 1024);
+    t!(socket.set_recv_buffer_size(64 * 1024));
+    assert!(t!(socket.recv_buffer_size()) >= 64 * 1024);
+}
@@ -7,3 +7,9 @@
 
 #[unstable(feature = "tcp_quickack", issue = "96256")]
 pub use crate::os::net::linux_ext::tcp::TcpStreamExt;
+
+#[unstable(feature = "tcp_socket", issue = "none")]
+pub use crate::os::net::linux_ext::tcp::TcpSocketExt;
+
+#[unstable(feature = "tcp_send_file", issue = "none")]
+pub use crate::os::net::linux_ext::tcp::TcpSendFileExt;
@@ -4,8 +4,14 @@
 
 #![stable(feature = "metadata_ext", since = "1.1.0")]
 
-use crate::fs::Metadata;
-use crate::sys_common::AsInner;
+use crate::ffi::OsStr;
+use crate::fmt;
+use crate::fs::{File, Metadata};
+use crate::io;
+use crate::ops::{BitOr, BitOrAssign};
+use crate::sealed::Sealed;
+use crate::sys::common::small_c_string::run_with_cstr;
+use crate::sys_common::{AsInner, FromInner};
 
 #[allow(deprecated)]
 use crate::os::linux::raw;
@@ -395,3 +401,272 @@ fn st_blocks(&self) -> u64 {
         self.as_inner().as_inner().st_blocks as u64
     }
 }
+
+/// Options and flags which can be used to configure how an anonymous memory-backed file is
+/// created with `memfd_create`.
+///
+/// Such a file behaves like a regular file, but lives in memory and has no path in the
+/// filesystem, so it can only be shared with other processes through its file descriptor. It
+/// is freed once all references to it are dropped.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(linux_memfd)]
+/// use std::io::Write;
+/// use std::os::linux::fs::{LinuxFileExt, MemfdOptions, Seals};
+///
+/// fn main() -> std::io::Result<()> {
+///     let mut file = MemfdOptions::new().allow_sealing(true).create("buffer")?;
+///     file.write_all(b"shared contents")?;
+///     // The contents can no longer change, so the file can be handed to another process.
+///     file.add_seals(Seals::SHRINK | Seals::GROW | Seals::WRITE | Seals::SEAL)?;
+///     Ok(())
+/// }
+/// ```
+#[unstable(feature = "linux_memfd", issue = "none")]
+#[derive(Clone, Debug)]
+pub struct MemfdOptions {
+    allow_sealing: bool,
+}
+
+impl MemfdOptions {
+    /// Creates a blank set of options, with sealing disallowed.
+    #[unstable(feature = "linux_memfd", issue = "none")]
+    #[must_use]
+    pub fn new() -> MemfdOptions {
+        MemfdOptions { allow_sealing: false }
+    }
+
+    /// Sets whether seals can be added to the file with [`LinuxFileExt::add_seals`].
+    ///
+    /// If sealing is not allowed, the file is created with [`Seals::SEAL`] already set.
+    #[unstable(feature = "linux_memfd", issue = "none")]
+    pub fn allow_sealing(&mut self, allow_sealing: bool) -> &mut MemfdOptions {
+        self.allow_sealing = allow_sealing;
+        self
+    }
+
+    /// Creates an anonymous file, open for reading and writing, with the options specified by
+    /// `self`.
+    ///
+    /// The `name` is only used for debugging purposes, and shows up as the target of the
+    /// symbolic link for the file descriptor in `/proc/self/fd`. Several files can have the
+    /// same name.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `name` contains a nul byte or is longer than 249
+    /// bytes, or if the kernel does not support `memfd_create` (Linux 3.17 or newer is
+    /// required).
+    #[unstable(feature = "linux_memfd", issue = "none")]
+    pub fn create<S: AsRef<OsStr>>(&self, name: S) -> io::Result<File> {
+        run_with_cstr(name.as_ref().as_encoded_bytes(), |name| {
+            crate::sys::fs::File::memfd_create(name, self.allow_sealing)
+        })
+        .map(File::from_inner)
+    }
+}
+
+#[unstable(feature = "linux_memfd", issue = "none")]
+impl Default for MemfdOptions {
+    fn default() -> MemfdOptions {
+        MemfdOptions::new()
+    }
+}
+
+/// A set of seals restricting the operations allowed on a file created with [`MemfdOptions`].
+///
+/// Seals are combined with the `|` operator. Refer to the man page of [`memfd_create(2)`] for
+/// their precise semantics.
+///
+/// [`memfd_create(2)`]: https://man7.org/linux/man-pages/man2/memfd_create.2.html
+#[unstable(feature = "linux_memfd", issue = "none")]
+#[derive(Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Seals(i32);
+
+impl Seals {
+    /// Prevents further seals from being added.
+    #[unstable(feature = "linux_memfd", issue = "none")]
+    pub const SEAL: Seals = Seals(0x0001);
+    /// Prevents the file from being shrunk.
+    #[unstable(feature = "linux_memfd", issue = "none")]
+    pub const SHRINK: Seals = Seals(0x0002);
+    /// Prevents the file from being grown.
+    #[unstable(feature = "linux_memfd", issue = "none")]
+    pub const GROW: Seals = Seals(0x0004);
+    /// Prevents the contents of the file from being modified. Adding this seal fails if a
+    /// shared writable mapping of the file exists.
+    #[unstable(feature = "linux_memfd", issue = "none")]
+    pub const WRITE: Seals = Seals(0x0008);
+    /// Prevents the contents of the file from being modified through new writable mappings or
+    /// writes, while existing shared writable mappings keep working. Requires Linux 5.1.
+    #[unstable(feature = "linux_memfd", issue = "none")]
+    pub const FUTURE_WRITE: Seals = Seals(0x0010);
+
+    /// Returns the empty set of seals.
+    #[unstable(feature = "linux_memfd", issue = "none")]
+    #[must_use]
+    pub const fn empty() -> Seals {
+        Seals(0)
+    }
+
+    /// Returns `true` if no seals are set.
+    #[unstable(feature = "linux_memfd", issue = "none")]
+    #[must_use]
+    pub const fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Returns `true` if all the seals in `other` are also set in `self`.
+    #[unstable(feature = "linux_memfd", issue = "none")]
+    #[must_use]
+    pub const fn contains(self, other: Seals) -> bool {
+        self.0 & other.0 == other.0
+    }
+}
+
+#[unstable(feature = "linux_memfd", issue = "none")]
+impl BitOr for Seals {
+    type Output = Seals;
+
+    fn bitor(self, rhs: Seals) -> Seals {
+        Seals(self.0 | rhs.0)
+    }
+}
+
+#[unstable(feature = "linux_memfd", issue = "none")]
+impl BitOrAssign for Seals {
+    fn bitor_assign(&mut self, rhs: Seals) {
+        self.0 |= rhs.0;
+    }
+}
+
+#[unstable(feature = "linux_memfd", issue = "none")]
+impl fmt::Debug for Seals {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const NAMES: [(Seals, &str); 5] = [
+            (Seals::SEAL, "SEAL"),
+            (Seals::SHRINK, "SHRINK"),
+            (Seals::GROW, "GROW"),
+            (Seals::WRITE, "WRITE"),
+            (Seals::FUTURE_WRITE, "FUTURE_WRITE"),
+        ];
+
+        write!(f, "Seals(")?;
+        let mut rest = self.0;
+        let mut first = true;
+        for (seal, name) in NAMES {
+            if self.contains(seal) {
+                f.write_str(if first { "" } else { " | " })?;
+                f.write_str(name)?;
+                rest &= !seal.0;
+                first = false;
+            }
+        }
+        if rest != 0 {
+            write!(f, "{}{rest:#x}", if first { "" } else { " | " })?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// Linux-specific extensions to [`fs::File`].
+///
+/// This trait is sealed: it cannot be implemented outside the standard library.
+/// This is so that future additional methods are not breaking changes.
+///
+/// [`fs::File`]: crate::fs::File
+#[unstable(feature = "linux_memfd", issue = "none")]
+pub trait LinuxFileExt: Sealed {
+    /// Adds `seals` to the seals of the file.
+    ///
+    /// This only succeeds for files supporting seals, such as the ones created by
+    /// [`MemfdOptions`] with sealing allowed, and fails with a permission error once
+    /// [`Seals::SEAL`] is set.
+    #[unstable(feature = "linux_memfd", issue = "none")]
+    fn add_seals(&self, seals: Seals) -> io::Result<()>;
+
+    /// Returns the seals currently set on the file.
+    #[unstable(feature = "linux_memfd", issue = "none")]
+    fn get_seals(&self) -> io::Result<Seals>;
+}
+
+#[unstable(feature = "linux_memfd", issue = "none")]
+impl LinuxFileExt for File {
+    fn add_seals(&self, seals: Seals) -> io::Result<()> {
+        self.as_inner().add_seals(seals.0)
+    }
+
+    fn get_seals(&self) -> io::Result<Seals> {
+        self.as_inner().get_seals().map(Seals)
+    }
+}
+
+/// Linux-specific extension to [`fs::File`] for copying a range of bytes between two files.
+///
+/// This trait is sealed: it cannot be implemented outside the standard library.
+/// This is so that future additional methods are not breaking changes.
+///
+/// [`fs::File`]: crate::fs::File
+#[unstable(feature = "copy_range", issue = "none")]
+pub trait CopyRangeExt: Sealed {
+    /// Copies up to `len` bytes starting at `offset` in this file to `dest`, starting at
+    /// `dest_offset`, and returns the number of bytes copied.
+    ///
+    /// Fewer than `len` bytes are only copied if the end of this file is reached. Neither the
+    /// file cursor of this file nor the one of `dest` is used or changed, so the same file can
+    /// be used concurrently by other readers and writers.
+    ///
+    /// The data is copied with [`copy_file_range(2)`] where possible, which avoids moving it
+    /// through userspace and creates reflinks on file systems supporting them. If the files
+    /// do not support it, for example because they are on different file systems on an older
+    /// kernel, the data is copied with reads and writes instead.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `offset` or `dest_offset` is larger than
+    /// [`i64::MAX`], if this file is not open for reading or `dest` not for writing, or if an
+    /// I/O error occurs while copying. `dest` may have been partially written to when an error
+    /// is returned.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(copy_range)]
+    /// use std::fs::File;
+    /// use std::os::linux::fs::CopyRangeExt;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let archive = File::open("archive.bin")?;
+    ///     let entry = File::create("entry.bin")?;
+    ///     // Extract the 4 KiB entry stored at offset 1024.
+    ///     let copied = archive.copy_range_to(1024, &entry, 0, 4096)?;
+    ///     assert_eq!(copied, 4096);
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// [`copy_file_range(2)`]: https://man7.org/linux/man-pages/man2/copy_file_range.2.html
+    #[unstable(feature = "copy_range", issue = "none")]
+    fn copy_range_to(
+        &self,
+        offset: u64,
+        dest: &File,
+        dest_offset: u64,
+        len: u64,
+    ) -> io::Result<u64>;
+}
+
+#[unstable(feature = "copy_range", issue = "none")]
+impl CopyRangeExt for File {
+    fn copy_range_to(
+        &self,
+        offset: u64,
+        dest: &File,
+        dest_offset: u64,
+        len: u64,
+    ) -> io::Result<u64> {
+        self.as_inner().copy_range_to(offset, dest.as_inner(), dest_offset, len)
+    }
+}
@@ -10,10 +10,14 @@
 use crate::sealed::Sealed;
 #[cfg(not(doc))]
 use crate::sys::fd::FileDesc;
+#[cfg(not(doc))]
+use crate::sys::pidfd::PidFd as InnerPidFd;
 use crate::sys_common::{AsInner, AsInnerMut, FromInner, IntoInner};
 
 #[cfg(doc)]
 struct FileDesc;
+#[cfg(doc)]
+struct InnerPidFd;
 
 /// This type represents a file descriptor that refers to a process.
 ///
@@ -38,6 +42,14 @@
 ///
 /// // The file descriptor will be closed when `pidfd` is dropped.
 /// ```
+///
+/// A pidfd can also be opened for an arbitrary existing process with [`PidFd::open`].
+///
+/// Unlike a pid, a pidfd keeps referring to the same process after it has exited and been
+/// reaped, even if its pid is reused by another process. This makes it possible to signal and
+/// wait on a process with [`kill`], [`wait`] and [`try_wait`] without racing against the reuse
+/// of its pid.
+///
 /// Refer to the man page of [`pidfd_open(2)`] for further details.
 ///
 /// [`Command`]: process::Command
@@ -45,28 +57,92 @@
 /// [`Child`]: process::Child
 /// [`pidfd`]: fn@ChildExt::pidfd
 /// [`take_pidfd`]: ChildExt::take_pidfd
+/// [`kill`]: PidFd::kill
+/// [`wait`]: PidFd::wait
+/// [`try_wait`]: PidFd::try_wait
 /// [`pidfd_open(2)`]: https://man7.org/linux/man-pages/man2/pidfd_open.2.html
 #[derive(Debug)]
 pub struct PidFd {
-    inner: FileDesc,
+    inner: InnerPidFd,
+}
+
+impl PidFd {
+    /// Opens a pidfd that refers to the existing process with the given id.
+    ///
+    /// The process may already have exited and its pid may have been reused by the time this
+    /// function is called, in which case the pidfd refers to the new process. For children of
+    /// the current process, this cannot happen until the child has been waited on.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(linux_pidfd)]
+    /// use std::os::linux::process::PidFd;
+    /// use std::process::Command;
+    ///
+    /// let child = Command::new("sleep").arg("10").spawn()?;
+    /// let pidfd = PidFd::open(child.id())?;
+    /// pidfd.kill()?;
+    /// let status = pidfd.wait()?;
+    /// assert!(!status.success());
+    /// # std::io::Result::Ok(())
+    /// ```
+    ///
+    /// Refer to the man page of [`pidfd_open(2)`] for further details.
+    ///
+    /// [`pidfd_open(2)`]: https://man7.org/linux/man-pages/man2/pidfd_open.2.html
+    pub fn open(pid: u32) -> Result<PidFd> {
+        InnerPidFd::open(pid).map(|inner| PidFd { inner })
+    }
+
+    /// Forces the process to exit by sending it a `SIGKILL` signal.
+    ///
+    /// Unlike [`Child::kill`], this can never signal an unrelated process that reused the pid
+    /// of a process that has already been reaped. Instead, an error is returned.
+    ///
+    /// [`Child::kill`]: process::Child::kill
+    pub fn kill(&self) -> Result<()> {
+        self.inner.kill()
+    }
+
+    /// Waits for the process to exit completely, returning the status that it exited with.
+    ///
+    /// Only children of the current process can be waited on. Unlike [`Child::wait`], this does
+    /// not close the stdin handle of the child, and an error is returned if the child has
+    /// already been reaped, e.g. by a previous call to this function.
+    ///
+    /// [`Child::wait`]: process::Child::wait
+    pub fn wait(&self) -> Result<process::ExitStatus> {
+        self.inner.wait().map(FromInner::from_inner)
+    }
+
+    /// Attempts to collect the exit status of the process if it has already exited.
+    ///
+    /// Only children of the current process can be waited on. Unlike [`Child::try_wait`], an
+    /// error is returned if the child has already been reaped.
+    ///
+    /// [`Child::try_wait`]: process::Child::try_wait
+    pub fn try_wait(&self) -> Result<Option<process::ExitStatus>> {
+        Ok(self.inner.try_wait()?.map(FromInner::from_inner))
+    }
 }
 
 impl AsInner<FileDesc> for PidFd {
     #[inline]
     fn as_inner(&self) -> &FileDesc {
-        &self.inner
+        self.inner.as_inner()
     }
 }
 
 impl FromInner<FileDesc> for PidFd {
     fn from_inner(inner: FileDesc) -> PidFd {
-        PidFd { inner }
+        PidFd { inner: InnerPidFd::from_inner(inner) }
     }
 }
 
 impl IntoInner<FileDesc> for PidFd {
     fn into_inner(self) -> FileDesc {
-        self.inner
+        self.inner.into_inner()
     }
 }
 
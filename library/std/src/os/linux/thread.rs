@@ -0,0 +1,100 @@
+//! Linux-specific extensions to primitives in the [`std::thread`] module.
+//!
+//! [`std::thread`]: crate::thread
+
+#![unstable(feature = "thread_affinity", issue = "none")]
+
+use crate::io;
+
+/// Returns the indices of the CPUs the current thread is allowed to run on.
+///
+/// This corresponds to `sched_getaffinity`. The number of CPUs returned is an
+/// upper bound for [`available_parallelism`], which additionally takes the CPU
+/// quota of the process' cgroup into account.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(thread_affinity)]
+/// use std::os::linux::thread;
+///
+/// let cpus = thread::affinity()?;
+/// println!("running on {} CPUs: {cpus:?}", cpus.len());
+/// # Ok::<(), std::io::Error>(())
+/// ```
+///
+/// [`available_parallelism`]: crate::thread::available_parallelism
+pub fn affinity() -> io::Result<Vec<usize>> {
+    crate::sys::thread::Thread::affinity()
+}
+
+/// Restricts the current thread to running on the CPUs with the given indices.
+///
+/// This corresponds to `sched_setaffinity`. See [`Builder::affinity`] to set
+/// the affinity of a thread when spawning it.
+///
+/// # Errors
+///
+/// An index that does not fit a `cpu_set_t` results in an
+/// [`InvalidInput`] error. Other errors, such as an empty list or a list
+/// containing no CPU that is online, are reported by the kernel.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(thread_affinity)]
+/// use std::os::linux::thread;
+///
+/// let cpus = thread::affinity()?;
+/// thread::set_affinity(&cpus[..1])?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+///
+/// [`Builder::affinity`]: crate::thread::Builder::affinity
+/// [`InvalidInput`]: io::ErrorKind::InvalidInput
+pub fn set_affinity(cpus: &[usize]) -> io::Result<()> {
+    crate::sys::thread::Thread::set_affinity(cpus)
+}
+
+/// Returns the nice value of the current thread.
+///
+/// On Linux, the nice value is an attribute of each thread rather than of the
+/// whole process. It ranges from -20 (highest priority) to 19 (lowest).
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(thread_priority)]
+/// use std::os::linux::thread;
+///
+/// println!("running with a nice value of {}", thread::priority()?);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[unstable(feature = "thread_priority", issue = "none")]
+pub fn priority() -> io::Result<i32> {
+    crate::sys::thread::Thread::priority()
+}
+
+/// Sets the nice value of the current thread.
+///
+/// This corresponds to `setpriority`, applied to the current thread only.
+/// Values outside of the range -20 to 19 are clamped, and lowering the nice
+/// value usually requires the `CAP_SYS_NICE` capability. See
+/// [`Builder::priority`] to set the priority of a thread when spawning it.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(thread_priority)]
+/// use std::os::linux::thread;
+///
+/// // Make room for more important work on other threads.
+/// thread::set_priority(thread::priority()? + 5)?;
+/// # Ok::<(), std::io::Error>(())
+/// ```
+///
+/// [`Builder::priority`]: crate::thread::Builder::priority
+#[unstable(feature = "thread_priority", issue = "none")]
+pub fn set_priority(nice: i32) -> io::Result<()> {
+    crate::sys::thread::Thread::set_priority(nice)
+}
@@ -2,10 +2,12 @@
 //!
 //! [`std::net`]: crate::net
 
+use crate::fs;
 use crate::io;
 use crate::net;
 use crate::sealed::Sealed;
 use crate::sys_common::AsInner;
+use crate::time::Duration;
 
 /// Os-specific extensions for [`TcpStream`]
 ///
@@ -68,3 +70,270 @@ fn quickack(&self) -> io::Result<bool> {
         self.as_inner().as_inner().quickack()
     }
 }
+
+/// Os-specific extension for sending a file over a [`TcpStream`]
+///
+/// [`TcpStream`]: net::TcpStream
+#[unstable(feature = "tcp_send_file", issue = "none")]
+pub trait TcpSendFileExt: Sealed {
+    /// Sends up to `len` bytes of `file`, starting at `offset`, over this stream, and returns
+    /// the number of bytes sent.
+    ///
+    /// The data is transferred with [`sendfile(2)`] where possible, without moving it through
+    /// userspace. The file cursor of `file` is neither used nor changed, which makes this
+    /// suitable for serving byte ranges of a file shared between several connections.
+    ///
+    /// Fewer than `len` bytes are sent if the end of the file is reached, or if this stream is
+    /// in non-blocking mode and some data was sent before the send buffer filled up. If no data
+    /// could be sent at all in non-blocking mode, an error of kind
+    /// [`io::ErrorKind::WouldBlock`] is returned.
+    ///
+    /// Unlike [`io::copy`], this does not guarantee that the data sent reflects the contents of
+    /// the file at the time of the call: the kernel may read the pages of the file only once
+    /// they are transmitted, so concurrent modifications of the file can become visible to the
+    /// peer.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `offset` is larger than [`i64::MAX`], if `file`
+    /// is not open for reading, or if an I/O error occurs.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(tcp_send_file)]
+    /// use std::fs::File;
+    /// use std::net::TcpStream;
+    /// use std::os::linux::net::TcpSendFileExt;
+    ///
+    /// fn main() -> std::io::Result<()> {
+    ///     let file = File::open("video.mp4")?;
+    ///     let stream = TcpStream::connect("127.0.0.1:8080")?;
+    ///     // Serve the second MiB of the file.
+    ///     let sent = stream.send_file(&file, 1 << 20, 1 << 20)?;
+    ///     println!("sent {sent} bytes");
+    ///     Ok(())
+    /// }
+    /// ```
+    ///
+    /// [`sendfile(2)`]: https://man7.org/linux/man-pages/man2/sendfile.2.html
+    /// [`io::copy`]: crate::io::copy
+    #[unstable(feature = "tcp_send_file", issue = "none")]
+    fn send_file(&self, file: &fs::File, offset: u64, len: u64) -> io::Result<u64>;
+}
+
+#[unstable(feature = "tcp_send_file", issue = "none")]
+impl TcpSendFileExt for net::TcpStream {
+    fn send_file(&self, file: &fs::File, offset: u64, len: u64) -> io::Result<u64> {
+        self.as_inner().send_file(file.as_inner(), offset, len)
+    }
+}
+
+/// Os-specific extensions for [`TcpSocket`]
+///
+/// These options have to be set before the socket is turned into a
+/// [`TcpStream`] or a [`TcpListener`] to affect the resulting connection.
+///
+/// [`TcpSocket`]: net::TcpSocket
+/// [`TcpStream`]: net::TcpStream
+/// [`TcpListener`]: net::TcpListener
+#[unstable(feature = "tcp_socket", issue = "none")]
+pub trait TcpSocketExt: Sealed {
+    /// Enable or disable `SO_REUSEPORT`.
+    ///
+    /// This allows multiple sockets, for example owned by different worker
+    /// processes, to bind to the same address and port. Incoming connections
+    /// are then distributed between the listening sockets by the kernel. All
+    /// sockets must set this option before they are bound.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(tcp_socket)]
+    /// use std::net::TcpSocket;
+    /// use std::os::linux::net::TcpSocketExt;
+    ///
+    /// let socket = TcpSocket::new_v4().expect("couldn't create socket");
+    /// socket.set_reuseport(true).expect("set_reuseport call failed");
+    /// socket.bind("0.0.0.0:8080".parse().unwrap()).expect("couldn't bind to address");
+    /// let listener = socket.listen(1024).expect("couldn't listen on socket");
+    /// ```
+    #[unstable(feature = "tcp_socket", issue = "none")]
+    fn set_reuseport(&self, reuseport: bool) -> io::Result<()>;
+
+    /// Gets the value of the `SO_REUSEPORT` option on this socket.
+    ///
+    /// For more information about this option, see [`TcpSocketExt::set_reuseport`].
+    #[unstable(feature = "tcp_socket", issue = "none")]
+    fn reuseport(&self) -> io::Result<bool>;
+
+    /// Sets the value of the `TCP_KEEPIDLE` option on this socket.
+    ///
+    /// This is how long the connection has to be idle before the first
+    /// keepalive probe is sent, if keepalive is enabled with
+    /// [`TcpSocket::set_keepalive`]. The duration is rounded down to whole
+    /// seconds.
+    ///
+    /// # Errors
+    ///
+    /// An error of kind [`io::ErrorKind::InvalidInput`] is returned if
+    /// `idle` is shorter than one second.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(tcp_socket)]
+    /// use std::net::TcpSocket;
+    /// use std::os::linux::net::TcpSocketExt;
+    /// use std::time::Duration;
+    ///
+    /// let socket = TcpSocket::new_v4().expect("couldn't create socket");
+    /// socket.set_keepalive(true).expect("set_keepalive call failed");
+    /// socket.set_keepalive_idle(Duration::from_secs(60)).expect("set_keepalive_idle call failed");
+    /// socket.set_keepalive_interval(Duration::from_secs(10))
+    ///     .expect("set_keepalive_interval call failed");
+    /// socket.set_keepalive_count(5).expect("set_keepalive_count call failed");
+    /// ```
+    ///
+    /// [`TcpSocket::set_keepalive`]: net::TcpSocket::set_keepalive
+    #[unstable(feature = "tcp_socket", issue = "none")]
+    fn set_keepalive_idle(&self, idle: Duration) -> io::Result<()>;
+
+    /// Gets the value of the `TCP_KEEPIDLE` option on this socket.
+    ///
+    /// For more information about this option, see [`TcpSocketExt::set_keepalive_idle`].
+    #[unstable(feature = "tcp_socket", issue = "none")]
+    fn keepalive_idle(&self) -> io::Result<Duration>;
+
+    /// Sets the value of the `TCP_KEEPINTVL` option on this socket.
+    ///
+    /// This is the time between two keepalive probes. The duration is rounded
+    /// down to whole seconds.
+    ///
+    /// # Errors
+    ///
+    /// An error of kind [`io::ErrorKind::InvalidInput`] is returned if
+    /// `interval` is shorter than one second.
+    #[unstable(feature = "tcp_socket", issue = "none")]
+    fn set_keepalive_interval(&self, interval: Duration) -> io::Result<()>;
+
+    /// Gets the value of the `TCP_KEEPINTVL` option on this socket.
+    ///
+    /// For more information about this option, see
+    /// [`TcpSocketExt::set_keepalive_interval`].
+    #[unstable(feature = "tcp_socket", issue = "none")]
+    fn keepalive_interval(&self) -> io::Result<Duration>;
+
+    /// Sets the value of the `TCP_KEEPCNT` option on this socket.
+    ///
+    /// This is the number of unanswered keepalive probes after which the
+    /// connection is dropped.
+    #[unstable(feature = "tcp_socket", issue = "none")]
+    fn set_keepalive_count(&self, count: u32) -> io::Result<()>;
+
+    /// Gets the value of the `TCP_KEEPCNT` option on this socket.
+    ///
+    /// For more information about this option, see [`TcpSocketExt::set_keepalive_count`].
+    #[unstable(feature = "tcp_socket", issue = "none")]
+    fn keepalive_count(&self) -> io::Result<u32>;
+
+    /// Sets the value of the `TCP_USER_TIMEOUT` option on this socket.
+    ///
+    /// This is the maximum amount of time transmitted data may remain
+    /// unacknowledged before the connection is forcibly closed. If the value
+    /// is `None`, the system default is used.
+    ///
+    /// See [`man 7 tcp`](https://man7.org/linux/man-pages/man7/tcp.7.html) for
+    /// how this option interacts with keepalive.
+    ///
+    /// # Errors
+    ///
+    /// An error of kind [`io::ErrorKind::InvalidInput`] is returned if the
+    /// zero [`Duration`] is passed to this method.
+    #[unstable(feature = "tcp_socket", issue = "none")]
+    fn set_user_timeout(&self, timeout: Option<Duration>) -> io::Result<()>;
+
+    /// Gets the value of the `TCP_USER_TIMEOUT` option on this socket.
+    ///
+    /// For more information about this option, see [`TcpSocketExt::set_user_timeout`].
+    #[unstable(feature = "tcp_socket", issue = "none")]
+    fn user_timeout(&self) -> io::Result<Option<Duration>>;
+
+    /// Sets the value of the `SO_BINDTODEVICE` option on this socket.
+    ///
+    /// If an interface name such as `b"eth0"` is given, the socket only sends
+    /// and receives packets through that interface. `None` removes a previous
+    /// binding. This usually requires the `CAP_NET_RAW` capability.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(tcp_socket)]
+    /// use std::net::TcpSocket;
+    /// use std::os::linux::net::TcpSocketExt;
+    ///
+    /// let socket = TcpSocket::new_v4().expect("couldn't create socket");
+    /// socket.bind_device(Some(b"eth0")).expect("bind_device call failed");
+    /// ```
+    #[unstable(feature = "tcp_socket", issue = "none")]
+    fn bind_device(&self, interface: Option<&[u8]>) -> io::Result<()>;
+
+    /// Gets the name of the interface this socket is bound to with
+    /// [`TcpSocketExt::bind_device`], if any.
+    #[unstable(feature = "tcp_socket", issue = "none")]
+    fn device(&self) -> io::Result<Option<Vec<u8>>>;
+}
+
+#[unstable(feature = "tcp_socket", issue = "none")]
+impl Sealed for net::TcpSocket {}
+
+#[unstable(feature = "tcp_socket", issue = "none")]
+impl TcpSocketExt for net::TcpSocket {
+    fn set_reuseport(&self, reuseport: bool) -> io::Result<()> {
+        self.as_inner().socket().set_reuseport(reuseport)
+    }
+
+    fn reuseport(&self) -> io::Result<bool> {
+        self.as_inner().socket().reuseport()
+    }
+
+    fn set_keepalive_idle(&self, idle: Duration) -> io::Result<()> {
+        self.as_inner().socket().set_keepalive_idle(idle)
+    }
+
+    fn keepalive_idle(&self) -> io::Result<Duration> {
+        self.as_inner().socket().keepalive_idle()
+    }
+
+    fn set_keepalive_interval(&self, interval: Duration) -> io::Result<()> {
+        self.as_inner().socket().set_keepalive_interval(interval)
+    }
+
+    fn keepalive_interval(&self) -> io::Result<Duration> {
+        self.as_inner().socket().keepalive_interval()
+    }
+
+    fn set_keepalive_count(&self, count: u32) -> io::Result<()> {
+        self.as_inner().socket().set_keepalive_count(count)
+    }
+
+    fn keepalive_count(&self) -> io::Result<u32> {
+        self.as_inner().socket().keepalive_count()
+    }
+
+    fn set_user_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        self.as_inner().socket().set_user_timeout(timeout)
+    }
+
+    fn user_timeout(&self) -> io::Result<Option<Duration>> {
+        self.as_inner().socket().user_timeout()
+    }
+
+    fn bind_device(&self, interface: Option<&[u8]>) -> io::Result<()> {
+        self.as_inner().socket().bind_device(interface)
+    }
+
+    fn device(&self) -> io::Result<Option<Vec<u8>>> {
+        self.as_inner().socket().device()
+    }
+}
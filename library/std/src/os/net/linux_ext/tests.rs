@@ -26,3 +26,51 @@ macro_rules! t {
     t!(stream.set_quickack(false));
     assert_eq!(false, t!(stream.quickack()));
 }
+
+#[test]
+fn tcp_socket_options() {
+    use crate::{
+        net::{test::next_test_ip4, TcpSocket},
+        os::net::linux_ext::tcp::TcpSocketExt,
+        time::Duration,
+    };
+
+    macro_rules! t {
+        ($e:expr) => {
+            match $e {
+                Ok(t) => t,
+                Err(e) => panic!("received error for `{}`: {}", stringify!($e), e),
+            }
+        };
+    }
+
+    let socket = t!(TcpSocket::new_v4());
+
+    t!(socket.set_reuseport(true));
+    assert_eq!(true, t!(socket.reuseport()));
+
+    t!(socket.set_keepalive_idle(Duration::from_secs(30)));
+    assert_eq!(Duration::from_secs(30), t!(socket.keepalive_idle()));
+    t!(socket.set_keepalive_interval(Duration::from_millis(5500)));
+    assert_eq!(Duration::from_secs(5), t!(socket.keepalive_interval()));
+    t!(socket.set_keepalive_count(3));
+    assert_eq!(3, t!(socket.keepalive_count()));
+    let err = socket.set_keepalive_idle(Duration::from_millis(500)).unwrap_err();
+    assert_eq!(err.kind(), crate::io::ErrorKind::InvalidInput);
+
+    t!(socket.set_user_timeout(Some(Duration::from_secs(10))));
+    assert_eq!(Some(Duration::from_secs(10)), t!(socket.user_timeout()));
+    t!(socket.set_user_timeout(None));
+    assert_eq!(None, t!(socket.user_timeout()));
+
+    assert_eq!(None, t!(socket.device()));
+
+    // Two sockets with `SO_REUSEPORT` can listen on the same port.
+    let addr = next_test_ip4();
+    t!(socket.bind(addr));
+    let _listener = t!(socket.listen(16));
+    let other = t!(TcpSocket::new_v4());
+    t!(other.set_reuseport(true));
+    t!(other.bind(addr));
+    let _other = t!(other.listen(16));
+}
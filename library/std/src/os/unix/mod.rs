@@ -91,6 +91,18 @@ mod platform {
 pub mod net;
 pub mod process;
 pub mod raw;
+#[unstable(feature = "unix_signal", issue = "none")]
+#[cfg(all(
+    unix,
+    not(any(
+        target_os = "espidf",
+        target_os = "horizon",
+        target_os = "vita",
+        target_os = "vxworks",
+        target_os = "fuchsia",
+    ))
+))]
+pub mod signal;
 pub mod thread;
 
 #[unstable(feature = "peer_credentials_unix_socket", issue = "42839", reason = "unstable")]
@@ -350,6 +350,48 @@ fn into_raw(self) -> i32 {
     }
 }
 
+/// Unix-specific extensions to [`process::Child`].
+///
+/// This trait is sealed: it cannot be implemented outside the standard library.
+/// This is so that future additional methods are not breaking changes.
+#[unstable(feature = "unix_send_signal", issue = "none")]
+pub trait ChildExt: Sealed {
+    /// Sends the signal `signal` to the child process.
+    ///
+    /// This is equivalent to [`Child::kill`] for `SIGKILL`, and like it does nothing if the
+    /// process has already exited and been waited for.
+    ///
+    /// [`Child::kill`]: process::Child::kill
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if `signal` is not a valid signal number, or if the
+    /// current process lacks the permission to send it.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(unix_send_signal, unix_signal)]
+    /// use std::os::unix::process::ChildExt;
+    /// use std::os::unix::signal::SIGTERM;
+    /// use std::process::Command;
+    ///
+    /// let mut child = Command::new("sleep").arg("60").spawn()?;
+    /// child.send_signal(SIGTERM)?;
+    /// child.wait()?;
+    /// # std::io::Result::Ok(())
+    /// ```
+    #[unstable(feature = "unix_send_signal", issue = "none")]
+    fn send_signal(&self, signal: i32) -> io::Result<()>;
+}
+
+#[unstable(feature = "unix_send_signal", issue = "none")]
+impl ChildExt for process::Child {
+    fn send_signal(&self, signal: i32) -> io::Result<()> {
+        self.as_inner().send_signal(signal)
+    }
+}
+
 #[stable(feature = "process_extensions", since = "1.2.0")]
 impl FromRawFd for process::Stdio {
     #[inline]
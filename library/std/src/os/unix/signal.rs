@@ -0,0 +1,158 @@
+//! Unix signal handling.
+//!
+//! Signal handlers can only perform a very limited set of operations safely, which makes
+//! reacting to signals such as `SIGINT` or `SIGTERM` difficult to get right. [`Signals`]
+//! instead lets a program register interest in a set of signals and receive them later, from
+//! ordinary code, either by blocking until one arrives or by polling its file descriptor
+//! alongside other I/O.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! #![feature(unix_signal)]
+//! use std::os::unix::signal::{Signals, SIGINT, SIGTERM};
+//!
+//! fn main() -> std::io::Result<()> {
+//!     let signals = Signals::new(&[SIGINT, SIGTERM])?;
+//!     let signal = signals.recv()?;
+//!     println!("received signal {signal}, shutting down");
+//!     Ok(())
+//! }
+//! ```
+
+use crate::fmt;
+use crate::io;
+use crate::os::unix::io::{AsFd, AsRawFd, BorrowedFd, RawFd};
+use crate::sys;
+
+/// Hangup detected on the controlling terminal, or death of the controlling process.
+#[unstable(feature = "unix_signal", issue = "none")]
+pub const SIGHUP: i32 = libc::SIGHUP;
+/// Interrupt from the keyboard.
+#[unstable(feature = "unix_signal", issue = "none")]
+pub const SIGINT: i32 = libc::SIGINT;
+/// Quit from the keyboard.
+#[unstable(feature = "unix_signal", issue = "none")]
+pub const SIGQUIT: i32 = libc::SIGQUIT;
+/// Kill signal. It cannot be caught, blocked or ignored.
+#[unstable(feature = "unix_signal", issue = "none")]
+pub const SIGKILL: i32 = libc::SIGKILL;
+/// User-defined signal 1.
+#[unstable(feature = "unix_signal", issue = "none")]
+pub const SIGUSR1: i32 = libc::SIGUSR1;
+/// User-defined signal 2.
+#[unstable(feature = "unix_signal", issue = "none")]
+pub const SIGUSR2: i32 = libc::SIGUSR2;
+/// Broken pipe: write to a pipe with no readers.
+#[unstable(feature = "unix_signal", issue = "none")]
+pub const SIGPIPE: i32 = libc::SIGPIPE;
+/// Timer signal from `alarm`.
+#[unstable(feature = "unix_signal", issue = "none")]
+pub const SIGALRM: i32 = libc::SIGALRM;
+/// Termination signal.
+#[unstable(feature = "unix_signal", issue = "none")]
+pub const SIGTERM: i32 = libc::SIGTERM;
+/// Child stopped or terminated.
+#[unstable(feature = "unix_signal", issue = "none")]
+pub const SIGCHLD: i32 = libc::SIGCHLD;
+/// Continue if stopped.
+#[unstable(feature = "unix_signal", issue = "none")]
+pub const SIGCONT: i32 = libc::SIGCONT;
+/// Stop process. It cannot be caught, blocked or ignored.
+#[unstable(feature = "unix_signal", issue = "none")]
+pub const SIGSTOP: i32 = libc::SIGSTOP;
+/// Stop typed at the terminal.
+#[unstable(feature = "unix_signal", issue = "none")]
+pub const SIGTSTP: i32 = libc::SIGTSTP;
+/// Window resize.
+#[unstable(feature = "unix_signal", issue = "none")]
+pub const SIGWINCH: i32 = libc::SIGWINCH;
+
+/// A handle receiving a set of signals delivered to the process.
+///
+/// Creating a `Signals` installs a handler for each of the given signals which records their
+/// delivery, replacing their default action (such as terminating the process) for the rest of
+/// the program's lifetime, even after the `Signals` is dropped. Every `Signals` receives each
+/// delivery of the signals it was created for.
+///
+/// The file descriptor returned by [`AsFd::as_fd`] becomes readable when a signal is pending, so
+/// it can be registered with `poll`, `epoll` or similar APIs. Pending signals must then be
+/// retrieved with [`try_recv`](Signals::try_recv).
+///
+/// Signals of the same kind that arrive in large numbers before being received may be
+/// coalesced.
+///
+/// # Examples
+///
+/// ```no_run
+/// #![feature(unix_signal)]
+/// use std::os::unix::signal::{Signals, SIGHUP};
+///
+/// fn main() -> std::io::Result<()> {
+///     let signals = Signals::new(&[SIGHUP])?;
+///     loop {
+///         // ... do some work ...
+///         while let Some(_) = signals.try_recv()? {
+///             println!("reloading configuration");
+///         }
+///     }
+/// }
+/// ```
+#[unstable(feature = "unix_signal", issue = "none")]
+pub struct Signals {
+    inner: sys::signal::Signals,
+}
+
+impl Signals {
+    /// Starts receiving the given signals.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error of kind [`io::ErrorKind::InvalidInput`] if one of the
+    /// signals is not a valid signal number below 64, or is one that cannot be handled this
+    /// way: `SIGKILL` and `SIGSTOP`, which cannot be caught, and `SIGILL`, `SIGFPE`, `SIGSEGV`
+    /// and `SIGBUS`, which are raised synchronously by faults in the program.
+    ///
+    /// It also returns an error if too many `Signals` exist at the same time.
+    #[unstable(feature = "unix_signal", issue = "none")]
+    pub fn new(signals: &[i32]) -> io::Result<Signals> {
+        sys::signal::Signals::new(signals).map(|inner| Signals { inner })
+    }
+
+    /// Blocks until one of the signals is received, and returns it.
+    ///
+    /// If signals are already pending, the oldest one is returned immediately.
+    #[unstable(feature = "unix_signal", issue = "none")]
+    pub fn recv(&self) -> io::Result<i32> {
+        self.inner.recv()
+    }
+
+    /// Returns the oldest pending signal, if any, without blocking.
+    #[unstable(feature = "unix_signal", issue = "none")]
+    pub fn try_recv(&self) -> io::Result<Option<i32>> {
+        self.inner.try_recv()
+    }
+}
+
+#[unstable(feature = "unix_signal", issue = "none")]
+impl AsFd for Signals {
+    #[inline]
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.inner.as_fd()
+    }
+}
+
+#[unstable(feature = "unix_signal", issue = "none")]
+impl AsRawFd for Signals {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.inner.as_raw_fd()
+    }
+}
+
+#[unstable(feature = "unix_signal", issue = "none")]
+impl fmt::Debug for Signals {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Signals").field("fd", &self.as_raw_fd()).finish_non_exhaustive()
+    }
+}
@@ -1,6 +1,6 @@
 use crate::sync::atomic::{AtomicUsize, Ordering};
 use crate::sync::mpsc::channel;
-use crate::sync::{Arc, Condvar, Mutex};
+use crate::sync::{Arc, Condvar, MappedMutexGuard, Mutex, MutexGuard};
 use crate::thread;
 
 struct Packet<T>(Arc<(Mutex<T>, Condvar)>);
@@ -236,3 +236,51 @@ fn test_mutex_unsized() {
     let comp: &[i32] = &[4, 2, 5];
     assert_eq!(&*mutex.lock().unwrap(), comp);
 }
+
+#[test]
+fn test_mapping_mapped_guard() {
+    let arr = [0; 4];
+    let mut lock = Mutex::new(arr);
+    let guard = lock.lock().unwrap();
+    let guard = MutexGuard::map(guard, |arr| &mut arr[..2]);
+    let mut guard = MappedMutexGuard::map(guard, |slice| &mut slice[1..]);
+    assert_eq!(guard.len(), 1);
+    guard[0] = 42;
+    drop(guard);
+    assert_eq!(*lock.get_mut().unwrap(), [0, 42, 0, 0]);
+}
+
+#[test]
+fn test_try_map_failure() {
+    let lock = Mutex::new((1, None::<i32>));
+    let guard = lock.lock().unwrap();
+    let guard = MutexGuard::try_map(guard, |(_, b)| b.as_mut()).unwrap_err();
+    let guard = MutexGuard::map(guard, |(a, _)| a);
+    let guard = MappedMutexGuard::try_map(guard, |_| None::<&mut i32>).unwrap_err();
+    assert_eq!(*guard, 1);
+    assert!(lock.try_lock().is_err());
+    drop(guard);
+    assert!(lock.try_lock().is_ok());
+}
+
+#[test]
+fn test_mapped_mutex_guard_poison() {
+    let arc = Arc::new(Mutex::new((1, 2)));
+    let arc2 = arc.clone();
+    let _ = thread::spawn(move || {
+        let lock = arc2.lock().unwrap();
+        let lock = MutexGuard::map(lock, |(_, b)| b);
+        assert_eq!(*lock, 1); // deliberate assertion failure to poison the mutex
+    })
+    .join();
+    assert!(arc.is_poisoned());
+
+    // A panic inside the mapping closure also poisons the mutex.
+    let arc = Arc::new(Mutex::new(1));
+    let arc2 = arc.clone();
+    let _ = thread::spawn(move || {
+        let _guard = MutexGuard::map::<i32, _>(arc2.lock().unwrap(), |_| panic!());
+    })
+    .join();
+    assert!(arc.is_poisoned());
+}
@@ -1,6 +1,9 @@
 use crate::sync::atomic::{AtomicUsize, Ordering};
 use crate::sync::mpsc::channel;
-use crate::sync::{Arc, RwLock, RwLockReadGuard, TryLockError};
+use crate::sync::{
+    Arc, MappedRwLockReadGuard, MappedRwLockWriteGuard, RwLock, RwLockReadGuard, RwLockWriteGuard,
+    TryLockError,
+};
 use crate::thread;
 use rand::Rng;
 
@@ -257,3 +260,71 @@ fn do_stuff<'a>(_: RwLockReadGuard<'_, &'a i32>, _: &'a i32) {}
     }
     drop(lock);
 }
+
+#[test]
+fn test_mapping_mapped_guard() {
+    let arr = [0; 4];
+    let mut lock = RwLock::new(arr);
+    let guard = lock.write().unwrap();
+    let guard = RwLockWriteGuard::map(guard, |arr| &mut arr[..2]);
+    let mut guard = MappedRwLockWriteGuard::map(guard, |slice| &mut slice[1..]);
+    assert_eq!(guard.len(), 1);
+    guard[0] = 42;
+    drop(guard);
+    assert_eq!(*lock.get_mut().unwrap(), [0, 42, 0, 0]);
+
+    let guard = lock.read().unwrap();
+    let guard = RwLockReadGuard::map(guard, |arr| &arr[..2]);
+    let guard = MappedRwLockReadGuard::map(guard, |slice| &slice[1..]);
+    assert_eq!(*guard, [42]);
+    // Mapped read guards still only hold shared access.
+    assert!(lock.try_read().is_ok());
+    assert!(lock.try_write().is_err());
+    drop(guard);
+    assert!(lock.try_write().is_ok());
+}
+
+#[test]
+fn test_try_map_failure() {
+    let lock = RwLock::new((1, None::<i32>));
+
+    let guard = lock.read().unwrap();
+    let guard = RwLockReadGuard::try_map(guard, |(_, b)| b.as_ref()).unwrap_err();
+    let guard = RwLockReadGuard::map(guard, |(a, _)| a);
+    let guard = MappedRwLockReadGuard::try_map(guard, |_| None::<&i32>).unwrap_err();
+    assert_eq!(*guard, 1);
+    drop(guard);
+
+    let guard = lock.write().unwrap();
+    let guard = RwLockWriteGuard::try_map(guard, |(_, b)| b.as_mut()).unwrap_err();
+    let guard = RwLockWriteGuard::map(guard, |(a, _)| a);
+    let guard = MappedRwLockWriteGuard::try_map(guard, |_| None::<&mut i32>).unwrap_err();
+    assert_eq!(*guard, 1);
+    assert!(lock.try_read().is_err());
+    drop(guard);
+    assert!(lock.try_write().is_ok());
+}
+
+#[test]
+fn test_mapped_rwlock_guard_poison() {
+    let arc = Arc::new(RwLock::new((1, 2)));
+    let arc2 = arc.clone();
+    let _ = thread::spawn(move || {
+        let lock = arc2.write().unwrap();
+        let lock = RwLockWriteGuard::map(lock, |(_, b)| b);
+        assert_eq!(*lock, 1); // deliberate assertion failure to poison the lock
+    })
+    .join();
+    assert!(arc.is_poisoned());
+
+    // Panicking while holding a mapped read guard does not poison the lock.
+    let arc = Arc::new(RwLock::new((1, 2)));
+    let arc2 = arc.clone();
+    let _ = thread::spawn(move || {
+        let lock = arc2.read().unwrap();
+        let lock = RwLockReadGuard::map(lock, |(_, b)| b);
+        assert_eq!(*lock, 1); // deliberate assertion failure
+    })
+    .join();
+    assert!(!arc.is_poisoned());
+}
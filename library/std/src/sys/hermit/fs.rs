@@ -15,7 +15,7 @@
 use crate::sys::unsupported;
 use crate::sys_common::{AsInner, AsInnerMut, FromInner, IntoInner};
 
-pub use crate::sys_common::fs::{copy, try_exists};
+pub use crate::sys_common::fs::{copy, try_exists, Dir};
 //pub use crate::sys_common::fs::remove_dir_all;
 
 #[derive(Debug)]
@@ -348,6 +348,26 @@ pub fn duplicate(&self) -> io::Result<File> {
         Err(Error::from_raw_os_error(22))
     }
 
+    pub fn lock(&self) -> io::Result<()> {
+        Err(Error::from_raw_os_error(22))
+    }
+
+    pub fn lock_shared(&self) -> io::Result<()> {
+        Err(Error::from_raw_os_error(22))
+    }
+
+    pub fn try_lock(&self) -> io::Result<bool> {
+        Err(Error::from_raw_os_error(22))
+    }
+
+    pub fn try_lock_shared(&self) -> io::Result<bool> {
+        Err(Error::from_raw_os_error(22))
+    }
+
+    pub fn unlock(&self) -> io::Result<()> {
+        Err(Error::from_raw_os_error(22))
+    }
+
     pub fn set_permissions(&self, _perm: FilePermissions) -> io::Result<()> {
         Err(Error::from_raw_os_error(22))
     }
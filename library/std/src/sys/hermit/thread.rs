@@ -71,6 +71,14 @@ pub fn set_name(_name: &CStr) {
         // nope
     }
 
+    pub fn set_affinity(_cpus: &[usize]) -> io::Result<()> {
+        super::unsupported()
+    }
+
+    pub fn set_priority(_nice: i32) -> io::Result<()> {
+        super::unsupported()
+    }
+
     #[inline]
     pub fn sleep(dur: Duration) {
         unsafe {
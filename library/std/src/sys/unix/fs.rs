@@ -261,8 +261,11 @@ pub struct FileAttr {
 
 // all DirEntry's will have a reference to this struct
 struct InnerReadDir {
-    dirp: Dir,
+    dirp: DirStream,
     root: PathBuf,
+    // The fd of `dirp` for streams opened by `Dir::read_dir`, whose `root` is relative to that
+    // directory rather than to the current directory.
+    dirfd: Option<RawFd>,
 }
 
 pub struct ReadDir {
@@ -276,10 +279,10 @@ fn new(inner: InnerReadDir) -> Self {
     }
 }
 
-struct Dir(*mut libc::DIR);
+struct DirStream(*mut libc::DIR);
 
-unsafe impl Send for Dir {}
-unsafe impl Sync for Dir {}
+unsafe impl Send for DirStream {}
+unsafe impl Sync for DirStream {}
 
 #[cfg(any(
     target_os = "android",
@@ -807,7 +810,7 @@ fn next(&mut self) -> Option<io::Result<DirEntry>> {
     }
 }
 
-impl Drop for Dir {
+impl Drop for DirStream {
     fn drop(&mut self) {
         let r = unsafe { libc::closedir(self.0) };
         assert!(
@@ -837,23 +840,11 @@ pub fn file_name(&self) -> OsString {
         not(miri)
     ))]
     pub fn metadata(&self) -> io::Result<FileAttr> {
-        let fd = cvt(unsafe { dirfd(self.dir.dirp.0) })?;
-        let name = self.name_cstr().as_ptr();
-
-        cfg_has_statx! {
-            if let Some(ret) = unsafe { try_statx(
-                fd,
-                name,
-                libc::AT_SYMLINK_NOFOLLOW | libc::AT_STATX_SYNC_AS_STAT,
-                libc::STATX_ALL,
-            ) } {
-                return ret;
-            }
-        }
-
-        let mut stat: stat64 = unsafe { mem::zeroed() };
-        cvt(unsafe { fstatat64(fd, name, &mut stat, libc::AT_SYMLINK_NOFOLLOW) })?;
-        Ok(FileAttr::from_stat64(stat))
+        let fd = match self.dir.dirfd {
+            Some(fd) => fd,
+            None => cvt(unsafe { dirfd(self.dir.dirp.0) })?,
+        };
+        dir_impl::entry_metadata(fd, self.name_cstr())
     }
 
     #[cfg(any(
@@ -866,7 +857,10 @@ pub fn metadata(&self) -> io::Result<FileAttr> {
         miri
     ))]
     pub fn metadata(&self) -> io::Result<FileAttr> {
-        lstat(&self.path())
+        match self.dir.dirfd {
+            Some(fd) => dir_impl::entry_metadata(fd, self.name_cstr()),
+            None => lstat(&self.path()),
+        }
     }
 
     #[cfg(any(
@@ -1080,6 +1074,13 @@ fn get_creation_mode(&self) -> io::Result<c_int> {
             (_, _, true) => libc::O_CREAT | libc::O_EXCL,
         })
     }
+
+    fn get_flags(&self) -> io::Result<c_int> {
+        Ok(libc::O_CLOEXEC
+            | self.get_access_mode()?
+            | self.get_creation_mode()?
+            | (self.custom_flags as c_int & !libc::O_ACCMODE))
+    }
 }
 
 impl File {
@@ -1088,10 +1089,7 @@ pub fn open(path: &Path, opts: &OpenOptions) -> io::Result<File> {
     }
 
     pub fn open_c(path: &CStr, opts: &OpenOptions) -> io::Result<File> {
-        let flags = libc::O_CLOEXEC
-            | opts.get_access_mode()?
-            | opts.get_creation_mode()?
-            | (opts.custom_flags as c_int & !libc::O_ACCMODE);
+        let flags = opts.get_flags()?;
         // The third argument of `open64` is documented to have type `mode_t`. On
         // some platforms (like macOS, where `open64` is actually `open`), `mode_t` is `u16`.
         // However, since this is a variadic function, C integer promotion rules mean that on
@@ -1350,6 +1348,117 @@ pub fn set_times(&self, times: FileTimes) -> io::Result<()> {
     }
 }
 
+cfg_if::cfg_if! {
+    if #[cfg(any(
+        target_os = "android",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "fuchsia",
+        target_os = "hurd",
+        target_os = "illumos",
+        target_os = "linux",
+        target_os = "netbsd",
+        target_os = "openbsd",
+        target_os = "macos",
+        target_os = "ios",
+        target_os = "tvos",
+        target_os = "watchos",
+    ))] {
+        impl File {
+            pub fn lock(&self) -> io::Result<()> {
+                cvt(unsafe { libc::flock(self.as_raw_fd(), libc::LOCK_EX) })?;
+                Ok(())
+            }
+
+            pub fn lock_shared(&self) -> io::Result<()> {
+                cvt(unsafe { libc::flock(self.as_raw_fd(), libc::LOCK_SH) })?;
+                Ok(())
+            }
+
+            pub fn try_lock(&self) -> io::Result<bool> {
+                self.try_flock(libc::LOCK_EX)
+            }
+
+            pub fn try_lock_shared(&self) -> io::Result<bool> {
+                self.try_flock(libc::LOCK_SH)
+            }
+
+            fn try_flock(&self, operation: c_int) -> io::Result<bool> {
+                match cvt(unsafe { libc::flock(self.as_raw_fd(), operation | libc::LOCK_NB) }) {
+                    Ok(_) => Ok(true),
+                    Err(err) if err.raw_os_error() == Some(libc::EWOULDBLOCK) => Ok(false),
+                    Err(err) => Err(err),
+                }
+            }
+
+            pub fn unlock(&self) -> io::Result<()> {
+                cvt(unsafe { libc::flock(self.as_raw_fd(), libc::LOCK_UN) })?;
+                Ok(())
+            }
+        }
+    } else {
+        // Solaris only gained `flock` in 11.4, while Solaris 10 is still supported.
+        impl File {
+            pub fn lock(&self) -> io::Result<()> {
+                Err(io::const_io_error!(io::ErrorKind::Unsupported, "lock() not supported"))
+            }
+
+            pub fn lock_shared(&self) -> io::Result<()> {
+                Err(io::const_io_error!(io::ErrorKind::Unsupported, "lock_shared() not supported"))
+            }
+
+            pub fn try_lock(&self) -> io::Result<bool> {
+                Err(io::const_io_error!(io::ErrorKind::Unsupported, "try_lock() not supported"))
+            }
+
+            pub fn try_lock_shared(&self) -> io::Result<bool> {
+                Err(io::const_io_error!(
+                    io::ErrorKind::Unsupported,
+                    "try_lock_shared() not supported",
+                ))
+            }
+
+            pub fn unlock(&self) -> io::Result<()> {
+                Err(io::const_io_error!(io::ErrorKind::Unsupported, "unlock() not supported"))
+            }
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl File {
+    pub fn memfd_create(name: &CStr, allow_sealing: bool) -> io::Result<File> {
+        let mut flags = libc::MFD_CLOEXEC;
+        if allow_sealing {
+            flags |= libc::MFD_ALLOW_SEALING;
+        }
+        // Use the raw syscall, as `memfd_create` is only exposed by glibc 2.27 and later.
+        let fd = cvt(unsafe { libc::syscall(libc::SYS_memfd_create, name.as_ptr(), flags) })?;
+        Ok(File(unsafe { FileDesc::from_raw_fd(fd as c_int) }))
+    }
+
+    pub fn add_seals(&self, seals: c_int) -> io::Result<()> {
+        cvt(unsafe { libc::fcntl(self.as_raw_fd(), libc::F_ADD_SEALS, seals) }).map(drop)
+    }
+
+    pub fn get_seals(&self) -> io::Result<c_int> {
+        cvt(unsafe { libc::fcntl(self.as_raw_fd(), libc::F_GET_SEALS) })
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl File {
+    pub fn copy_range_to(
+        &self,
+        offset: u64,
+        dest: &File,
+        dest_offset: u64,
+        len: u64,
+    ) -> io::Result<u64> {
+        super::kernel_copy::copy_file_range_at(self, offset, dest, dest_offset, len)
+    }
+}
+
 impl DirBuilder {
     pub fn new() -> DirBuilder {
         DirBuilder { mode: 0o777 }
@@ -1542,7 +1651,7 @@ pub fn readdir(path: &Path) -> io::Result<ReadDir> {
         Err(Error::last_os_error())
     } else {
         let root = path.to_path_buf();
-        let inner = InnerReadDir { dirp: Dir(ptr), root };
+        let inner = InnerReadDir { dirp: DirStream(ptr), root, dirfd: None };
         Ok(ReadDir::new(inner))
     }
 }
@@ -1910,6 +2019,223 @@ pub fn chroot(dir: &Path) -> io::Result<()> {
     run_path_with_cstr(dir, |dir| cvt(unsafe { libc::chroot(dir.as_ptr()) }).map(|_| ()))
 }
 
+pub use dir_impl::Dir;
+
+// Fallback for REDOX, ESP-IDF, Horizon, Vita, NTO, L4Re, VxWorks and Miri
+#[cfg(any(
+    target_os = "redox",
+    target_os = "espidf",
+    target_os = "horizon",
+    target_os = "vita",
+    target_os = "nto",
+    target_os = "l4re",
+    target_os = "vxworks",
+    miri
+))]
+mod dir_impl {
+    use super::FileAttr;
+    use crate::ffi::CStr;
+    use crate::io;
+    use crate::os::unix::io::RawFd;
+
+    pub use crate::sys_common::fs::Dir;
+
+    // The `Dir` used here reads directories by path, so no `ReadDir` carries a directory fd.
+    pub(super) fn entry_metadata(_dirfd: RawFd, _name: &CStr) -> io::Result<FileAttr> {
+        unreachable!("`ReadDir` without a directory fd")
+    }
+}
+
+// Implementation relative to an open directory using openat(), mkdirat(), unlinkat(), renameat()
+// and fstatat()
+#[cfg(not(any(
+    target_os = "redox",
+    target_os = "espidf",
+    target_os = "horizon",
+    target_os = "vita",
+    target_os = "nto",
+    target_os = "l4re",
+    target_os = "vxworks",
+    miri
+)))]
+mod dir_impl {
+    use super::{stat64, DirStream, File, FileAttr, InnerReadDir, OpenOptions, ReadDir};
+    use crate::ffi::CStr;
+    use crate::fmt;
+    use crate::io;
+    use crate::mem;
+    use crate::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, RawFd};
+    use crate::path::Path;
+    use crate::sys::common::small_c_string::run_path_with_cstr;
+    use crate::sys::fd::FileDesc;
+    use crate::sys::{cvt, cvt_r};
+    use crate::sys_common::{AsInner, FromInner, IntoInner};
+
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "emscripten",
+        target_os = "android",
+        target_os = "hurd",
+    ))]
+    use super::fstatat64;
+    #[cfg(not(any(
+        target_os = "linux",
+        target_os = "emscripten",
+        target_os = "android",
+        target_os = "hurd",
+    )))]
+    use libc::fstatat as fstatat64;
+    #[cfg(not(all(target_os = "linux", target_env = "gnu")))]
+    use libc::openat;
+    #[cfg(all(target_os = "linux", target_env = "gnu"))]
+    use libc::openat64 as openat;
+
+    pub struct Dir(FileDesc);
+
+    impl Dir {
+        pub fn open(path: &Path) -> io::Result<Dir> {
+            run_path_with_cstr(path, |path| Dir::open_c(libc::AT_FDCWD, path))
+        }
+
+        pub fn open_dir(&self, path: &Path) -> io::Result<Dir> {
+            run_path_with_cstr(path, |path| Dir::open_c(self.as_raw_fd(), path))
+        }
+
+        fn open_c(dirfd: RawFd, path: &CStr) -> io::Result<Dir> {
+            let fd = cvt_r(|| unsafe {
+                openat(dirfd, path.as_ptr(), libc::O_CLOEXEC | libc::O_RDONLY | libc::O_DIRECTORY)
+            })?;
+            Ok(Dir(unsafe { FileDesc::from_raw_fd(fd) }))
+        }
+
+        pub fn open_file(&self, path: &Path, opts: &OpenOptions) -> io::Result<File> {
+            run_path_with_cstr(path, |path| {
+                let flags = opts.get_flags()?;
+                // See `File::open_c` for why the mode is passed as `c_int`.
+                let fd = cvt_r(|| unsafe {
+                    openat(self.as_raw_fd(), path.as_ptr(), flags, opts.mode as libc::c_int)
+                })?;
+                Ok(File::from_inner(unsafe { FileDesc::from_raw_fd(fd) }))
+            })
+        }
+
+        pub fn read_dir(&self, path: &Path) -> io::Result<ReadDir> {
+            let dir = run_path_with_cstr(path, |p| Dir::open_c(self.as_raw_fd(), p))?;
+            let ptr = unsafe { libc::fdopendir(dir.as_raw_fd()) };
+            if ptr.is_null() {
+                return Err(io::Error::last_os_error());
+            }
+            // file descriptor is automatically closed by libc::closedir() now, so give up ownership
+            let fd = dir.into_inner().into_raw_fd();
+            let inner =
+                InnerReadDir { dirp: DirStream(ptr), root: path.to_path_buf(), dirfd: Some(fd) };
+            Ok(ReadDir::new(inner))
+        }
+
+        pub fn create_dir(&self, path: &Path) -> io::Result<()> {
+            run_path_with_cstr(path, |p| {
+                cvt(unsafe { libc::mkdirat(self.as_raw_fd(), p.as_ptr(), 0o777) }).map(drop)
+            })
+        }
+
+        pub fn remove_file(&self, path: &Path) -> io::Result<()> {
+            self.unlinkat(path, 0)
+        }
+
+        pub fn remove_dir(&self, path: &Path) -> io::Result<()> {
+            self.unlinkat(path, libc::AT_REMOVEDIR)
+        }
+
+        fn unlinkat(&self, path: &Path, flags: libc::c_int) -> io::Result<()> {
+            run_path_with_cstr(path, |p| {
+                cvt(unsafe { libc::unlinkat(self.as_raw_fd(), p.as_ptr(), flags) }).map(drop)
+            })
+        }
+
+        pub fn rename(&self, from: &Path, to_dir: &Dir, to: &Path) -> io::Result<()> {
+            run_path_with_cstr(from, |from| {
+                run_path_with_cstr(to, |to| {
+                    cvt(unsafe {
+                        libc::renameat(
+                            self.as_raw_fd(),
+                            from.as_ptr(),
+                            to_dir.as_raw_fd(),
+                            to.as_ptr(),
+                        )
+                    })
+                    .map(drop)
+                })
+            })
+        }
+
+        pub fn metadata(&self, path: &Path) -> io::Result<FileAttr> {
+            self.fstatat(path, 0)
+        }
+
+        pub fn symlink_metadata(&self, path: &Path) -> io::Result<FileAttr> {
+            self.fstatat(path, libc::AT_SYMLINK_NOFOLLOW)
+        }
+
+        fn fstatat(&self, path: &Path, flags: libc::c_int) -> io::Result<FileAttr> {
+            run_path_with_cstr(path, |p| fstatat(self.as_raw_fd(), p, flags))
+        }
+    }
+
+    // `DirEntry::metadata` relative to the directory the entry was read from.
+    pub(super) fn entry_metadata(dirfd: RawFd, name: &CStr) -> io::Result<FileAttr> {
+        fstatat(dirfd, name, libc::AT_SYMLINK_NOFOLLOW)
+    }
+
+    fn fstatat(dirfd: RawFd, path: &CStr, flags: libc::c_int) -> io::Result<FileAttr> {
+        cfg_has_statx! {
+            if let Some(ret) = unsafe { super::try_statx(
+                dirfd,
+                path.as_ptr(),
+                flags | libc::AT_STATX_SYNC_AS_STAT,
+                libc::STATX_ALL,
+            ) } {
+                return ret;
+            }
+        }
+
+        let mut stat: stat64 = unsafe { mem::zeroed() };
+        cvt(unsafe { fstatat64(dirfd, path.as_ptr(), &mut stat, flags) })?;
+        Ok(FileAttr::from_stat64(stat))
+    }
+
+    impl AsRawFd for Dir {
+        #[inline]
+        fn as_raw_fd(&self) -> RawFd {
+            self.0.as_raw_fd()
+        }
+    }
+
+    impl AsInner<FileDesc> for Dir {
+        #[inline]
+        fn as_inner(&self) -> &FileDesc {
+            &self.0
+        }
+    }
+
+    impl IntoInner<FileDesc> for Dir {
+        fn into_inner(self) -> FileDesc {
+            self.0
+        }
+    }
+
+    impl FromInner<FileDesc> for Dir {
+        fn from_inner(fd: FileDesc) -> Dir {
+            Dir(fd)
+        }
+    }
+
+    impl fmt::Debug for Dir {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.debug_struct("Dir").field("fd", &self.as_raw_fd()).finish()
+        }
+    }
+}
+
 pub use remove_dir_impl::remove_dir_all;
 
 // Fallback for REDOX, ESP-ID, Horizon, Vita and Miri
@@ -1935,7 +2261,7 @@ mod remove_dir_impl {
     miri
 )))]
 mod remove_dir_impl {
-    use super::{lstat, Dir, DirEntry, InnerReadDir, ReadDir};
+    use super::{lstat, DirEntry, DirStream, InnerReadDir, ReadDir};
     use crate::ffi::CStr;
     use crate::io;
     use crate::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd};
@@ -2011,13 +2337,13 @@ fn fdreaddir(dir_fd: OwnedFd) -> io::Result<(ReadDir, RawFd)> {
         if ptr.is_null() {
             return Err(io::Error::last_os_error());
         }
-        let dirp = Dir(ptr);
+        let dirp = DirStream(ptr);
         // file descriptor is automatically closed by libc::closedir() now, so give up ownership
         let new_parent_fd = dir_fd.into_raw_fd();
         // a valid root is not needed because we do not call any functions involving the full path
         // of the `DirEntry`s.
         let dummy_root = PathBuf::new();
-        let inner = InnerReadDir { dirp, root: dummy_root };
+        let inner = InnerReadDir { dirp, root: dummy_root, dirfd: None };
         Ok((ReadDir::new(inner), new_parent_fd))
     }
 
@@ -46,8 +46,8 @@
 use crate::fs::{File, Metadata};
 use crate::io::copy::generic_copy;
 use crate::io::{
-    BufRead, BufReader, BufWriter, Error, Read, Result, StderrLock, StdinLock, StdoutLock, Take,
-    Write,
+    self, BufRead, BufReader, BufWriter, Error, Read, Result, StderrLock, StdinLock, StdoutLock,
+    Take, Write,
 };
 use crate::mem::ManuallyDrop;
 use crate::net::TcpStream;
@@ -58,11 +58,14 @@
 use crate::ptr;
 use crate::sync::atomic::{AtomicBool, AtomicU8, Ordering};
 use crate::sys::cvt;
+use crate::sys::fs as fs_imp;
 use crate::sys::weak::syscall;
+use crate::sys_common::io::DEFAULT_BUF_SIZE;
+use crate::sys_common::net as net_imp;
 #[cfg(not(any(all(target_os = "linux", target_env = "gnu"), target_os = "hurd")))]
-use libc::sendfile as sendfile64;
+use libc::{off_t as off64_t, sendfile as sendfile64};
 #[cfg(any(all(target_os = "linux", target_env = "gnu"), target_os = "hurd"))]
-use libc::sendfile64;
+use libc::{off64_t, sendfile64};
 use libc::{EBADF, EINVAL, ENOSYS, EOPNOTSUPP, EOVERFLOW, EPERM, EXDEV};
 
 #[cfg(test)]
@@ -269,7 +272,7 @@ trait CopyRead: Read {
     ///
     /// This is necessary to directly bypass the wrapper types while preserving the data order
     /// when operating directly on the underlying file descriptors.
-    fn drain_to<W: Write>(&mut self, _writer: &mut W, _limit: u64) -> Result<u64> {
+    fn drain_to<W: CopyWrite>(&mut self, _writer: &mut W, _limit: u64) -> Result<u64> {
         Ok(0)
     }
 
@@ -290,6 +293,17 @@ fn min_limit(&self) -> u64 {
 
 #[rustc_specialization_trait]
 trait CopyWrite: Write {
+    /// Writes `buf` after any data buffered by the implementation (i.e. `BufWriter`), so that
+    /// both reach the underlying file descriptor in order.
+    ///
+    /// Buffering implementations should pass their buffered data and `buf` to the inner writer
+    /// in vectored writes instead of copying `buf` into the buffer only to write it out again
+    /// right after, which is the common case when `drain_to` moves data from a `BufReader` into
+    /// a partially filled `BufWriter`.
+    fn write_through(&mut self, buf: &[u8]) -> Result<()> {
+        self.write_all(buf)
+    }
+
     /// Extracts the file descriptor and hints/metadata, delegating through wrappers if necessary.
     fn properties(&self) -> CopyParams;
 }
@@ -298,7 +312,7 @@ impl<T> CopyRead for &mut T
 where
     T: CopyRead,
 {
-    fn drain_to<W: Write>(&mut self, writer: &mut W, limit: u64) -> Result<u64> {
+    fn drain_to<W: CopyWrite>(&mut self, writer: &mut W, limit: u64) -> Result<u64> {
         (**self).drain_to(writer, limit)
     }
 
@@ -319,6 +333,10 @@ impl<T> CopyWrite for &mut T
 where
     T: CopyWrite,
 {
+    fn write_through(&mut self, buf: &[u8]) -> Result<()> {
+        (**self).write_through(buf)
+    }
+
     fn properties(&self) -> CopyParams {
         (**self).properties()
     }
@@ -423,12 +441,12 @@ fn properties(&self) -> CopyParams {
 }
 
 impl CopyRead for StdinLock<'_> {
-    fn drain_to<W: Write>(&mut self, writer: &mut W, outer_limit: u64) -> Result<u64> {
+    fn drain_to<W: CopyWrite>(&mut self, writer: &mut W, outer_limit: u64) -> Result<u64> {
         let buf_reader = self.as_mut_buf();
         let buf = buf_reader.buffer();
         let buf = &buf[0..min(buf.len(), outer_limit.try_into().unwrap_or(usize::MAX))];
         let bytes_drained = buf.len();
-        writer.write_all(buf)?;
+        writer.write_through(buf)?;
         buf_reader.consume(bytes_drained);
 
         Ok(bytes_drained as u64)
@@ -452,7 +470,7 @@ fn properties(&self) -> CopyParams {
 }
 
 impl<T: CopyRead> CopyRead for Take<T> {
-    fn drain_to<W: Write>(&mut self, writer: &mut W, outer_limit: u64) -> Result<u64> {
+    fn drain_to<W: CopyWrite>(&mut self, writer: &mut W, outer_limit: u64) -> Result<u64> {
         let local_limit = self.limit();
         let combined_limit = min(outer_limit, local_limit);
         let bytes_drained = self.get_mut().drain_to(writer, combined_limit)?;
@@ -477,11 +495,11 @@ fn properties(&self) -> CopyParams {
 }
 
 impl<T: ?Sized + CopyRead> CopyRead for BufReader<T> {
-    fn drain_to<W: Write>(&mut self, writer: &mut W, outer_limit: u64) -> Result<u64> {
+    fn drain_to<W: CopyWrite>(&mut self, writer: &mut W, outer_limit: u64) -> Result<u64> {
         let buf = self.buffer();
         let buf = &buf[0..min(buf.len(), outer_limit.try_into().unwrap_or(usize::MAX))];
         let bytes = buf.len();
-        writer.write_all(buf)?;
+        writer.write_through(buf)?;
         self.consume(bytes);
 
         let remaining = outer_limit - bytes as u64;
@@ -506,6 +524,10 @@ fn properties(&self) -> CopyParams {
 }
 
 impl<T: ?Sized + CopyWrite> CopyWrite for BufWriter<T> {
+    fn write_through(&mut self, buf: &[u8]) -> Result<()> {
+        self.flush_buf_vectored(buf)
+    }
+
     fn properties(&self) -> CopyParams {
         self.get_ref().properties()
     }
@@ -536,6 +558,18 @@ fn update_take(&self, reader: &mut impl CopyRead) {
     }
 }
 
+// Kernels prior to 4.5 don't have copy_file_range, so it is looked up at runtime.
+syscall! {
+    fn copy_file_range(
+        fd_in: libc::c_int,
+        off_in: *mut libc::loff_t,
+        fd_out: libc::c_int,
+        off_out: *mut libc::loff_t,
+        len: libc::size_t,
+        flags: libc::c_uint
+    ) -> libc::ssize_t
+}
+
 /// Invalid file descriptor.
 ///
 /// Valid file descriptors are guaranteed to be positive numbers (see `open()` manpage)
@@ -560,17 +594,6 @@ pub(super) fn copy_regular_files(reader: RawFd, writer: RawFd, max_len: u64) ->
     // We store the availability in a global to avoid unnecessary syscalls
     static HAS_COPY_FILE_RANGE: AtomicU8 = AtomicU8::new(NOT_PROBED);
 
-    syscall! {
-        fn copy_file_range(
-            fd_in: libc::c_int,
-            off_in: *mut libc::loff_t,
-            fd_out: libc::c_int,
-            off_out: *mut libc::loff_t,
-            len: libc::size_t,
-            flags: libc::c_uint
-        ) -> libc::ssize_t
-    }
-
     match HAS_COPY_FILE_RANGE.load(Ordering::Relaxed) {
         NOT_PROBED => {
             // EPERM can indicate seccomp filters or an immutable file.
@@ -728,3 +751,142 @@ fn splice(
     }
     CopyResult::Ended(written)
 }
+
+/// Copies up to `len` bytes from `reader` at `reader_offset` to `writer` at `writer_offset`,
+/// without using or changing the file offsets of either file. Used by
+/// `os::linux::fs::CopyRangeExt::copy_range_to`.
+///
+/// `copy_file_range` is tried first, falling back to a `pread`/`pwrite` loop where it is not
+/// available or not supported for these files. Stops early only at the end of `reader`.
+#[cfg(target_os = "linux")]
+pub(crate) fn copy_file_range_at(
+    reader: &fs_imp::File,
+    reader_offset: u64,
+    writer: &fs_imp::File,
+    writer_offset: u64,
+    len: u64,
+) -> Result<u64> {
+    let offset_error = || io::const_io_error!(io::ErrorKind::InvalidInput, "offset is too large");
+    let mut off_in: libc::loff_t = reader_offset.try_into().map_err(|_| offset_error())?;
+    let mut off_out: libc::loff_t = writer_offset.try_into().map_err(|_| offset_error())?;
+
+    let mut written = 0u64;
+    while written < len {
+        let bytes_to_copy = min(len - written, 0x4000_0000) as usize;
+        let result = cvt(unsafe {
+            copy_file_range(
+                reader.as_raw_fd(),
+                &mut off_in,
+                writer.as_raw_fd(),
+                &mut off_out,
+                bytes_to_copy,
+                0,
+            )
+        });
+
+        match result {
+            // See `copy_regular_files` for the files `copy_file_range` fails to copy from without
+            // reporting an error.
+            Ok(0) if written == 0 => break,
+            Ok(0) => return Ok(written),
+            Ok(n) => written += n as u64,
+            Err(err) => match err.raw_os_error() {
+                Some(ENOSYS | EXDEV | EINVAL | EPERM | EOPNOTSUPP | EBADF) if written == 0 => break,
+                _ => return Err(err),
+            },
+        }
+    }
+
+    let mut buf = [0; DEFAULT_BUF_SIZE];
+    while written < len {
+        let bytes_to_read = min(len - written, buf.len() as u64) as usize;
+        let n = match reader.read_at(&mut buf[..bytes_to_read], reader_offset + written) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.is_interrupted() => continue,
+            Err(e) => return Err(e),
+        };
+        let mut chunk = &buf[..n];
+        while !chunk.is_empty() {
+            match writer.write_at(chunk, writer_offset + written) {
+                Ok(0) => {
+                    return Err(io::const_io_error!(
+                        io::ErrorKind::WriteZero,
+                        "failed to write whole buffer",
+                    ));
+                }
+                Ok(n) => {
+                    written += n as u64;
+                    chunk = &chunk[n..];
+                }
+                Err(e) if e.is_interrupted() => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+    Ok(written)
+}
+
+/// Sends up to `len` bytes of `file`, starting at `offset`, to `socket` without using or
+/// changing the file offset. Used by `os::linux::net::TcpSendFileExt::send_file`.
+///
+/// `sendfile` is tried first, falling back to a `pread`/`send` loop for files it cannot send
+/// from. Stops early at the end of `file`, or if a non-blocking `socket` would block after some
+/// data was sent.
+pub(crate) fn send_file(
+    socket: &net_imp::TcpStream,
+    file: &fs_imp::File,
+    offset: u64,
+    len: u64,
+) -> Result<u64> {
+    let mut off: off64_t = offset
+        .try_into()
+        .map_err(|_| io::const_io_error!(io::ErrorKind::InvalidInput, "offset is too large"))?;
+    let fd = socket.socket().as_raw_fd();
+
+    let mut written = 0u64;
+    while written < len {
+        // according to its manpage that's the maximum size sendfile() will copy per invocation
+        let chunk_size = min(len - written, 0x7ffff000_u64) as usize;
+        match cvt(unsafe { sendfile64(fd, file.as_raw_fd(), &mut off, chunk_size) }) {
+            Ok(0) => return Ok(written), // EOF
+            Ok(n) => written += n as u64,
+            Err(e) if e.is_interrupted() => {}
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock && written > 0 => return Ok(written),
+            Err(e) if matches!(e.raw_os_error(), Some(EINVAL | ENOSYS)) && written == 0 => break,
+            Err(e) => return Err(e),
+        }
+    }
+
+    let mut buf = [0; DEFAULT_BUF_SIZE];
+    while written < len {
+        let bytes_to_read = min(len - written, buf.len() as u64) as usize;
+        let n = match file.read_at(&mut buf[..bytes_to_read], offset + written) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.is_interrupted() => continue,
+            Err(e) => return Err(e),
+        };
+        let mut chunk = &buf[..n];
+        while !chunk.is_empty() {
+            match socket.write(chunk) {
+                Ok(0) => {
+                    return Err(io::const_io_error!(
+                        io::ErrorKind::WriteZero,
+                        "failed to write whole buffer",
+                    ));
+                }
+                Ok(n) => {
+                    written += n as u64;
+                    chunk = &chunk[n..];
+                }
+                Err(e) if e.is_interrupted() => {}
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock && written > 0 => {
+                    return Ok(written);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+    Ok(written)
+}
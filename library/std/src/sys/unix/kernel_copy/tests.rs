@@ -125,6 +125,143 @@ fn dont_splice_pipes_from_files() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn copy_buffered_file_to_buffered_socket() -> Result<()> {
+    use super::{CopyParams, CopyWrite};
+    use crate::io::{BufReader, BufWriter, IoSlice};
+    use crate::net::{TcpListener, TcpStream};
+
+    // Records the data of each write call that goes through user space.
+    struct RecordWrites {
+        inner: TcpStream,
+        writes: Vec<Vec<u8>>,
+    }
+
+    impl Write for RecordWrites {
+        fn write(&mut self, buf: &[u8]) -> Result<usize> {
+            self.write_vectored(&[IoSlice::new(buf)])
+        }
+
+        fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> Result<usize> {
+            let n = self.inner.write_vectored(bufs)?;
+            self.writes.push(bufs.iter().flat_map(|b| b.iter().copied()).take(n).collect());
+            Ok(n)
+        }
+
+        fn is_write_vectored(&self) -> bool {
+            true
+        }
+
+        fn flush(&mut self) -> Result<()> {
+            self.inner.flush()
+        }
+    }
+
+    impl CopyWrite for RecordWrites {
+        fn properties(&self) -> CopyParams {
+            self.inner.properties()
+        }
+    }
+
+    let tmp_path = tmpdir();
+    let source_path = tmp_path.join("buffered-to-socket.source");
+    let mut source =
+        OpenOptions::new().create(true).truncate(true).read(true).write(true).open(&source_path)?;
+    source.write_all(b"body of the file")?;
+    source.seek(SeekFrom::Start(0))?;
+
+    let listener = TcpListener::bind("localhost:0")?;
+    let inner = TcpStream::connect(listener.local_addr()?)?;
+    let mut receiver = listener.accept()?.0;
+
+    let mut source = BufReader::with_capacity(4, source);
+    source.fill_buf()?;
+    assert_eq!(source.buffer(), b"body");
+    // The pending header and the drained reader buffer don't fit into the writer's buffer
+    // together, so buffering the drained data would have to write out the header on its own.
+    let mut sink = BufWriter::with_capacity(8, RecordWrites { inner, writes: Vec::new() });
+    sink.write_all(b"header\n")?;
+    assert_eq!(sink.buffer(), b"header\n");
+
+    let copied = io::copy(&mut source, &mut sink)?;
+    assert_eq!(copied, 16);
+    assert_eq!(sink.buffer().len(), 0, "sink buffer was flushed");
+    let writes = sink.into_inner()?.writes;
+    assert_eq!(writes[0], b"header\nbody", "buffers were written out in one vectored write");
+
+    let mut received = Vec::new();
+    receiver.read_to_end(&mut received)?;
+    assert_eq!(received, b"header\nbody of the file");
+
+    Ok(())
+}
+
+#[test]
+#[cfg(target_os = "linux")]
+fn copy_range_to() -> Result<()> {
+    use crate::os::linux::fs::CopyRangeExt;
+
+    let tmp_path = tmpdir();
+    let open = |name| {
+        OpenOptions::new()
+            .create(true)
+            .truncate(true)
+            .read(true)
+            .write(true)
+            .open(tmp_path.join(name))
+    };
+    let mut source = open("copy-range.source")?;
+    let mut sink = open("copy-range.sink")?;
+    source.write_all(b"0123456789")?;
+    sink.write_all(b"abcdef")?;
+
+    assert_eq!(source.copy_range_to(3, &sink, 2, 4)?, 4);
+    // only the bytes up to the end of the source are copied
+    assert_eq!(source.copy_range_to(8, &sink, 6, 4)?, 2);
+    assert_eq!(source.copy_range_to(10, &sink, 0, 4)?, 0);
+
+    // the file cursors are left alone
+    assert_eq!(source.stream_position()?, 10);
+    assert_eq!(sink.stream_position()?, 6);
+
+    let mut copied = Vec::new();
+    sink.seek(SeekFrom::Start(0))?;
+    sink.read_to_end(&mut copied)?;
+    assert_eq!(copied, b"ab345689");
+
+    Ok(())
+}
+
+#[test]
+fn send_file() -> Result<()> {
+    use crate::net::{TcpListener, TcpStream};
+    use crate::os::net::linux_ext::tcp::TcpSendFileExt;
+
+    let tmp_path = tmpdir();
+    let mut file = OpenOptions::new()
+        .create(true)
+        .truncate(true)
+        .read(true)
+        .write(true)
+        .open(tmp_path.join("send-file.source"))?;
+    file.write_all(b"0123456789")?;
+
+    let listener = TcpListener::bind("localhost:0")?;
+    let sink = TcpStream::connect(listener.local_addr()?)?;
+    let mut receiver = listener.accept()?.0;
+
+    assert_eq!(sink.send_file(&file, 2, 3)?, 3);
+    assert_eq!(sink.send_file(&file, 7, 10)?, 3, "stops at the end of the file");
+    assert_eq!(file.stream_position()?, 10, "the file cursor is left alone");
+    drop(sink);
+
+    let mut received = Vec::new();
+    receiver.read_to_end(&mut received)?;
+    assert_eq!(received, b"234789");
+
+    Ok(())
+}
+
 #[bench]
 fn bench_file_to_file_copy(b: &mut test::Bencher) {
     const BYTES: usize = 128 * 1024;
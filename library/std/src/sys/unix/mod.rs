@@ -32,9 +32,19 @@
 pub mod os;
 pub mod os_str;
 pub mod path;
+#[cfg(target_os = "linux")]
+pub mod pidfd;
 pub mod pipe;
 pub mod process;
 pub mod rand;
+#[cfg(not(any(
+    target_os = "espidf",
+    target_os = "horizon",
+    target_os = "vita",
+    target_os = "vxworks",
+    target_os = "fuchsia",
+)))]
+pub mod signal;
 pub mod stack_overflow;
 pub mod stdio;
 pub mod thread;
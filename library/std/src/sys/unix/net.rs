@@ -58,6 +58,19 @@ pub fn cvt_gai(err: c_int) -> io::Result<()> {
     ))
 }
 
+/// Converts a keepalive timing to the whole number of seconds expected by `TCP_KEEPIDLE` and
+/// `TCP_KEEPINTVL`.
+#[cfg(any(target_os = "android", target_os = "linux",))]
+fn keepalive_secs(dur: Duration) -> io::Result<c_int> {
+    if dur.as_secs() == 0 {
+        return Err(io::const_io_error!(
+            io::ErrorKind::InvalidInput,
+            "keepalive timings must be at least one second",
+        ));
+    }
+    Ok(cmp::min(dur.as_secs(), c_int::MAX as u64) as c_int)
+}
+
 impl Socket {
     pub fn new(addr: &SocketAddr, ty: c_int) -> io::Result<Socket> {
         let fam = match *addr {
@@ -424,6 +437,107 @@ pub fn quickack(&self) -> io::Result<bool> {
         Ok(raw != 0)
     }
 
+    #[cfg(any(target_os = "android", target_os = "linux",))]
+    pub fn set_reuseport(&self, reuseport: bool) -> io::Result<()> {
+        setsockopt(self, libc::SOL_SOCKET, libc::SO_REUSEPORT, reuseport as c_int)
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux",))]
+    pub fn reuseport(&self) -> io::Result<bool> {
+        let raw: c_int = getsockopt(self, libc::SOL_SOCKET, libc::SO_REUSEPORT)?;
+        Ok(raw != 0)
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux",))]
+    pub fn set_keepalive_idle(&self, idle: Duration) -> io::Result<()> {
+        setsockopt(self, libc::IPPROTO_TCP, libc::TCP_KEEPIDLE, keepalive_secs(idle)?)
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux",))]
+    pub fn keepalive_idle(&self) -> io::Result<Duration> {
+        let raw: c_int = getsockopt(self, libc::IPPROTO_TCP, libc::TCP_KEEPIDLE)?;
+        Ok(Duration::from_secs(raw as u64))
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux",))]
+    pub fn set_keepalive_interval(&self, interval: Duration) -> io::Result<()> {
+        setsockopt(self, libc::IPPROTO_TCP, libc::TCP_KEEPINTVL, keepalive_secs(interval)?)
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux",))]
+    pub fn keepalive_interval(&self) -> io::Result<Duration> {
+        let raw: c_int = getsockopt(self, libc::IPPROTO_TCP, libc::TCP_KEEPINTVL)?;
+        Ok(Duration::from_secs(raw as u64))
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux",))]
+    pub fn set_keepalive_count(&self, count: u32) -> io::Result<()> {
+        let count = cmp::min(count, c_int::MAX as u32) as c_int;
+        setsockopt(self, libc::IPPROTO_TCP, libc::TCP_KEEPCNT, count)
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux",))]
+    pub fn keepalive_count(&self) -> io::Result<u32> {
+        let raw: c_int = getsockopt(self, libc::IPPROTO_TCP, libc::TCP_KEEPCNT)?;
+        Ok(raw as u32)
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux",))]
+    pub fn set_user_timeout(&self, timeout: Option<Duration>) -> io::Result<()> {
+        // A timeout of 0 selects the system default.
+        let millis = match timeout {
+            Some(dur) if dur.is_zero() => {
+                return Err(io::const_io_error!(
+                    io::ErrorKind::InvalidInput,
+                    "cannot set a 0 duration timeout",
+                ));
+            }
+            Some(dur) => cmp::max(cmp::min(dur.as_millis(), c_int::MAX as u128), 1) as c_int,
+            None => 0,
+        };
+        setsockopt(self, libc::IPPROTO_TCP, libc::TCP_USER_TIMEOUT, millis)
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux",))]
+    pub fn user_timeout(&self) -> io::Result<Option<Duration>> {
+        let raw: c_int = getsockopt(self, libc::IPPROTO_TCP, libc::TCP_USER_TIMEOUT)?;
+        Ok((raw != 0).then(|| Duration::from_millis(raw as u64)))
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux",))]
+    pub fn bind_device(&self, interface: Option<&[u8]>) -> io::Result<()> {
+        // An empty name removes a previous binding.
+        let interface = interface.unwrap_or(&[]);
+        cvt(unsafe {
+            libc::setsockopt(
+                self.as_raw_fd(),
+                libc::SOL_SOCKET,
+                libc::SO_BINDTODEVICE,
+                interface.as_ptr().cast(),
+                interface.len() as libc::socklen_t,
+            )
+        })?;
+        Ok(())
+    }
+
+    #[cfg(any(target_os = "android", target_os = "linux",))]
+    pub fn device(&self) -> io::Result<Option<Vec<u8>>> {
+        let mut buf = [0u8; libc::IFNAMSIZ];
+        let mut len = buf.len() as libc::socklen_t;
+        cvt(unsafe {
+            libc::getsockopt(
+                self.as_raw_fd(),
+                libc::SOL_SOCKET,
+                libc::SO_BINDTODEVICE,
+                buf.as_mut_ptr().cast(),
+                &mut len,
+            )
+        })?;
+        let name = &buf[..len as usize];
+        let name = name.split(|&b| b == 0).next().unwrap_or(name);
+        Ok((!name.is_empty()).then(|| name.to_vec()))
+    }
+
     #[cfg(any(target_os = "android", target_os = "linux",))]
     pub fn set_passcred(&self, passcred: bool) -> io::Result<()> {
         setsockopt(self, libc::SOL_SOCKET, libc::SO_PASSCRED, passcred as libc::c_int)
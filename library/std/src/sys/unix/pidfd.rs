@@ -0,0 +1,85 @@
+//! Process file descriptors, which refer to a process without being affected by the reuse of
+//! its pid after it has been reaped.
+
+use crate::io;
+use crate::mem;
+use crate::os::unix::io::{AsRawFd, FromRawFd, RawFd};
+use crate::sys::fd::FileDesc;
+use crate::sys::process::ExitStatus;
+use crate::sys::{cvt, cvt_r};
+use crate::sys_common::{AsInner, FromInner, IntoInner};
+
+#[derive(Debug)]
+pub struct PidFd(FileDesc);
+
+impl PidFd {
+    pub fn open(pid: u32) -> io::Result<PidFd> {
+        // pidfd_open sets CLOEXEC by default
+        let fd = cvt(unsafe { libc::syscall(libc::SYS_pidfd_open, pid as libc::pid_t, 0) })?;
+        // Safety: pidfd_open returns a new file descriptor that is not owned by anything else.
+        Ok(PidFd(unsafe { FileDesc::from_raw_fd(fd as RawFd) }))
+    }
+
+    pub fn kill(&self) -> io::Result<()> {
+        cvt(unsafe {
+            libc::syscall(
+                libc::SYS_pidfd_send_signal,
+                self.0.as_raw_fd(),
+                libc::SIGKILL,
+                crate::ptr::null::<libc::siginfo_t>(),
+                0,
+            )
+        })
+        .map(drop)
+    }
+
+    pub fn wait(&self) -> io::Result<ExitStatus> {
+        let mut siginfo: libc::siginfo_t = unsafe { mem::zeroed() };
+        cvt_r(|| unsafe {
+            libc::waitid(
+                libc::P_PIDFD,
+                self.0.as_raw_fd() as libc::id_t,
+                &mut siginfo,
+                libc::WEXITED,
+            )
+        })?;
+        Ok(ExitStatus::from_waitid_siginfo(siginfo))
+    }
+
+    pub fn try_wait(&self) -> io::Result<Option<ExitStatus>> {
+        let mut siginfo: libc::siginfo_t = unsafe { mem::zeroed() };
+        cvt(unsafe {
+            libc::waitid(
+                libc::P_PIDFD,
+                self.0.as_raw_fd() as libc::id_t,
+                &mut siginfo,
+                libc::WEXITED | libc::WNOHANG,
+            )
+        })?;
+        // With `WNOHANG`, `waitid` leaves `siginfo` zeroed if the process has not exited yet.
+        if unsafe { siginfo.si_pid() } == 0 {
+            Ok(None)
+        } else {
+            Ok(Some(ExitStatus::from_waitid_siginfo(siginfo)))
+        }
+    }
+}
+
+impl AsInner<FileDesc> for PidFd {
+    #[inline]
+    fn as_inner(&self) -> &FileDesc {
+        &self.0
+    }
+}
+
+impl FromInner<FileDesc> for PidFd {
+    fn from_inner(inner: FileDesc) -> PidFd {
+        PidFd(inner)
+    }
+}
+
+impl IntoInner<FileDesc> for PidFd {
+    fn into_inner(self) -> FileDesc {
+        self.0
+    }
+}
@@ -822,6 +822,15 @@ pub fn kill(&mut self) -> io::Result<()> {
         }
     }
 
+    pub fn send_signal(&self, signal: i32) -> io::Result<()> {
+        // See `kill` for why the signal is not sent once the process has been waited for.
+        if self.status.is_some() {
+            Ok(())
+        } else {
+            cvt(unsafe { libc::kill(self.pid, signal) }).map(drop)
+        }
+    }
+
     pub fn wait(&mut self) -> io::Result<ExitStatus> {
         use crate::sys::cvt_r;
         if let Some(status) = self.status {
@@ -866,6 +875,20 @@ pub fn new(status: c_int) -> ExitStatus {
         ExitStatus(status)
     }
 
+    /// Converts the result of `waitid` to the wait status that `waitpid` would have returned.
+    #[cfg(target_os = "linux")]
+    pub fn from_waitid_siginfo(siginfo: libc::siginfo_t) -> ExitStatus {
+        let status = unsafe { siginfo.si_status() };
+        match siginfo.si_code {
+            libc::CLD_EXITED => ExitStatus((status & 0xff) << 8),
+            libc::CLD_KILLED => ExitStatus(status),
+            libc::CLD_DUMPED => ExitStatus(status | 0x80),
+            libc::CLD_CONTINUED => ExitStatus(0xffff),
+            libc::CLD_STOPPED | libc::CLD_TRAPPED => ExitStatus(((status & 0xff) << 8) | 0x7f),
+            _ => unreachable!("waitid() should only return the above codes"),
+        }
+    }
+
     fn exited(&self) -> bool {
         libc::WIFEXITED(self.0)
     }
@@ -85,3 +85,51 @@ fn test_command_pidfd() {
         assert!(child.pidfd().is_ok())
     }
 }
+
+#[test]
+#[cfg(target_os = "linux")]
+fn test_pidfd_kill_and_wait() {
+    use crate::os::linux::process::{ChildExt, CommandExt, PidFd};
+    use crate::os::unix::process::ExitStatusExt;
+    use crate::process::Command;
+
+    // Opening a pidfd for ourselves only fails if the kernel does not support pidfds.
+    let Ok(ours) = PidFd::open(crate::process::id()) else { return };
+    // We are not a child of ourselves.
+    assert!(ours.try_wait().is_err());
+
+    let mut child = Command::new("sleep").arg("1000").create_pidfd(true).spawn().unwrap();
+    let Ok(pidfd) = child.take_pidfd() else {
+        // `clone3` is not available.
+        child.kill().unwrap();
+        child.wait().unwrap();
+        return;
+    };
+    assert_eq!(pidfd.try_wait().unwrap(), None);
+    pidfd.kill().unwrap();
+    let status = pidfd.wait().unwrap();
+    assert_eq!(status.signal(), Some(libc::SIGKILL));
+
+    // The child has been reaped, so it can neither be waited on nor signalled anymore.
+    assert!(pidfd.try_wait().is_err());
+    assert!(pidfd.kill().is_err());
+
+    // A pidfd opened for a child that has not been reaped yet can be used to reap it.
+    let child = Command::new("true").spawn().unwrap();
+    let pidfd = PidFd::open(child.id()).unwrap();
+    assert!(pidfd.wait().unwrap().success());
+}
+
+#[test]
+fn test_send_signal() {
+    use crate::os::unix::process::{ChildExt, ExitStatusExt};
+    use crate::process::Command;
+
+    let mut child = Command::new("sleep").arg("1000").spawn().unwrap();
+    child.send_signal(libc::SIGTERM).unwrap();
+    let status = child.wait().unwrap();
+    assert_eq!(status.signal(), Some(libc::SIGTERM));
+
+    // Once the child has been waited for, signals are no longer sent to its pid.
+    child.send_signal(libc::SIGTERM).unwrap();
+}
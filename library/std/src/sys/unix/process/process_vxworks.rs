@@ -152,6 +152,15 @@ pub fn kill(&mut self) -> io::Result<()> {
         }
     }
 
+    pub fn send_signal(&self, signal: i32) -> io::Result<()> {
+        // See `kill` for why the signal is not sent once the process has been waited for.
+        if self.status.is_some() {
+            Ok(())
+        } else {
+            cvt(unsafe { libc::kill(self.pid, signal) }).map(drop)
+        }
+    }
+
     pub fn wait(&mut self) -> io::Result<ExitStatus> {
         use crate::sys::cvt_r;
         if let Some(status) = self.status {
@@ -0,0 +1,168 @@
+//! Delivery of signals to the rest of the program through a self-pipe.
+//!
+//! Each `Signals` owns a non-blocking pipe and a slot in a fixed table that the signal handler
+//! walks, writing the number of the received signal to every pipe that registered interest in
+//! it. Only async-signal-safe operations are performed inside the handler: atomic loads and
+//! stores and `write`.
+
+#[cfg(test)]
+mod tests;
+
+use crate::hint;
+use crate::io;
+use crate::mem;
+use crate::os::unix::io::{AsFd, AsRawFd, BorrowedFd, RawFd};
+use crate::ptr;
+use crate::sync::atomic::{AtomicI32, AtomicU64, AtomicUsize, Ordering};
+use crate::sync::{Mutex, PoisonError};
+use crate::sys::fd::FileDesc;
+use crate::sys::os::{errno, set_errno};
+use crate::sys::pipe::anon_pipe;
+use crate::sys::{cvt, cvt_r};
+use crate::sys_common::IntoInner;
+
+use libc::c_int;
+
+/// The maximum number of `Signals` that can exist at the same time.
+const MAX_SLOTS: usize = 64;
+
+/// Signals whose handling would interfere with the program or the runtime: those that cannot be
+/// caught, and those raised synchronously by faults, which would be raised again as soon as the
+/// handler returns.
+const FORBIDDEN: [c_int; 6] =
+    [libc::SIGKILL, libc::SIGSTOP, libc::SIGILL, libc::SIGFPE, libc::SIGSEGV, libc::SIGBUS];
+
+struct Slot {
+    /// The write end of the pipe of the `Signals` owning this slot, or -1 if it is free.
+    fd: AtomicI32,
+    /// The signals the owner is interested in, as a bit set indexed by signal number.
+    mask: AtomicU64,
+}
+
+const FREE_SLOT: Slot = Slot { fd: AtomicI32::new(-1), mask: AtomicU64::new(0) };
+
+static SLOTS: [Slot; MAX_SLOTS] = [FREE_SLOT; MAX_SLOTS];
+
+/// The number of signal handlers currently walking `SLOTS`.
+static ACTIVE_HANDLERS: AtomicUsize = AtomicUsize::new(0);
+
+/// The signals `handler` has been installed for. Also serializes the allocation of slots.
+static INSTALLED: Mutex<u64> = Mutex::new(0);
+
+pub struct Signals {
+    read: FileDesc,
+    // Only written to by the signal handler, through `SLOTS`.
+    _write: FileDesc,
+    slot: usize,
+}
+
+impl Signals {
+    pub fn new(signals: &[i32]) -> io::Result<Signals> {
+        let mut mask = 0u64;
+        for &signal in signals {
+            if !(1..64).contains(&signal) || FORBIDDEN.contains(&signal) {
+                return Err(io::const_io_error!(
+                    io::ErrorKind::InvalidInput,
+                    "signal cannot be handled",
+                ));
+            }
+            mask |= 1 << signal;
+        }
+
+        let (read, write) = anon_pipe()?;
+        let (read, write): (FileDesc, FileDesc) = (read.into_inner(), write.into_inner());
+        read.set_nonblocking(true)?;
+        // A full pipe already holds plenty of signals to process, so dropping the ones that do
+        // not fit is preferable to blocking inside the handler.
+        write.set_nonblocking(true)?;
+
+        let mut installed = INSTALLED.lock().unwrap_or_else(PoisonError::into_inner);
+        let slot = SLOTS.iter().position(|slot| slot.fd.load(Ordering::Relaxed) == -1).ok_or(
+            io::const_io_error!(io::ErrorKind::Uncategorized, "too many signal handles exist"),
+        )?;
+        for signal in 1..64 {
+            if mask & (1 << signal) != 0 && *installed & (1 << signal) == 0 {
+                unsafe { install_handler(signal)? };
+                *installed |= 1 << signal;
+            }
+        }
+        SLOTS[slot].mask.store(mask, Ordering::Relaxed);
+        SLOTS[slot].fd.store(write.as_raw_fd(), Ordering::SeqCst);
+
+        Ok(Signals { read, _write: write, slot })
+    }
+
+    pub fn try_recv(&self) -> io::Result<Option<i32>> {
+        let mut signal = [0u8];
+        loop {
+            match self.read.read(&mut signal) {
+                Ok(_) => return Ok(Some(signal[0] as i32)),
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => return Ok(None),
+                Err(e) if e.is_interrupted() => {}
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    pub fn recv(&self) -> io::Result<i32> {
+        loop {
+            if let Some(signal) = self.try_recv()? {
+                return Ok(signal);
+            }
+            let mut pollfd =
+                libc::pollfd { fd: self.read.as_raw_fd(), events: libc::POLLIN, revents: 0 };
+            cvt_r(|| unsafe { libc::poll(&mut pollfd, 1, -1) })?;
+        }
+    }
+}
+
+impl Drop for Signals {
+    fn drop(&mut self) {
+        // Clear the mask before freeing the slot: once `fd` is -1, `Signals::new` may hand the slot
+        // out again, and its new mask must not be overwritten.
+        let _installed = INSTALLED.lock().unwrap_or_else(PoisonError::into_inner);
+        SLOTS[self.slot].mask.store(0, Ordering::Relaxed);
+        SLOTS[self.slot].fd.store(-1, Ordering::SeqCst);
+        // A handler running on another thread may have read the old file descriptor before it was
+        // cleared. Wait for it to finish, so that it cannot write to an unrelated file reusing the
+        // descriptor once the pipe is closed.
+        while ACTIVE_HANDLERS.load(Ordering::SeqCst) != 0 {
+            hint::spin_loop();
+        }
+    }
+}
+
+impl AsFd for Signals {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.read.as_fd()
+    }
+}
+
+impl AsRawFd for Signals {
+    #[inline]
+    fn as_raw_fd(&self) -> RawFd {
+        self.read.as_raw_fd()
+    }
+}
+
+unsafe fn install_handler(signal: c_int) -> io::Result<()> {
+    let mut action: libc::sigaction = mem::zeroed();
+    action.sa_sigaction = handler as libc::sighandler_t;
+    action.sa_flags = libc::SA_RESTART;
+    libc::sigemptyset(&mut action.sa_mask);
+    cvt(libc::sigaction(signal, &action, ptr::null_mut())).map(drop)
+}
+
+extern "C" fn handler(signal: c_int) {
+    let saved_errno = errno();
+    ACTIVE_HANDLERS.fetch_add(1, Ordering::SeqCst);
+    for slot in &SLOTS {
+        let fd = slot.fd.load(Ordering::SeqCst);
+        if fd != -1 && slot.mask.load(Ordering::Relaxed) & (1 << signal) != 0 {
+            let byte = signal as u8;
+            unsafe { libc::write(fd, ptr::addr_of!(byte).cast(), 1) };
+        }
+    }
+    ACTIVE_HANDLERS.fetch_sub(1, Ordering::SeqCst);
+    set_errno(saved_errno);
+}
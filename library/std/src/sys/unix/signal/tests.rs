@@ -0,0 +1,30 @@
+use super::*;
+
+#[test]
+fn receive_signals() {
+    let first = Signals::new(&[libc::SIGUSR1, libc::SIGUSR2]).unwrap();
+    let second = Signals::new(&[libc::SIGUSR2]).unwrap();
+    assert_eq!(first.try_recv().unwrap(), None);
+
+    unsafe { libc::raise(libc::SIGUSR1) };
+    unsafe { libc::raise(libc::SIGUSR2) };
+
+    assert_eq!(first.recv().unwrap(), libc::SIGUSR1);
+    assert_eq!(first.recv().unwrap(), libc::SIGUSR2);
+    assert_eq!(first.try_recv().unwrap(), None);
+    assert_eq!(second.try_recv().unwrap(), Some(libc::SIGUSR2));
+    assert_eq!(second.try_recv().unwrap(), None);
+
+    // A dropped handle frees its slot, and the remaining ones keep receiving signals.
+    drop(first);
+    unsafe { libc::raise(libc::SIGUSR2) };
+    assert_eq!(second.recv().unwrap(), libc::SIGUSR2);
+}
+
+#[test]
+fn forbidden_signals() {
+    for signal in [0, libc::SIGKILL, libc::SIGSTOP, libc::SIGSEGV, libc::SIGBUS, 64] {
+        let err = Signals::new(&[signal]).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+}
@@ -7,6 +7,8 @@
 use crate::sys::{os, stack_overflow};
 use crate::time::Duration;
 
+#[cfg(any(target_os = "linux", target_os = "android"))]
+use crate::sys::cvt;
 #[cfg(all(target_os = "linux", target_env = "gnu"))]
 use crate::sys::weak::dlsym;
 #[cfg(any(target_os = "solaris", target_os = "illumos", target_os = "nto"))]
@@ -223,6 +225,60 @@ pub fn set_name(_name: &CStr) {
         // Newlib, Emscripten, and VxWorks have no way to set a thread name.
     }
 
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn set_affinity(cpus: &[usize]) -> io::Result<()> {
+        let mut set: libc::cpu_set_t = unsafe { mem::zeroed() };
+        for &cpu in cpus {
+            if cpu >= CPU_SETSIZE {
+                return Err(io::const_io_error!(
+                    io::ErrorKind::InvalidInput,
+                    "CPU index is out of range",
+                ));
+            }
+            unsafe { libc::CPU_SET(cpu, &mut set) };
+        }
+        // A pid of 0 refers to the calling thread.
+        cvt(unsafe { libc::sched_setaffinity(0, mem::size_of_val(&set), &set) }).map(drop)
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn affinity() -> io::Result<Vec<usize>> {
+        let mut set: libc::cpu_set_t = unsafe { mem::zeroed() };
+        cvt(unsafe { libc::sched_getaffinity(0, mem::size_of_val(&set), &mut set) })?;
+        Ok((0..CPU_SETSIZE).filter(|&cpu| unsafe { libc::CPU_ISSET(cpu, &set) }).collect())
+    }
+
+    // The nice value is a per-thread attribute on Linux, addressed by thread ID rather than by
+    // the ID of the process.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    pub fn set_priority(nice: i32) -> io::Result<()> {
+        cvt(unsafe { libc::setpriority(libc::PRIO_PROCESS, gettid(), nice) }).map(drop)
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn priority() -> io::Result<i32> {
+        // -1 is a valid nice value, so errors can only be told apart through errno.
+        os::set_errno(0);
+        let nice = unsafe { libc::getpriority(libc::PRIO_PROCESS, gettid()) };
+        if nice == -1 && os::errno() != 0 { Err(io::Error::last_os_error()) } else { Ok(nice) }
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    pub fn set_affinity(_cpus: &[usize]) -> io::Result<()> {
+        Err(io::const_io_error!(
+            io::ErrorKind::Unsupported,
+            "setting the CPU affinity of threads is not supported on this platform",
+        ))
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    pub fn set_priority(_nice: i32) -> io::Result<()> {
+        Err(io::const_io_error!(
+            io::ErrorKind::Unsupported,
+            "setting the priority of threads is not supported on this platform",
+        ))
+    }
+
     #[cfg(not(target_os = "espidf"))]
     pub fn sleep(dur: Duration) {
         let mut secs = dur.as_secs();
@@ -288,6 +344,16 @@ fn drop(&mut self) {
     }
 }
 
+/// The number of CPUs a `cpu_set_t` can describe.
+#[cfg(any(target_os = "linux", target_os = "android"))]
+const CPU_SETSIZE: usize = mem::size_of::<libc::cpu_set_t>() * 8;
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn gettid() -> libc::id_t {
+    // glibc only provides a `gettid` wrapper since version 2.30.
+    unsafe { libc::syscall(libc::SYS_gettid) as libc::id_t }
+}
+
 #[cfg(any(
     target_os = "linux",
     target_os = "macos",
@@ -6,6 +6,8 @@
 use crate::sys::time::SystemTime;
 use crate::sys::unsupported;
 
+pub use crate::sys_common::fs::Dir;
+
 pub struct File(!);
 
 pub struct FileAttr(!);
@@ -242,6 +244,26 @@ pub fn duplicate(&self) -> io::Result<File> {
         self.0
     }
 
+    pub fn lock(&self) -> io::Result<()> {
+        self.0
+    }
+
+    pub fn lock_shared(&self) -> io::Result<()> {
+        self.0
+    }
+
+    pub fn try_lock(&self) -> io::Result<bool> {
+        self.0
+    }
+
+    pub fn try_lock_shared(&self) -> io::Result<bool> {
+        self.0
+    }
+
+    pub fn unlock(&self) -> io::Result<()> {
+        self.0
+    }
+
     pub fn set_permissions(&self, _perm: FilePermissions) -> io::Result<()> {
         self.0
     }
@@ -17,7 +17,7 @@
 use crate::sys::unsupported;
 use crate::sys_common::{AsInner, FromInner, IntoInner};
 
-pub use crate::sys_common::fs::try_exists;
+pub use crate::sys_common::fs::{try_exists, Dir};
 
 pub struct File {
     fd: WasiFd,
@@ -470,6 +470,26 @@ pub fn duplicate(&self) -> io::Result<File> {
         unsupported()
     }
 
+    pub fn lock(&self) -> io::Result<()> {
+        unsupported()
+    }
+
+    pub fn lock_shared(&self) -> io::Result<()> {
+        unsupported()
+    }
+
+    pub fn try_lock(&self) -> io::Result<bool> {
+        unsupported()
+    }
+
+    pub fn try_lock_shared(&self) -> io::Result<bool> {
+        unsupported()
+    }
+
+    pub fn unlock(&self) -> io::Result<()> {
+        unsupported()
+    }
+
     pub fn set_permissions(&self, _perm: FilePermissions) -> io::Result<()> {
         // Permissions haven't been fully figured out in wasi yet, so this is
         // likely temporary
@@ -21,6 +21,8 @@
 use super::path::maybe_verbatim;
 use super::to_u16s;
 
+pub use crate::sys_common::fs::Dir;
+
 pub struct File {
     handle: Handle,
 }
@@ -482,6 +484,59 @@ pub fn duplicate(&self) -> io::Result<File> {
         Ok(Self { handle: self.handle.try_clone()? })
     }
 
+    pub fn lock(&self) -> io::Result<()> {
+        self.lock_file_ex(c::LOCKFILE_EXCLUSIVE_LOCK)?;
+        Ok(())
+    }
+
+    pub fn lock_shared(&self) -> io::Result<()> {
+        self.lock_file_ex(0)?;
+        Ok(())
+    }
+
+    pub fn try_lock(&self) -> io::Result<bool> {
+        self.try_lock_file_ex(c::LOCKFILE_EXCLUSIVE_LOCK)
+    }
+
+    pub fn try_lock_shared(&self) -> io::Result<bool> {
+        self.try_lock_file_ex(0)
+    }
+
+    fn try_lock_file_ex(&self, flags: c::LOCK_FILE_FLAGS) -> io::Result<bool> {
+        match self.lock_file_ex(flags | c::LOCKFILE_FAIL_IMMEDIATELY) {
+            Ok(()) => Ok(true),
+            Err(err)
+                if err.raw_os_error() == Some(c::ERROR_IO_PENDING as i32)
+                    || err.raw_os_error() == Some(c::ERROR_LOCK_VIOLATION as i32) =>
+            {
+                Ok(false)
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Locks the whole file, including any data appended to it later.
+    fn lock_file_ex(&self, flags: c::LOCK_FILE_FLAGS) -> io::Result<()> {
+        cvt(unsafe {
+            let mut overlapped = mem::zeroed();
+            c::LockFileEx(
+                self.handle.as_raw_handle(),
+                flags,
+                0,
+                u32::MAX,
+                u32::MAX,
+                &mut overlapped,
+            )
+        })?;
+        Ok(())
+    }
+
+    pub fn unlock(&self) -> io::Result<()> {
+        // Shared and exclusive locks are released the same way.
+        cvt(unsafe { c::UnlockFile(self.handle.as_raw_handle(), 0, 0, u32::MAX, u32::MAX) })?;
+        Ok(())
+    }
+
     // NB: returned pointer is derived from `space`, and has provenance to
     // match. A raw pointer is returned rather than a reference in order to
     // avoid narrowing provenance to the actual `REPARSE_DATA_BUFFER`.
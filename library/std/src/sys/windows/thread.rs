@@ -70,6 +70,20 @@ pub fn set_name(name: &CStr) {
         };
     }
 
+    pub fn set_affinity(_cpus: &[usize]) -> io::Result<()> {
+        Err(io::const_io_error!(
+            io::ErrorKind::Unsupported,
+            "operation not supported on this platform",
+        ))
+    }
+
+    pub fn set_priority(_nice: i32) -> io::Result<()> {
+        Err(io::const_io_error!(
+            io::ErrorKind::Unsupported,
+            "operation not supported on this platform",
+        ))
+    }
+
     pub fn join(self) {
         let rc = unsafe { c::WaitForSingleObject(self.handle.as_raw_handle(), c::INFINITE) };
         if rc == c::WAIT_FAILED {
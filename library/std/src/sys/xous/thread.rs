@@ -109,6 +109,14 @@ pub fn set_name(_name: &CStr) {
         // nope
     }
 
+    pub fn set_affinity(_cpus: &[usize]) -> io::Result<()> {
+        super::unsupported()
+    }
+
+    pub fn set_priority(_nice: i32) -> io::Result<()> {
+        super::unsupported()
+    }
+
     pub fn sleep(dur: Duration) {
         // Because the sleep server works on units of `usized milliseconds`, split
         // the messages up into these chunks. This means we may run into issues
@@ -2,7 +2,8 @@
 
 use crate::fs;
 use crate::io::{self, Error, ErrorKind};
-use crate::path::Path;
+use crate::path::{Path, PathBuf};
+use crate::sys::fs as fs_imp;
 
 pub(crate) const NOT_FILE_ERROR: Error = io::const_io_error!(
     ErrorKind::InvalidInput,
@@ -49,3 +50,58 @@ pub fn try_exists(path: &Path) -> io::Result<bool> {
         Err(error) => Err(error),
     }
 }
+
+/// Fallback for platforms that cannot perform operations relative to an open directory.
+///
+/// The operations are performed on the given path joined to the path the directory was opened
+/// with, so unlike the native implementations they are not protected against the directory being
+/// moved or replaced in the meantime.
+#[derive(Debug)]
+pub struct Dir {
+    path: PathBuf,
+}
+
+impl Dir {
+    pub fn open(path: &Path) -> io::Result<Dir> {
+        if !fs_imp::stat(path)?.file_type().is_dir() {
+            return Err(io::const_io_error!(ErrorKind::NotADirectory, "not a directory"));
+        }
+        Ok(Dir { path: path.to_path_buf() })
+    }
+
+    pub fn open_dir(&self, path: &Path) -> io::Result<Dir> {
+        Dir::open(&self.path.join(path))
+    }
+
+    pub fn open_file(&self, path: &Path, opts: &fs_imp::OpenOptions) -> io::Result<fs_imp::File> {
+        fs_imp::File::open(&self.path.join(path), opts)
+    }
+
+    pub fn read_dir(&self, path: &Path) -> io::Result<fs_imp::ReadDir> {
+        fs_imp::readdir(&self.path.join(path))
+    }
+
+    pub fn create_dir(&self, path: &Path) -> io::Result<()> {
+        fs_imp::DirBuilder::new().mkdir(&self.path.join(path))
+    }
+
+    pub fn remove_file(&self, path: &Path) -> io::Result<()> {
+        fs_imp::unlink(&self.path.join(path))
+    }
+
+    pub fn remove_dir(&self, path: &Path) -> io::Result<()> {
+        fs_imp::rmdir(&self.path.join(path))
+    }
+
+    pub fn rename(&self, from: &Path, to_dir: &Dir, to: &Path) -> io::Result<()> {
+        fs_imp::rename(&self.path.join(from), &to_dir.path.join(to))
+    }
+
+    pub fn metadata(&self, path: &Path) -> io::Result<fs_imp::FileAttr> {
+        fs_imp::stat(&self.path.join(path))
+    }
+
+    pub fn symlink_metadata(&self, path: &Path) -> io::Result<fs_imp::FileAttr> {
+        fs_imp::lstat(&self.path.join(path))
+    }
+}
@@ -172,6 +172,7 @@
 use crate::pin::Pin;
 use crate::ptr::addr_of_mut;
 use crate::str;
+use crate::sync::mpsc;
 use crate::sync::Arc;
 use crate::sys::thread as imp;
 use crate::sys_common::thread;
@@ -220,10 +221,12 @@ pub mod local_impl {
 ///
 /// Methods can be chained on it in order to configure it.
 ///
-/// The two configurations available are:
+/// The configurations available are:
 ///
 /// - [`name`]: specifies an [associated name for the thread][naming-threads]
 /// - [`stack_size`]: specifies the [desired stack size for the thread][stack-size]
+/// - [`affinity`]: restricts the CPUs the thread may run on
+/// - [`priority`]: specifies the scheduling priority of the thread
 ///
 /// The [`spawn`] method will take ownership of the builder and create an
 /// [`io::Result`] to the thread handle with the given configuration.
@@ -251,6 +254,8 @@ pub mod local_impl {
 ///
 /// [`stack_size`]: Builder::stack_size
 /// [`name`]: Builder::name
+/// [`affinity`]: Builder::affinity
+/// [`priority`]: Builder::priority
 /// [`spawn`]: Builder::spawn
 /// [`thread::spawn`]: spawn
 /// [`io::Result`]: crate::io::Result
@@ -265,6 +270,10 @@ pub struct Builder {
     name: Option<String>,
     // The size of the stack for the spawned thread in bytes
     stack_size: Option<usize>,
+    // The indices of the CPUs the spawned thread may run on
+    affinity: Option<Vec<usize>>,
+    // The nice value of the spawned thread
+    priority: Option<i32>,
 }
 
 impl Builder {
@@ -288,7 +297,7 @@ impl Builder {
     /// ```
     #[stable(feature = "rust1", since = "1.0.0")]
     pub fn new() -> Builder {
-        Builder { name: None, stack_size: None }
+        Builder { name: None, stack_size: None, affinity: None, priority: None }
     }
 
     /// Names the thread-to-be. Currently the name is used for identification
@@ -344,6 +353,84 @@ pub fn stack_size(mut self, size: usize) -> Builder {
         self
     }
 
+    /// Restricts the new thread to running on the CPUs with the given indices.
+    ///
+    /// The new thread applies the affinity to itself before running its
+    /// closure, and [`spawn`] waits for it to do so. If the affinity cannot be
+    /// set, the closure is dropped without being run and [`spawn`] returns the
+    /// error.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This corresponds to `sched_setaffinity` on Linux and Android. An index
+    /// that does not fit the CPU set of the platform results in an
+    /// [`InvalidInput`] error, as does an empty list of CPUs. Spawning a thread
+    /// with an affinity fails with an [`Unsupported`] error on other platforms.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(thread_affinity)]
+    /// use std::thread;
+    ///
+    /// let builder = thread::Builder::new().affinity(&[0, 1]);
+    ///
+    /// let handler = builder.spawn(|| {
+    ///     // thread code, running on CPU 0 or 1
+    /// }).unwrap();
+    ///
+    /// handler.join().unwrap();
+    /// ```
+    ///
+    /// [`spawn`]: Builder::spawn
+    /// [`InvalidInput`]: io::ErrorKind::InvalidInput
+    /// [`Unsupported`]: io::ErrorKind::Unsupported
+    #[unstable(feature = "thread_affinity", issue = "none")]
+    pub fn affinity(mut self, cpus: &[usize]) -> Builder {
+        self.affinity = Some(cpus.to_vec());
+        self
+    }
+
+    /// Sets the scheduling priority of the new thread as a nice value, where
+    /// lower values mean a higher priority.
+    ///
+    /// Threads otherwise inherit the nice value of the thread spawning them.
+    /// Like the [`affinity`], the priority is applied by the new thread before
+    /// running its closure, and [`spawn`] returns the error if it cannot be
+    /// set.
+    ///
+    /// # Platform-specific behavior
+    ///
+    /// This corresponds to `setpriority` on Linux and Android, where nice
+    /// values range from -20 to 19 and values outside of this range are
+    /// clamped. Lowering the nice value below the current one usually requires
+    /// the `CAP_SYS_NICE` capability. Spawning a thread with a priority fails
+    /// with an [`Unsupported`] error on other platforms.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// #![feature(thread_priority)]
+    /// use std::thread;
+    ///
+    /// let builder = thread::Builder::new().priority(10);
+    ///
+    /// let handler = builder.spawn(|| {
+    ///     // background work
+    /// }).unwrap();
+    ///
+    /// handler.join().unwrap();
+    /// ```
+    ///
+    /// [`affinity`]: Builder::affinity
+    /// [`spawn`]: Builder::spawn
+    /// [`Unsupported`]: io::ErrorKind::Unsupported
+    #[unstable(feature = "thread_priority", issue = "none")]
+    pub fn priority(mut self, nice: i32) -> Builder {
+        self.priority = Some(nice);
+        self
+    }
+
     /// Spawns a new thread by taking ownership of the `Builder`, and returns an
     /// [`io::Result`] to its [`JoinHandle`].
     ///
@@ -468,7 +555,7 @@ unsafe fn spawn_unchecked_<'a, 'scope, F, T>(
         T: Send + 'a,
         'scope: 'a,
     {
-        let Builder { name, stack_size } = self;
+        let Builder { name, stack_size, affinity, priority } = self;
 
         let stack_size = stack_size.unwrap_or_else(thread::min_stack);
 
@@ -511,12 +598,35 @@ fn drop(&mut self) {
             }
         }
 
+        // The scheduling settings can only be applied by the new thread itself, which reports
+        // the outcome back so that failures are returned from `spawn`.
+        let (scheduled_tx, scheduled_rx) =
+            (affinity.is_some() || priority.is_some()).then(|| mpsc::sync_channel(1)).unzip();
+
         let f = MaybeDangling::new(f);
         let main = move || {
             if let Some(name) = their_thread.cname() {
                 imp::Thread::set_name(name);
             }
 
+            if let Some(scheduled_tx) = scheduled_tx {
+                let scheduled = (|| -> io::Result<()> {
+                    if let Some(cpus) = &affinity {
+                        imp::Thread::set_affinity(cpus)?;
+                    }
+                    if let Some(nice) = priority {
+                        imp::Thread::set_priority(nice)?;
+                    }
+                    Ok(())
+                })();
+                let failed = scheduled.is_err();
+                let _ = scheduled_tx.send(scheduled);
+                if failed {
+                    // `f` is dropped without being run.
+                    return;
+                }
+            }
+
             crate::io::set_output_capture(output_capture);
 
             // SAFETY: we constructed `f` initialized.
@@ -545,31 +655,36 @@ fn drop(&mut self) {
             scope_data.increment_num_running_threads();
         }
 
-        Ok(JoinInner {
-            // SAFETY:
-            //
-            // `imp::Thread::new` takes a closure with a `'static` lifetime, since it's passed
-            // through FFI or otherwise used with low-level threading primitives that have no
-            // notion of or way to enforce lifetimes.
-            //
-            // As mentioned in the `Safety` section of this function's documentation, the caller of
-            // this function needs to guarantee that the passed-in lifetime is sufficiently long
-            // for the lifetime of the thread.
-            //
-            // Similarly, the `sys` implementation must guarantee that no references to the closure
-            // exist after the thread has terminated, which is signaled by `Thread::join`
-            // returning.
-            native: unsafe {
-                imp::Thread::new(
-                    stack_size,
-                    mem::transmute::<Box<dyn FnOnce() + 'a>, Box<dyn FnOnce() + 'static>>(
-                        Box::new(main),
-                    ),
-                )?
-            },
-            thread: my_thread,
-            packet: my_packet,
-        })
+        // SAFETY:
+        //
+        // `imp::Thread::new` takes a closure with a `'static` lifetime, since it's passed
+        // through FFI or otherwise used with low-level threading primitives that have no
+        // notion of or way to enforce lifetimes.
+        //
+        // As mentioned in the `Safety` section of this function's documentation, the caller of
+        // this function needs to guarantee that the passed-in lifetime is sufficiently long
+        // for the lifetime of the thread.
+        //
+        // Similarly, the `sys` implementation must guarantee that no references to the closure
+        // exist after the thread has terminated, which is signaled by `Thread::join`
+        // returning.
+        let native = unsafe {
+            imp::Thread::new(
+                stack_size,
+                mem::transmute::<Box<dyn FnOnce() + 'a>, Box<dyn FnOnce() + 'static>>(Box::new(
+                    main,
+                )),
+            )?
+        };
+
+        if let Some(Ok(Err(e))) = scheduled_rx.map(|rx| rx.recv()) {
+            // The thread is about to exit without running `f`; wait for it, so that nothing
+            // borrowed by `f` outlives this call.
+            native.join();
+            return Err(e);
+        }
+
+        Ok(JoinInner { native, thread: my_thread, packet: my_packet })
     }
 }
 
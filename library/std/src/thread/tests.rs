@@ -75,6 +75,51 @@ fn test_invalid_named_thread() {
     let _ = Builder::new().name("ada l\0velace".to_string()).spawn(|| {});
 }
 
+#[cfg(target_os = "linux")]
+#[test]
+fn test_affinity() {
+    use crate::os::linux::thread::affinity;
+
+    let cpus = affinity().unwrap();
+    let cpu = *cpus.last().unwrap();
+    let pinned = Builder::new().affinity(&[cpu]).spawn(affinity).unwrap().join().unwrap();
+    assert_eq!(pinned.unwrap(), [cpu]);
+    // The spawning thread is left alone.
+    assert_eq!(affinity().unwrap(), cpus);
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn test_priority() {
+    use crate::os::linux::thread::priority;
+
+    let nice = priority().unwrap();
+    let lowered = (nice + 1).min(19);
+    let spawned = Builder::new().priority(lowered).spawn(priority).unwrap().join().unwrap();
+    assert_eq!(spawned.unwrap(), lowered);
+    assert_eq!(priority().unwrap(), nice);
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+#[test]
+fn test_invalid_affinity() {
+    use crate::io::ErrorKind;
+
+    let ran = Arc::new(AtomicBool::new(false));
+    let ran2 = ran.clone();
+    let err = Builder::new()
+        .affinity(&[usize::MAX])
+        .spawn(move || ran2.store(true, Ordering::SeqCst))
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+    assert!(!ran.load(Ordering::SeqCst));
+    // The closure was dropped by the time `spawn` returned.
+    assert_eq!(Arc::strong_count(&ran), 1);
+
+    let err = Builder::new().affinity(&[]).spawn(|| {}).unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidInput);
+}
+
 #[test]
 fn test_run_basic() {
     let (tx, rx) = channel();
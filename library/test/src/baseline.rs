@@ -0,0 +1,349 @@
+//! Saving benchmark results as named baselines, and comparing later runs against them.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use super::bench::{fmt_bench_samples, BenchSamples};
+use super::event::CompletedTest;
+use super::formatters::EscapedString;
+use super::stats;
+use super::test_result::TestResult;
+
+#[cfg(test)]
+mod tests;
+
+/// The p-value below which a change is considered significant.
+const SIGNIFICANCE_LEVEL: f64 = 0.05;
+
+/// Options for saving benchmark results and comparing them against earlier ones.
+#[derive(Clone, Debug)]
+pub struct BaselineOptions {
+    /// The directory baselines are stored in.
+    pub dir: PathBuf,
+    /// The name of the baseline to save the benchmark results as.
+    pub save: Option<String>,
+    /// The name of the baseline to compare the benchmark results against.
+    pub compare: Option<String>,
+    /// The slowdown, in percent, above which a significant change fails the benchmark.
+    pub regression_threshold: f64,
+}
+
+impl Default for BaselineOptions {
+    fn default() -> BaselineOptions {
+        BaselineOptions {
+            dir: PathBuf::from("target/bench-baselines"),
+            save: None,
+            compare: None,
+            regression_threshold: 2.0,
+        }
+    }
+}
+
+/// How the results of a benchmark compare to its baseline.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Comparison {
+    /// The change of the mean time per iteration, in percent.
+    pub change: f64,
+    /// The p-value of the change, as computed by Welch's t-test.
+    pub p_value: f64,
+    pub verdict: Verdict,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Verdict {
+    /// The change is not significant, or smaller than the regression threshold.
+    NoChange,
+    Improved,
+    Regressed,
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let verdict = match self.verdict {
+            Verdict::NoChange => "no change",
+            Verdict::Improved => "improved",
+            Verdict::Regressed => "regressed",
+        };
+        write!(f, "{:+.2}% vs baseline, p = {:.3}: {verdict}", self.change, self.p_value)
+    }
+}
+
+/// Benchmark results, by benchmark name.
+#[derive(Debug, Default)]
+pub struct Baseline {
+    benches: BTreeMap<String, Entry>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Entry {
+    samples: usize,
+    mean: f64,
+    median: f64,
+    variance: f64,
+}
+
+impl Baseline {
+    pub fn load(dir: &Path, name: &str) -> io::Result<Baseline> {
+        let path = baseline_path(dir, name);
+        let contents = fs::read_to_string(&path).map_err(|e| {
+            io::Error::new(e.kind(), format!("failed to read baseline {}: {e}", path.display()))
+        })?;
+        Baseline::from_json(&contents).map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("failed to parse baseline {}: {e}", path.display()),
+            )
+        })
+    }
+
+    pub fn save(&self, dir: &Path, name: &str) -> io::Result<()> {
+        fs::create_dir_all(dir)?;
+        fs::write(baseline_path(dir, name), self.to_json())
+    }
+
+    pub fn insert(&mut self, name: &str, bs: &BenchSamples) {
+        // Benchmarks that did not call `iter` have nothing to compare.
+        if bs.samples < 2 {
+            return;
+        }
+        let summ = &bs.ns_iter_summ;
+        let entry =
+            Entry { samples: bs.samples, mean: summ.mean, median: summ.median, variance: summ.var };
+        self.benches.insert(name.to_owned(), entry);
+    }
+
+    pub fn compare(
+        &self,
+        name: &str,
+        bs: &BenchSamples,
+        regression_threshold: f64,
+    ) -> Option<Comparison> {
+        let old = self.benches.get(name)?;
+        if bs.samples < 2 {
+            return None;
+        }
+        let new = &bs.ns_iter_summ;
+        let change = (new.mean - old.mean) / old.mean * 100.0;
+        let p_value =
+            stats::welch_t_test(new.mean, new.var, bs.samples, old.mean, old.variance, old.samples);
+        let verdict = if p_value >= SIGNIFICANCE_LEVEL || change.abs() <= regression_threshold {
+            Verdict::NoChange
+        } else if change > 0.0 {
+            Verdict::Regressed
+        } else {
+            Verdict::Improved
+        };
+        Some(Comparison { change, p_value, verdict })
+    }
+
+    /// Compares the results of a benchmark against this baseline, failing it if it regressed.
+    pub fn compare_result(&self, completed_test: &mut CompletedTest, regression_threshold: f64) {
+        let TestResult::TrBench(bs) = &mut completed_test.result else {
+            return;
+        };
+        let name = completed_test.desc.name.as_slice();
+        bs.comparison = self.compare(name, bs, regression_threshold);
+        if let Some(Comparison { verdict: Verdict::Regressed, .. }) = bs.comparison {
+            let message = format!(
+                "benchmark regressed by more than {regression_threshold}%: {}",
+                fmt_bench_samples(bs).trim_start(),
+            );
+            completed_test.result = TestResult::TrFailedMsg(message);
+        }
+    }
+
+    fn to_json(&self) -> String {
+        let mut json = String::from("{\n  \"benchmarks\": {");
+        for (i, (name, entry)) in self.benches.iter().enumerate() {
+            let Entry { samples, mean, median, variance } = entry;
+            json.push_str(if i == 0 { "\n" } else { ",\n" });
+            json.push_str(&format!(
+                "    \"{}\": {{ \"samples\": {samples}, \"mean\": {mean}, \
+                 \"median\": {median}, \"variance\": {variance} }}",
+                EscapedString(name),
+            ));
+        }
+        json.push_str("\n  }\n}\n");
+        json
+    }
+
+    fn from_json(json: &str) -> Result<Baseline, String> {
+        let mut parser = Parser { input: json.as_bytes(), pos: 0 };
+        let value = parser.parse_value()?;
+        parser.skip_whitespace();
+        if parser.pos != parser.input.len() {
+            return Err(parser.error("trailing characters"));
+        }
+
+        let mut baseline = Baseline::default();
+        let benches = value.field("benchmarks")?.as_object()?;
+        for (name, entry) in benches {
+            let number = |field| entry.field(field)?.as_number();
+            let samples = number("samples")?;
+            if samples < 2.0 || samples.fract() != 0.0 {
+                return Err(format!("invalid sample count for benchmark `{name}`"));
+            }
+            let entry = Entry {
+                samples: samples as usize,
+                mean: number("mean")?,
+                median: number("median")?,
+                variance: number("variance")?,
+            };
+            baseline.benches.insert(name.clone(), entry);
+        }
+        Ok(baseline)
+    }
+}
+
+fn baseline_path(dir: &Path, name: &str) -> PathBuf {
+    dir.join(format!("{name}.json"))
+}
+
+/// The subset of JSON values baselines are made of.
+enum Json {
+    Object(Vec<(String, Json)>),
+    Number(f64),
+}
+
+impl Json {
+    fn as_object(&self) -> Result<&[(String, Json)], String> {
+        match self {
+            Json::Object(fields) => Ok(fields),
+            _ => Err("expected an object".to_string()),
+        }
+    }
+
+    fn as_number(&self) -> Result<f64, String> {
+        match *self {
+            Json::Number(n) => Ok(n),
+            _ => Err("expected a number".to_string()),
+        }
+    }
+
+    fn field(&self, name: &str) -> Result<&Json, String> {
+        self.as_object()?
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value)
+            .ok_or_else(|| format!("missing field `{name}`"))
+    }
+}
+
+struct Parser<'a> {
+    input: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, msg: &str) -> String {
+        format!("{msg} at byte {}", self.pos)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\t' | b'\n' | b'\r') = self.input.get(self.pos) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), String> {
+        self.skip_whitespace();
+        if self.input.get(self.pos) != Some(&byte) {
+            return Err(self.error(&format!("expected `{}`", byte as char)));
+        }
+        self.pos += 1;
+        Ok(())
+    }
+
+    fn parse_value(&mut self) -> Result<Json, String> {
+        self.skip_whitespace();
+        match self.input.get(self.pos) {
+            Some(b'{') => self.parse_object(),
+            Some(b'-' | b'0'..=b'9') => self.parse_number(),
+            _ => Err(self.error("expected an object or a number")),
+        }
+    }
+
+    fn parse_object(&mut self) -> Result<Json, String> {
+        self.expect(b'{')?;
+        let mut fields = Vec::new();
+        self.skip_whitespace();
+        if self.input.get(self.pos) == Some(&b'}') {
+            self.pos += 1;
+            return Ok(Json::Object(fields));
+        }
+        loop {
+            self.skip_whitespace();
+            let key = self.parse_string()?;
+            self.expect(b':')?;
+            fields.push((key, self.parse_value()?));
+            self.skip_whitespace();
+            match self.input.get(self.pos) {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Ok(Json::Object(fields));
+                }
+                _ => return Err(self.error("expected `,` or `}`")),
+            }
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, String> {
+        self.expect(b'"')?;
+        let mut bytes = Vec::new();
+        loop {
+            let Some(&byte) = self.input.get(self.pos) else {
+                return Err(self.error("unterminated string"));
+            };
+            self.pos += 1;
+            match byte {
+                b'"' => break,
+                b'\\' => {
+                    let Some(&escape) = self.input.get(self.pos) else {
+                        return Err(self.error("unterminated string"));
+                    };
+                    self.pos += 1;
+                    let unescaped = match escape {
+                        b'"' => '"',
+                        b'\\' => '\\',
+                        b'/' => '/',
+                        b'b' => '\x08',
+                        b'f' => '\x0c',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'u' => {
+                            let hex = self.input.get(self.pos..self.pos + 4);
+                            let code = hex
+                                .and_then(|hex| std::str::from_utf8(hex).ok())
+                                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                                .and_then(char::from_u32)
+                                .ok_or_else(|| self.error("invalid unicode escape"))?;
+                            self.pos += 4;
+                            code
+                        }
+                        _ => return Err(self.error("invalid escape")),
+                    };
+                    bytes.extend_from_slice(unescaped.encode_utf8(&mut [0; 4]).as_bytes());
+                }
+                _ => bytes.push(byte),
+            }
+        }
+        String::from_utf8(bytes).map_err(|_| self.error("invalid UTF-8 in string"))
+    }
+
+    fn parse_number(&mut self) -> Result<Json, String> {
+        let start = self.pos;
+        while let Some(b'-' | b'+' | b'.' | b'e' | b'E' | b'0'..=b'9') = self.input.get(self.pos) {
+            self.pos += 1;
+        }
+        std::str::from_utf8(&self.input[start..self.pos])
+            .ok()
+            .and_then(|number| number.parse().ok())
+            .map(Json::Number)
+            .ok_or_else(|| self.error("invalid number"))
+    }
+}
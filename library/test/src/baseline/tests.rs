@@ -0,0 +1,108 @@
+use super::*;
+
+use crate::stats::{Outliers, Summary};
+use crate::test::TestDesc;
+use crate::types::{TestId, TestName, TestType};
+use crate::ShouldPanic;
+
+fn bench_samples(mean: f64) -> BenchSamples {
+    // Evenly spread around `mean`, with a standard deviation of about 1% of it.
+    let samples: Vec<f64> = (0..50).map(|i| mean * (1.0 + (i as f64 - 24.5) / 1500.0)).collect();
+    BenchSamples {
+        ns_iter_summ: Summary::new(&samples),
+        mb_s: 0,
+        samples: samples.len(),
+        outliers: Outliers::default(),
+        comparison: None,
+    }
+}
+
+fn completed_bench(name: &'static str, bs: BenchSamples) -> CompletedTest {
+    let desc = TestDesc {
+        name: TestName::StaticTestName(name),
+        ignore: false,
+        ignore_message: None,
+        source_file: "",
+        start_line: 0,
+        start_col: 0,
+        end_line: 0,
+        end_col: 0,
+        should_panic: ShouldPanic::No,
+        compile_fail: false,
+        no_run: false,
+        test_type: TestType::Unknown,
+        timeout: None,
+    };
+    CompletedTest::new(TestId(0), desc, TestResult::TrBench(bs), None, Vec::new())
+}
+
+#[test]
+fn json_roundtrip() {
+    let mut baseline = Baseline::default();
+    baseline.insert("plain", &bench_samples(1000.0));
+    baseline.insert("needs \"escaping\"\n\u{7f} ünïcode", &bench_samples(0.25));
+
+    let parsed = Baseline::from_json(&baseline.to_json()).unwrap();
+    assert_eq!(parsed.benches, baseline.benches);
+}
+
+#[test]
+fn json_errors() {
+    assert!(Baseline::from_json("").is_err());
+    assert!(Baseline::from_json("{}").is_err());
+    assert!(Baseline::from_json(r#"{ "benchmarks": {} } trailing"#).is_err());
+    assert!(Baseline::from_json(r#"{ "benchmarks": { "a": { "samples": 50 } } }"#).is_err());
+    assert!(Baseline::from_json(r#"{ "benchmarks": { "a": 1 } }"#).is_err());
+    assert!(
+        Baseline::from_json(
+            r#"{ "benchmarks": { "a": { "samples": 1, "mean": 1, "median": 1, "variance": 0 } } }"#
+        )
+        .is_err()
+    );
+
+    let baseline = Baseline::from_json(
+        r#"{"benchmarks":{"a":{"samples":50,"mean":1.5e2,"median":-0,"variance":2}}}"#,
+    )
+    .unwrap();
+    let entry = baseline.benches["a"];
+    assert_eq!(entry, Entry { samples: 50, mean: 150.0, median: 0.0, variance: 2.0 });
+}
+
+#[test]
+fn compare_against_baseline() {
+    let mut baseline = Baseline::default();
+    baseline.insert("bench", &bench_samples(1000.0));
+
+    let comparison = baseline.compare("bench", &bench_samples(1001.0), 2.0).unwrap();
+    assert_eq!(comparison.verdict, Verdict::NoChange);
+
+    let comparison = baseline.compare("bench", &bench_samples(1100.0), 2.0).unwrap();
+    assert_eq!(comparison.verdict, Verdict::Regressed);
+    assert!((comparison.change - 10.0).abs() < 1e-9);
+    assert!(comparison.p_value < SIGNIFICANCE_LEVEL);
+
+    let comparison = baseline.compare("bench", &bench_samples(900.0), 2.0).unwrap();
+    assert_eq!(comparison.verdict, Verdict::Improved);
+
+    // Significant, but below the threshold.
+    let comparison = baseline.compare("bench", &bench_samples(1010.0), 2.0).unwrap();
+    assert!(comparison.p_value < SIGNIFICANCE_LEVEL);
+    assert_eq!(comparison.verdict, Verdict::NoChange);
+
+    assert!(baseline.compare("other", &bench_samples(1000.0), 2.0).is_none());
+}
+
+#[test]
+fn regressions_fail_the_benchmark() {
+    let mut baseline = Baseline::default();
+    baseline.insert("fast", &bench_samples(1000.0));
+    baseline.insert("slow", &bench_samples(1000.0));
+
+    let mut fast = completed_bench("fast", bench_samples(1000.0));
+    baseline.compare_result(&mut fast, 2.0);
+    assert!(matches!(fast.result, TestResult::TrBench(BenchSamples { comparison: Some(_), .. })));
+
+    let mut slow = completed_bench("slow", bench_samples(1500.0));
+    baseline.compare_result(&mut slow, 2.0);
+    assert!(matches!(slow.result, TestResult::TrFailedMsg(_)));
+}
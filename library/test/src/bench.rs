@@ -1,5 +1,6 @@
 //! Benchmarking module.
 use super::{
+    baseline::Comparison,
     event::CompletedTest,
     options::BenchMode,
     test_result::TestResult,
@@ -23,6 +24,20 @@ pub fn black_box<T>(dummy: T) -> T {
     std::hint::black_box(dummy)
 }
 
+/// Number of samples taken of each benchmark.
+const SAMPLE_COUNT: usize = 50;
+
+/// Options for measuring benchmarks.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct BenchOptions {
+    /// Time to run a benchmark for before measuring it, so that caches, branch predictors and
+    /// CPU frequency scaling can settle.
+    pub warm_up_time: Option<Duration>,
+    /// Time to spend measuring a benchmark. By default, benchmarks are measured until their
+    /// results stabilize, for up to 3 seconds.
+    pub measurement_time: Option<Duration>,
+}
+
 /// Manager of the benchmarking runs.
 ///
 /// This is fed into functions marked with `#[bench]` to allow for
@@ -31,7 +46,9 @@ pub fn black_box<T>(dummy: T) -> T {
 #[derive(Clone)]
 pub struct Bencher {
     mode: BenchMode,
+    options: BenchOptions,
     summary: Option<stats::Summary>,
+    outliers: stats::Outliers,
     pub bytes: u64,
 }
 
@@ -46,7 +63,9 @@ pub fn iter<T, F>(&mut self, mut inner: F)
             return;
         }
 
-        self.summary = Some(iter(&mut inner));
+        let (summary, outliers) = measure(&mut inner, self.options);
+        self.summary = Some(summary);
+        self.outliers = outliers;
     }
 
     pub fn bench<F>(&mut self, mut f: F) -> Result<Option<stats::Summary>, String>
@@ -62,6 +81,11 @@ pub fn bench<F>(&mut self, mut f: F) -> Result<Option<stats::Summary>, String>
 pub struct BenchSamples {
     pub ns_iter_summ: stats::Summary,
     pub mb_s: usize,
+    /// The number of samples `ns_iter_summ` was computed from.
+    pub samples: usize,
+    pub outliers: stats::Outliers,
+    /// How these results compare to the baseline given with `--baseline`, if any.
+    pub comparison: Option<Comparison>,
 }
 
 pub fn fmt_bench_samples(bs: &BenchSamples) -> String {
@@ -81,6 +105,13 @@ pub fn fmt_bench_samples(bs: &BenchSamples) -> String {
     if bs.mb_s != 0 {
         write!(output, " = {} MB/s", bs.mb_s).unwrap();
     }
+    let outliers = bs.outliers.total();
+    if outliers != 0 {
+        write!(output, " ({outliers} outliers)").unwrap();
+    }
+    if let Some(comparison) = bs.comparison {
+        write!(output, " [{comparison}]").unwrap();
+    }
     output
 }
 
@@ -120,12 +151,75 @@ fn ns_iter_inner<T, F>(inner: &mut F, k: u64) -> u64
 }
 
 pub fn iter<T, F>(inner: &mut F) -> stats::Summary
+where
+    F: FnMut() -> T,
+{
+    measure(inner, BenchOptions::default()).0
+}
+
+fn measure<T, F>(inner: &mut F, options: BenchOptions) -> (stats::Summary, stats::Outliers)
 where
     F: FnMut() -> T,
 {
     // Initial bench run to get ballpark figure.
-    let ns_single = ns_iter_inner(inner, 1);
+    let ns_single = match options.warm_up_time {
+        Some(warm_up_time) => warm_up(inner, warm_up_time),
+        None => ns_iter_inner(inner, 1),
+    };
 
+    match options.measurement_time {
+        Some(measurement_time) => measure_for(inner, ns_single, measurement_time),
+        None => measure_until_stable(inner, ns_single),
+    }
+}
+
+// Runs `inner` for `warm_up_time`, and returns the average time it took per iteration.
+fn warm_up<T, F>(inner: &mut F, warm_up_time: Duration) -> u64
+where
+    F: FnMut() -> T,
+{
+    let start = Instant::now();
+    let mut n = 1;
+    let mut total_iters = 0;
+    loop {
+        ns_iter_inner(inner, n);
+        total_iters += n;
+        let elapsed = start.elapsed();
+        if elapsed >= warm_up_time {
+            return (elapsed.as_nanos() / total_iters as u128) as u64;
+        }
+        n = n.saturating_mul(2);
+    }
+}
+
+// Takes samples of `inner` spread over `measurement_time`.
+fn measure_for<T, F>(
+    inner: &mut F,
+    ns_single: u64,
+    measurement_time: Duration,
+) -> (stats::Summary, stats::Outliers)
+where
+    F: FnMut() -> T,
+{
+    let ns_sample = measurement_time.as_nanos() / SAMPLE_COUNT as u128;
+    let n = (ns_sample / cmp::max(1, ns_single) as u128).clamp(1, u64::MAX as u128) as u64;
+
+    let samples: &mut [f64] = &mut [0.0_f64; SAMPLE_COUNT];
+    for p in &mut *samples {
+        *p = ns_iter_inner(inner, n) as f64 / n as f64;
+    }
+
+    let outliers = stats::Outliers::classify(samples);
+    stats::winsorize(samples, 5.0);
+    (stats::Summary::new(samples), outliers)
+}
+
+// Takes samples of `inner` with an increasing number of iterations, until their median stops
+// changing.
+fn measure_until_stable<T, F>(inner: &mut F, ns_single: u64) -> (stats::Summary, stats::Outliers)
+where
+    F: FnMut() -> T,
+{
     // Try to estimate iter count for 1ms falling back to 1m
     // iterations if first run took < 1ns.
     let ns_target_total = 1_000_000; // 1ms
@@ -139,7 +233,7 @@ pub fn iter<T, F>(inner: &mut F) -> stats::Summary
     n = cmp::max(1, n);
 
     let mut total_run = Duration::new(0, 0);
-    let samples: &mut [f64] = &mut [0.0_f64; 50];
+    let samples: &mut [f64] = &mut [0.0_f64; SAMPLE_COUNT];
     loop {
         let loop_start = Instant::now();
 
@@ -155,6 +249,7 @@ pub fn iter<T, F>(inner: &mut F) -> stats::Summary
             *p = ns as f64 / (5 * n) as f64;
         }
 
+        let outliers5 = stats::Outliers::classify(samples);
         stats::winsorize(samples, 5.0);
         let summ5 = stats::Summary::new(samples);
 
@@ -166,13 +261,13 @@ pub fn iter<T, F>(inner: &mut F) -> stats::Summary
             && summ.median_abs_dev_pct < 1.0
             && summ.median - summ5.median < summ5.median_abs_dev
         {
-            return summ5;
+            return (summ5, outliers5);
         }
 
         total_run += loop_run;
         // Longest we ever run for is 3s.
         if total_run > Duration::from_secs(3) {
-            return summ5;
+            return (summ5, outliers5);
         }
 
         // If we overflow here just return the results so far. We check a
@@ -182,7 +277,7 @@ pub fn iter<T, F>(inner: &mut F) -> stats::Summary
         n = match n.checked_mul(10) {
             Some(_) => n * 2,
             None => {
-                return summ5;
+                return (summ5, outliers5);
             }
         };
     }
@@ -193,11 +288,18 @@ pub fn benchmark<F>(
     desc: TestDesc,
     monitor_ch: Sender<CompletedTest>,
     nocapture: bool,
+    options: BenchOptions,
     f: F,
 ) where
     F: FnMut(&mut Bencher) -> Result<(), String>,
 {
-    let mut bs = Bencher { mode: BenchMode::Auto, summary: None, bytes: 0 };
+    let mut bs = Bencher {
+        mode: BenchMode::Auto,
+        options,
+        summary: None,
+        outliers: stats::Outliers::default(),
+        bytes: 0,
+    };
 
     let data = Arc::new(Mutex::new(Vec::new()));
 
@@ -215,14 +317,26 @@ pub fn benchmark<F>(
             let ns_iter = cmp::max(ns_iter_summ.median as u64, 1);
             let mb_s = bs.bytes * 1000 / ns_iter;
 
-            let bs = BenchSamples { ns_iter_summ, mb_s: mb_s as usize };
+            let bs = BenchSamples {
+                ns_iter_summ,
+                mb_s: mb_s as usize,
+                samples: SAMPLE_COUNT,
+                outliers: bs.outliers,
+                comparison: None,
+            };
             TestResult::TrBench(bs)
         }
         Ok(Ok(None)) => {
             // iter not called, so no data.
             // FIXME: error in this case?
             let samples: &mut [f64] = &mut [0.0_f64; 1];
-            let bs = BenchSamples { ns_iter_summ: stats::Summary::new(samples), mb_s: 0 };
+            let bs = BenchSamples {
+                ns_iter_summ: stats::Summary::new(samples),
+                mb_s: 0,
+                samples: 0,
+                outliers: stats::Outliers::default(),
+                comparison: None,
+            };
             TestResult::TrBench(bs)
         }
         Err(_) => TestResult::TrFailed,
@@ -238,6 +352,12 @@ pub fn run_once<F>(f: F) -> Result<(), String>
 where
     F: FnMut(&mut Bencher) -> Result<(), String>,
 {
-    let mut bs = Bencher { mode: BenchMode::Single, summary: None, bytes: 0 };
+    let mut bs = Bencher {
+        mode: BenchMode::Single,
+        options: BenchOptions::default(),
+        summary: None,
+        outliers: stats::Outliers::default(),
+        bytes: 0,
+    };
     bs.bench(f).map(|_| ())
 }
@@ -2,7 +2,10 @@
 
 use std::env;
 use std::path::PathBuf;
+use std::time::Duration;
 
+use super::baseline::BaselineOptions;
+use super::bench::BenchOptions;
 use super::options::{ColorConfig, Options, OutputFormat, RunIgnored};
 use super::time::TestTimeOptions;
 use std::io::{self, IsTerminal};
@@ -13,6 +16,8 @@ pub struct TestOpts {
     pub filters: Vec<String>,
     pub filter_exact: bool,
     pub force_run_in_process: bool,
+    /// Run every test in its own child process, even when panic=unwind.
+    pub isolate: bool,
     pub exclude_should_panic: bool,
     pub run_ignored: RunIgnored,
     pub run_tests: bool,
@@ -26,6 +31,10 @@ pub struct TestOpts {
     pub test_threads: Option<usize>,
     pub skip: Vec<String>,
     pub time_options: Option<TestTimeOptions>,
+    /// Fail tests that run for longer than this, unless their `TestDesc::timeout` is set.
+    pub test_timeout: Option<Duration>,
+    pub bench_options: BenchOptions,
+    pub baseline_options: BaselineOptions,
     /// Stop at first failing test.
     /// May run a few more tests due to threading, but will
     /// abort as soon as possible.
@@ -53,6 +62,13 @@ fn optgroups() -> getopts::Options {
     opts.optflag("", "include-ignored", "Run ignored and not ignored tests")
         .optflag("", "ignored", "Run only ignored tests")
         .optflag("", "force-run-in-process", "Forces tests to run in-process when panic=abort")
+        .optflag(
+            "",
+            "isolate",
+            "Run each test in a separate process, so that tests changing
+            process-global state (environment variables, the working
+            directory, signal handlers...) cannot affect each other",
+        )
         .optflag("", "exclude-should-panic", "Excludes tests marked as should_panic")
         .optflag("", "test", "Run tests and not benchmarks")
         .optflag("", "bench", "Run benchmarks instead of tests")
@@ -102,8 +118,10 @@ fn optgroups() -> getopts::Options {
             pretty = Print verbose output;
             terse  = Display one character per test;
             json   = Output a json document;
-            junit  = Output a JUnit document",
-            "pretty|terse|json|junit",
+            junit  = Output a JUnit document;
+            tap    = Output a TAP 14 document;
+            github = Verbose output with GitHub Actions annotations",
+            "pretty|terse|json|junit|tap|github",
         )
         .optflag("", "show-output", "Show captured stdout of successful tests")
         .optopt(
@@ -142,6 +160,50 @@ fn optgroups() -> getopts::Options {
             `CRITICAL_TIME` here means the limit that should not be exceeded by test.
             ",
         )
+        .optopt(
+            "",
+            "test-timeout",
+            "Fail tests that run for longer than SECONDS.
+
+            Tests run in a separate process (with panic=abort) are killed once
+            they time out. Tests run in-process cannot be killed: they are
+            reported as failed, and keep running in the background.",
+            "SECONDS",
+        )
+        .optopt("", "warm-up-time", "Run each benchmark for SECONDS before measuring it", "SECONDS")
+        .optopt(
+            "",
+            "measurement-time",
+            "Measure each benchmark for SECONDS, instead of until its
+            results are stable",
+            "SECONDS",
+        )
+        .optopt(
+            "",
+            "save-baseline",
+            "Save the benchmark results as the baseline called NAME",
+            "NAME",
+        )
+        .optopt(
+            "",
+            "baseline",
+            "Compare the benchmark results against the baseline called
+            NAME, and fail the benchmarks that regressed significantly",
+            "NAME",
+        )
+        .optopt(
+            "",
+            "baseline-dir",
+            "Directory to save and load baselines in (default: target/bench-baselines)",
+            "PATH",
+        )
+        .optopt(
+            "",
+            "regression-threshold",
+            "Slowdown, in percent, from which a significant change against
+            the baseline fails a benchmark (default: 2)",
+            "PERCENT",
+        )
         .optflag("", "shuffle", "Run tests in random order")
         .optopt(
             "",
@@ -257,8 +319,15 @@ fn parse_opts_impl(matches: getopts::Matches) -> OptRes {
 
     // Unstable flags
     let force_run_in_process = unstable_optflag!(matches, allow_unstable, "force-run-in-process");
+    let isolate = unstable_optflag!(matches, allow_unstable, "isolate");
+    if isolate && force_run_in_process {
+        return Err("--isolate and --force-run-in-process are mutually exclusive".to_string());
+    }
     let exclude_should_panic = unstable_optflag!(matches, allow_unstable, "exclude-should-panic");
     let time_options = get_time_options(&matches, allow_unstable)?;
+    let test_timeout = get_test_timeout(&matches, allow_unstable)?;
+    let bench_options = get_bench_options(&matches, allow_unstable)?;
+    let baseline_options = get_baseline_options(&matches, allow_unstable)?;
     let shuffle = get_shuffle(&matches, allow_unstable)?;
     let shuffle_seed = get_shuffle_seed(&matches, allow_unstable)?;
 
@@ -286,6 +355,7 @@ fn parse_opts_impl(matches: getopts::Matches) -> OptRes {
         filters,
         filter_exact: exact,
         force_run_in_process,
+        isolate,
         exclude_should_panic,
         run_ignored,
         run_tests,
@@ -299,6 +369,9 @@ fn parse_opts_impl(matches: getopts::Matches) -> OptRes {
         test_threads,
         skip,
         time_options,
+        test_timeout,
+        bench_options,
+        baseline_options,
         options,
         fail_fast: false,
     };
@@ -336,6 +409,77 @@ fn get_time_options(
     Ok(options)
 }
 
+fn get_test_timeout(
+    matches: &getopts::Matches,
+    allow_unstable: bool,
+) -> OptPartRes<Option<Duration>> {
+    let test_timeout = match unstable_optopt!(matches, allow_unstable, "test-timeout") {
+        Some(secs_str) => match secs_str.parse::<u64>() {
+            Ok(0) => return Err("argument for --test-timeout must not be 0".to_string()),
+            Ok(secs) => Some(Duration::from_secs(secs)),
+            Err(e) => {
+                return Err(format!(
+                    "argument for --test-timeout must be a number of seconds > 0 \
+                     (error: {e})"
+                ));
+            }
+        },
+        None => None,
+    };
+
+    Ok(test_timeout)
+}
+
+fn get_bench_options(matches: &getopts::Matches, allow_unstable: bool) -> OptPartRes<BenchOptions> {
+    let parse_secs = |option: &str, secs_str: Option<String>| match secs_str {
+        Some(secs_str) => match secs_str.parse::<f64>().map(Duration::try_from_secs_f64) {
+            Ok(Ok(duration)) => Ok(Some(duration)),
+            _ => Err(format!("argument for --{option} must be a number of seconds")),
+        },
+        None => Ok(None),
+    };
+    let warm_up_time = unstable_optopt!(matches, allow_unstable, "warm-up-time");
+    let measurement_time = unstable_optopt!(matches, allow_unstable, "measurement-time");
+
+    Ok(BenchOptions {
+        warm_up_time: parse_secs("warm-up-time", warm_up_time)?,
+        measurement_time: parse_secs("measurement-time", measurement_time)?,
+    })
+}
+
+fn get_baseline_options(
+    matches: &getopts::Matches,
+    allow_unstable: bool,
+) -> OptPartRes<BaselineOptions> {
+    let defaults = BaselineOptions::default();
+    let save = unstable_optopt!(matches, allow_unstable, "save-baseline");
+    let compare = unstable_optopt!(matches, allow_unstable, "baseline");
+    for name in save.iter().chain(&compare) {
+        // Baselines are stored in a file named after them.
+        if name.is_empty() || name.contains(['/', '\\']) {
+            return Err(format!("invalid baseline name `{name}`"));
+        }
+    }
+    let dir = match unstable_optopt!(matches, allow_unstable, "baseline-dir") {
+        Some(dir) => PathBuf::from(dir),
+        None => defaults.dir,
+    };
+    let regression_threshold =
+        match unstable_optopt!(matches, allow_unstable, "regression-threshold") {
+            Some(threshold) => match threshold.parse::<f64>() {
+                Ok(threshold) if threshold >= 0.0 => threshold,
+                _ => {
+                    return Err("argument for --regression-threshold must be a non-negative \
+                                percentage"
+                        .to_string());
+                }
+            },
+            None => defaults.regression_threshold,
+        };
+
+    Ok(BaselineOptions { dir, save, compare, regression_threshold })
+}
+
 fn get_shuffle(matches: &getopts::Matches, allow_unstable: bool) -> OptPartRes<bool> {
     let mut shuffle = unstable_optflag!(matches, allow_unstable, "shuffle");
     if !shuffle && allow_unstable {
@@ -414,10 +558,22 @@ fn get_format(
             }
             OutputFormat::Junit
         }
+        Some("tap") => {
+            if !allow_unstable {
+                return Err("The \"tap\" format is only accepted on the nightly compiler with -Z unstable-options".into());
+            }
+            OutputFormat::Tap
+        }
+        Some("github") => {
+            if !allow_unstable {
+                return Err("The \"github\" format is only accepted on the nightly compiler with -Z unstable-options".into());
+            }
+            OutputFormat::Github
+        }
         Some(v) => {
             return Err(format!(
-                "argument for --format must be pretty, terse, json or junit (was \
-                 {v})"
+                "argument for --format must be pretty, terse, json, junit, tap or github \
+                 (was {v})"
             ));
         }
     };
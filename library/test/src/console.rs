@@ -6,11 +6,15 @@
 use std::time::Instant;
 
 use super::{
+    baseline::Baseline,
     bench::fmt_bench_samples,
     cli::TestOpts,
     event::{CompletedTest, TestEvent},
     filter_tests,
-    formatters::{JsonFormatter, JunitFormatter, OutputFormatter, PrettyFormatter, TerseFormatter},
+    formatters::{
+        join_output, GithubFormatter, JsonFormatter, JunitFormatter, OutputFormatter,
+        PrettyFormatter, TapFormatter, TerseFormatter,
+    },
     helpers::{concurrency::get_concurrency, metrics::MetricMap},
     options::{Options, OutputFormat},
     run_tests, term,
@@ -95,6 +99,10 @@ pub struct ConsoleTestState {
     pub not_failures: Vec<(TestDesc, Vec<u8>)>,
     pub ignores: Vec<(TestDesc, Vec<u8>)>,
     pub time_failures: Vec<(TestDesc, Vec<u8>)>,
+    /// Benchmark results of an earlier run to compare against.
+    pub baseline: Option<Baseline>,
+    /// Benchmark results of this run, if they are to be saved as a baseline.
+    pub new_baseline: Option<Baseline>,
     pub options: Options,
 }
 
@@ -104,6 +112,11 @@ pub fn new(opts: &TestOpts) -> io::Result<ConsoleTestState> {
             Some(ref path) => Some(File::create(path)?),
             None => None,
         };
+        let baseline = match opts.baseline_options.compare {
+            Some(ref name) => Some(Baseline::load(&opts.baseline_options.dir, name)?),
+            None => None,
+        };
+        let new_baseline = opts.baseline_options.save.as_ref().map(|_| Baseline::default());
 
         Ok(ConsoleTestState {
             log_out,
@@ -119,6 +132,8 @@ pub fn new(opts: &TestOpts) -> io::Result<ConsoleTestState> {
             not_failures: Vec::new(),
             ignores: Vec::new(),
             time_failures: Vec::new(),
+            baseline,
+            new_baseline,
             options: opts.options,
         })
     }
@@ -184,7 +199,7 @@ pub fn list_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Res
     };
 
     let mut out: Box<dyn OutputFormatter> = match opts.format {
-        OutputFormat::Pretty | OutputFormat::Junit => {
+        OutputFormat::Pretty | OutputFormat::Junit | OutputFormat::Tap | OutputFormat::Github => {
             Box::new(PrettyFormatter::new(output, false, 0, false, None))
         }
         OutputFormat::Terse => Box::new(TerseFormatter::new(output, false, 0, false)),
@@ -221,7 +236,10 @@ pub fn list_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Res
 // Updates `ConsoleTestState` depending on result of the test execution.
 fn handle_test_result(st: &mut ConsoleTestState, completed_test: CompletedTest) {
     let test = completed_test.desc;
-    let stdout = completed_test.stdout;
+    let stdout = match completed_test.stderr {
+        Some(stderr) => join_output(&test.name, &completed_test.stdout, Some(&stderr)).into_owned(),
+        None => completed_test.stdout,
+    };
     match completed_test.result {
         TestResult::TrOk => {
             st.passed += 1;
@@ -256,6 +274,24 @@ fn handle_test_result(st: &mut ConsoleTestState, completed_test: CompletedTest)
     }
 }
 
+// Records the results of a benchmark for `--save-baseline`, and compares them against
+// `--baseline`, failing the benchmark if it regressed.
+fn handle_bench_result(
+    st: &mut ConsoleTestState,
+    completed_test: &mut CompletedTest,
+    regression_threshold: f64,
+) {
+    let TestResult::TrBench(ref bs) = completed_test.result else {
+        return;
+    };
+    if let Some(ref mut new_baseline) = st.new_baseline {
+        new_baseline.insert(completed_test.desc.name.as_slice(), bs);
+    }
+    if let Some(ref baseline) = st.baseline {
+        baseline.compare_result(completed_test, regression_threshold);
+    }
+}
+
 // Handler for events that occur during test execution.
 // It is provided as a callback to the `run_tests` function.
 fn on_test_event(
@@ -278,9 +314,10 @@ fn on_test_event(
             let result = &completed_test.result;
             let exec_time = &completed_test.exec_time;
             let stdout = &completed_test.stdout;
+            let stderr = completed_test.stderr.as_deref();
 
             st.write_log_result(test, result, exec_time.as_ref())?;
-            out.write_result(test, result, exec_time.as_ref(), stdout, st)?;
+            out.write_result(test, result, exec_time.as_ref(), stdout, stderr, st)?;
             handle_test_result(st, completed_test);
         }
     }
@@ -317,6 +354,14 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Resu
         }
         OutputFormat::Json => Box::new(JsonFormatter::new(output)),
         OutputFormat::Junit => Box::new(JunitFormatter::new(output)),
+        OutputFormat::Tap => Box::new(TapFormatter::new(output)),
+        OutputFormat::Github => Box::new(GithubFormatter::new(PrettyFormatter::new(
+            output,
+            opts.use_color(),
+            max_name_len,
+            is_multithreaded,
+            opts.time_options,
+        ))),
     };
     let mut st = ConsoleTestState::new(opts)?;
 
@@ -326,9 +371,19 @@ pub fn run_tests_console(opts: &TestOpts, tests: Vec<TestDescAndFn>) -> io::Resu
     let is_instant_supported = !cfg!(target_family = "wasm") && !cfg!(miri);
 
     let start_time = is_instant_supported.then(Instant::now);
-    run_tests(opts, tests, |x| on_test_event(&x, &mut st, &mut *out))?;
+    let regression_threshold = opts.baseline_options.regression_threshold;
+    run_tests(opts, tests, |mut x| {
+        if let TestEvent::TeResult(ref mut completed_test) = x {
+            handle_bench_result(&mut st, completed_test, regression_threshold);
+        }
+        on_test_event(&x, &mut st, &mut *out)
+    })?;
     st.exec_time = start_time.map(|t| TestSuiteExecTime(t.elapsed()));
 
+    if let (Some(new_baseline), Some(name)) = (&st.new_baseline, &opts.baseline_options.save) {
+        new_baseline.save(&opts.baseline_options.dir, name)?;
+    }
+
     assert!(opts.fail_fast || st.current_test_count() == st.total);
 
     out.write_run_finish(&st)
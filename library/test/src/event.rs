@@ -1,6 +1,8 @@
 //! Module containing different events that can occur
 //! during tests execution process.
 
+use std::process::ExitStatus;
+
 use super::test_result::TestResult;
 use super::time::TestExecTime;
 use super::types::{TestDesc, TestId};
@@ -12,6 +14,11 @@ pub struct CompletedTest {
     pub result: TestResult,
     pub exec_time: Option<TestExecTime>,
     pub stdout: Vec<u8>,
+    /// What the test wrote to stderr, if it was captured separately from stdout. Tests that run
+    /// in the test harness process capture both streams into `stdout`.
+    pub stderr: Option<Vec<u8>>,
+    /// How the child process the test ran in exited, if it ran in one.
+    pub exit_status: Option<ExitStatus>,
 }
 
 impl CompletedTest {
@@ -22,7 +29,7 @@ pub fn new(
         exec_time: Option<TestExecTime>,
         stdout: Vec<u8>,
     ) -> Self {
-        Self { id, desc, result, exec_time, stdout }
+        Self { id, desc, result, exec_time, stdout, stderr: None, exit_status: None }
     }
 }
 
@@ -0,0 +1,187 @@
+use std::{io, io::prelude::Write};
+
+use super::{join_output, OutputFormatter, PrettyFormatter};
+use crate::{
+    console::{ConsoleTestDiscoveryState, ConsoleTestState},
+    test_result::TestResult,
+    time,
+    types::TestDesc,
+};
+
+/// Prints the same output as the pretty formatter, and additionally emits a GitHub Actions
+/// [workflow command](https://docs.github.com/en/actions/using-workflows/workflow-commands-for-github-actions)
+/// for every failure, so that it shows up as an annotation on the failing line.
+pub(crate) struct GithubFormatter<T> {
+    pretty: PrettyFormatter<T>,
+}
+
+impl<T: Write> GithubFormatter<T> {
+    pub fn new(pretty: PrettyFormatter<T>) -> Self {
+        Self { pretty }
+    }
+
+    #[cfg(test)]
+    pub fn output_location(&self) -> &crate::console::OutputLocation<T> {
+        self.pretty.output_location()
+    }
+
+    fn write_annotation(
+        &mut self,
+        level: &str,
+        location: Location<'_>,
+        title: &str,
+        message: &str,
+    ) -> io::Result<()> {
+        // Tests that were not generated by `#[test]` may not know where they are defined.
+        let Location { file, line, col } = location;
+        let location = if file.is_empty() {
+            String::new()
+        } else {
+            format!("file={},line={line},col={col},", EscapedProperty(file))
+        };
+        self.pretty.write_plain(format!(
+            "::{level} {location}title={}::{}\n",
+            EscapedProperty(title),
+            EscapedData(message)
+        ))
+    }
+}
+
+impl<T: Write> OutputFormatter for GithubFormatter<T> {
+    fn write_discovery_start(&mut self) -> io::Result<()> {
+        self.pretty.write_discovery_start()
+    }
+
+    fn write_test_discovered(&mut self, desc: &TestDesc, test_type: &str) -> io::Result<()> {
+        self.pretty.write_test_discovered(desc, test_type)
+    }
+
+    fn write_discovery_finish(&mut self, state: &ConsoleTestDiscoveryState) -> io::Result<()> {
+        self.pretty.write_discovery_finish(state)
+    }
+
+    fn write_run_start(&mut self, test_count: usize, shuffle_seed: Option<u64>) -> io::Result<()> {
+        self.pretty.write_run_start(test_count, shuffle_seed)
+    }
+
+    fn write_test_start(&mut self, desc: &TestDesc) -> io::Result<()> {
+        self.pretty.write_test_start(desc)
+    }
+
+    fn write_timeout(&mut self, desc: &TestDesc) -> io::Result<()> {
+        self.pretty.write_timeout(desc)?;
+        self.write_annotation(
+            "warning",
+            Location::of_test(desc),
+            desc.name.as_slice(),
+            &format!("test has been running for over {} seconds", time::TEST_WARN_TIMEOUT_S),
+        )
+    }
+
+    fn write_result(
+        &mut self,
+        desc: &TestDesc,
+        result: &TestResult,
+        exec_time: Option<&time::TestExecTime>,
+        stdout: &[u8],
+        stderr: Option<&[u8]>,
+        state: &ConsoleTestState,
+    ) -> io::Result<()> {
+        self.pretty.write_result(desc, result, exec_time, stdout, stderr, state)?;
+
+        let output = join_output(&desc.name, stdout, stderr);
+        let output = String::from_utf8_lossy(&output);
+        let panic = parse_panic(&output);
+        let location = panic.as_ref().map_or_else(|| Location::of_test(desc), |p| p.location);
+        let message = match *result {
+            TestResult::TrFailedMsg(ref m) => m.as_str(),
+            TestResult::TrTimedFail => "time limit exceeded",
+            TestResult::TrFailed => panic.as_ref().map_or("test failed", |p| p.message),
+            TestResult::TrOk | TestResult::TrIgnored | TestResult::TrBench(_) => return Ok(()),
+        };
+        self.write_annotation("error", location, desc.name.as_slice(), message)
+    }
+
+    fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
+        self.pretty.write_run_finish(state)
+    }
+}
+
+#[derive(Clone, Copy)]
+struct Location<'a> {
+    file: &'a str,
+    line: usize,
+    col: usize,
+}
+
+impl<'a> Location<'a> {
+    fn of_test(desc: &'a TestDesc) -> Self {
+        Location { file: desc.source_file, line: desc.start_line, col: desc.start_col }
+    }
+}
+
+struct Panic<'a> {
+    location: Location<'a>,
+    message: &'a str,
+}
+
+/// Finds the first panic in the captured output of a test, as printed by the default panic hook:
+///
+/// ```text
+/// thread 'tests::foo' panicked at src/lib.rs:10:5:
+/// assertion failed: false
+/// note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace
+/// ```
+fn parse_panic(output: &str) -> Option<Panic<'_>> {
+    let start = output.find("' panicked at ")? + "' panicked at ".len();
+    let (header, rest) = output[start..].split_once('\n').unwrap_or((&output[start..], ""));
+    let mut parts = header.strip_suffix(':')?.rsplitn(3, ':');
+    let col = parts.next()?.parse().ok()?;
+    let line = parts.next()?.parse().ok()?;
+    let file = parts.next()?;
+
+    // The message can span several lines. It ends where the hook's notes start, or where the next
+    // section of captured output (such as stderr) starts.
+    let end = ["\nnote: ", "\nstack backtrace:\n", "\n---- "]
+        .iter()
+        .filter_map(|terminator| rest.find(terminator))
+        .min()
+        .unwrap_or(rest.len());
+    let message = rest[..end].trim_end();
+
+    Some(Panic { location: Location { file, line, col }, message })
+}
+
+/// Escapes the data of a workflow command.
+struct EscapedData<'a>(&'a str);
+
+impl std::fmt::Display for EscapedData<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '%' => f.write_str("%25")?,
+                '\r' => f.write_str("%0D")?,
+                '\n' => f.write_str("%0A")?,
+                c => write!(f, "{c}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Escapes the value of a workflow command property, which additionally can't contain the
+/// property and data separators.
+struct EscapedProperty<'a>(&'a str);
+
+impl std::fmt::Display for EscapedProperty<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                ':' => f.write_str("%3A")?,
+                ',' => f.write_str("%2C")?,
+                c => write!(f, "{}", EscapedData(c.encode_utf8(&mut [0; 4])))?,
+            }
+        }
+        Ok(())
+    }
+}
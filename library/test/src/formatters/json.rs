@@ -3,11 +3,17 @@
 use super::OutputFormatter;
 use crate::{
     console::{ConsoleTestDiscoveryState, ConsoleTestState, OutputLocation},
+    options::ShouldPanic,
     test_result::TestResult,
     time,
     types::TestDesc,
 };
 
+/// Version of the events emitted by the JSON formatter, reported by the events that start the
+/// discovery and the test run. It is bumped whenever an event changes in a way that could break
+/// existing consumers; adding new fields to an event does not count as such a change.
+const SCHEMA_VERSION: u32 = 1;
+
 pub(crate) struct JsonFormatter<T> {
     out: OutputLocation<T>,
 }
@@ -17,6 +23,11 @@ pub fn new(out: OutputLocation<T>) -> Self {
         Self { out }
     }
 
+    #[cfg(test)]
+    pub fn output_location(&self) -> &OutputLocation<T> {
+        &self.out
+    }
+
     fn writeln_message(&mut self, s: &str) -> io::Result<()> {
         // self.out will take a lock, but that lock is released when write_all returns. This
         // results in a race condition and json output may not end with a new line. We avoid this
@@ -28,11 +39,11 @@ fn writeln_message(&mut self, s: &str) -> io::Result<()> {
 
     fn write_event(
         &mut self,
-        ty: &str,
         name: &str,
         event: &str,
         exec_time: Option<&time::TestExecTime>,
         stdout: Option<Cow<'_, str>>,
+        stderr: Option<Cow<'_, str>>,
         extra: Option<&str>,
     ) -> io::Result<()> {
         // A doc test's name includes a filename which must be escaped for correct json.
@@ -47,40 +58,39 @@ fn write_event(
         } else {
             String::from("")
         };
+        let stderr_json = if let Some(stderr) = stderr {
+            format!(r#", "stderr": "{}""#, EscapedString(stderr))
+        } else {
+            String::from("")
+        };
         let extra_json =
             if let Some(extra) = extra { format!(r#", {extra}"#) } else { String::from("") };
         let newline = "\n";
 
         self.writeln_message(&format!(
-                r#"{{ "type": "{ty}", "name": "{name}", "event": "{event}"{exec_time_json}{stdout_json}{extra_json} }}{newline}"#))
+                r#"{{ "type": "test", "name": "{name}", "event": "{event}"{exec_time_json}{stdout_json}{stderr_json}{extra_json} }}{newline}"#))
     }
 }
 
 impl<T: Write> OutputFormatter for JsonFormatter<T> {
     fn write_discovery_start(&mut self) -> io::Result<()> {
-        self.writeln_message(concat!(r#"{ "type": "suite", "event": "discovery" }"#, "\n"))
+        let newline = "\n";
+        self.writeln_message(&format!(
+            r#"{{ "type": "suite", "event": "discovery", "schema_version": {SCHEMA_VERSION} }}{newline}"#
+        ))
     }
 
     fn write_test_discovered(&mut self, desc: &TestDesc, test_type: &str) -> io::Result<()> {
-        let TestDesc {
-            name,
-            ignore,
-            ignore_message,
-            source_file,
-            start_line,
-            start_col,
-            end_line,
-            end_col,
-            ..
-        } = desc;
+        let TestDesc { name, ignore, ignore_message, .. } = desc;
 
         let name = EscapedString(name.as_slice());
-        let ignore_message = ignore_message.unwrap_or("");
-        let source_path = EscapedString(source_file);
+        let ignore_message = EscapedString(ignore_message.unwrap_or(""));
+        let location_json = location_json(desc);
+        let should_panic_json = should_panic_json(desc);
         let newline = "\n";
 
         self.writeln_message(&format!(
-            r#"{{ "type": "{test_type}", "event": "discovered", "name": "{name}", "ignore": {ignore}, "ignore_message": "{ignore_message}", "source_path": "{source_path}", "start_line": {start_line}, "start_col": {start_col}, "end_line": {end_line}, "end_col": {end_col} }}{newline}"#
+            r#"{{ "type": "{test_type}", "event": "discovered", "name": "{name}", "ignore": {ignore}, "ignore_message": "{ignore_message}"{location_json}{should_panic_json} }}{newline}"#
         ))
     }
 
@@ -102,15 +112,17 @@ fn write_run_start(&mut self, test_count: usize, shuffle_seed: Option<u64>) -> i
         };
         let newline = "\n";
         self.writeln_message(&format!(
-            r#"{{ "type": "suite", "event": "started", "test_count": {test_count}{shuffle_seed_json} }}{newline}"#
+            r#"{{ "type": "suite", "event": "started", "schema_version": {SCHEMA_VERSION}, "test_count": {test_count}{shuffle_seed_json} }}{newline}"#
             ))
     }
 
     fn write_test_start(&mut self, desc: &TestDesc) -> io::Result<()> {
         let name = EscapedString(desc.name.as_slice());
+        let location_json = location_json(desc);
+        let should_panic_json = should_panic_json(desc);
         let newline = "\n";
         self.writeln_message(&format!(
-            r#"{{ "type": "test", "event": "started", "name": "{name}" }}{newline}"#
+            r#"{{ "type": "test", "event": "started", "name": "{name}"{location_json}{should_panic_json} }}{newline}"#
         ))
     }
 
@@ -120,6 +132,7 @@ fn write_result(
         result: &TestResult,
         exec_time: Option<&time::TestExecTime>,
         stdout: &[u8],
+        stderr: Option<&[u8]>,
         state: &ConsoleTestState,
     ) -> io::Result<()> {
         let display_stdout = state.options.display_output || *result != TestResult::TrOk;
@@ -128,39 +141,45 @@ fn write_result(
         } else {
             None
         };
+        let stderr = match stderr {
+            Some(stderr) if display_stdout && !stderr.is_empty() => {
+                Some(String::from_utf8_lossy(stderr))
+            }
+            _ => None,
+        };
         match *result {
             TestResult::TrOk => {
-                self.write_event("test", desc.name.as_slice(), "ok", exec_time, stdout, None)
+                self.write_event(desc.name.as_slice(), "ok", exec_time, stdout, stderr, None)
             }
 
             TestResult::TrFailed => {
-                self.write_event("test", desc.name.as_slice(), "failed", exec_time, stdout, None)
+                self.write_event(desc.name.as_slice(), "failed", exec_time, stdout, stderr, None)
             }
 
             TestResult::TrTimedFail => self.write_event(
-                "test",
                 desc.name.as_slice(),
                 "failed",
                 exec_time,
                 stdout,
+                stderr,
                 Some(r#""reason": "time limit exceeded""#),
             ),
 
             TestResult::TrFailedMsg(ref m) => self.write_event(
-                "test",
                 desc.name.as_slice(),
                 "failed",
                 exec_time,
                 stdout,
+                stderr,
                 Some(&*format!(r#""message": "{}""#, EscapedString(m))),
             ),
 
             TestResult::TrIgnored => self.write_event(
-                "test",
                 desc.name.as_slice(),
                 "ignored",
                 exec_time,
                 stdout,
+                stderr,
                 desc.ignore_message
                     .map(|msg| format!(r#""message": "{}""#, EscapedString(msg)))
                     .as_deref(),
@@ -217,9 +236,29 @@ fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
     }
 }
 
+/// Formats where the test is defined, as fields to append to an event.
+fn location_json(desc: &TestDesc) -> String {
+    let TestDesc { source_file, start_line, start_col, end_line, end_col, .. } = desc;
+    let source_path = EscapedString(source_file);
+    format!(
+        r#", "source_path": "{source_path}", "start_line": {start_line}, "start_col": {start_col}, "end_line": {end_line}, "end_col": {end_col}"#
+    )
+}
+
+/// Formats whether the test is expected to panic, as fields to append to an event.
+fn should_panic_json(desc: &TestDesc) -> String {
+    match desc.should_panic {
+        ShouldPanic::No => String::from(r#", "should_panic": false"#),
+        ShouldPanic::Yes => String::from(r#", "should_panic": true"#),
+        ShouldPanic::YesWithMessage(msg) => {
+            format!(r#", "should_panic": true, "should_panic_message": "{}""#, EscapedString(msg))
+        }
+    }
+}
+
 /// A formatting utility used to print strings with characters in need of escaping.
 /// Base code taken form `libserialize::json::escape_str`
-struct EscapedString<S: AsRef<str>>(S);
+pub(crate) struct EscapedString<S: AsRef<str>>(pub(crate) S);
 
 impl<S: AsRef<str>> std::fmt::Display for EscapedString<S> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> ::std::fmt::Result {
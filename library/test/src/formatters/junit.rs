@@ -1,7 +1,7 @@
 use std::io::{self, prelude::Write};
 use std::time::Duration;
 
-use super::OutputFormatter;
+use super::{join_output, OutputFormatter};
 use crate::{
     console::{ConsoleTestDiscoveryState, ConsoleTestState, OutputLocation},
     test_result::TestResult,
@@ -76,13 +76,15 @@ fn write_result(
         result: &TestResult,
         exec_time: Option<&time::TestExecTime>,
         stdout: &[u8],
+        stderr: Option<&[u8]>,
         _state: &ConsoleTestState,
     ) -> io::Result<()> {
         // Because the testsuite node holds some of the information as attributes, we can't write it
         // until all of the tests have finished. Instead of writing every result as they come in, we add
         // them to a Vec and write them all at once when run is complete.
         let duration = exec_time.map(|t| t.0).unwrap_or_default();
-        self.results.push((desc.clone(), result.clone(), duration, stdout.to_vec()));
+        let stdout = join_output(&desc.name, stdout, stderr).into_owned();
+        self.results.push((desc.clone(), result.clone(), duration, stdout));
         Ok(())
     }
     fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
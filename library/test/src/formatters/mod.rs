@@ -1,4 +1,4 @@
-use std::{io, io::prelude::Write};
+use std::{borrow::Cow, io, io::prelude::Write};
 
 use crate::{
     console::{ConsoleTestDiscoveryState, ConsoleTestState},
@@ -7,14 +7,18 @@
     types::{TestDesc, TestName},
 };
 
+mod github;
 mod json;
 mod junit;
 mod pretty;
+mod tap;
 mod terse;
 
-pub(crate) use self::json::JsonFormatter;
+pub(crate) use self::github::GithubFormatter;
+pub(crate) use self::json::{EscapedString, JsonFormatter};
 pub(crate) use self::junit::JunitFormatter;
 pub(crate) use self::pretty::PrettyFormatter;
+pub(crate) use self::tap::TapFormatter;
 pub(crate) use self::terse::TerseFormatter;
 
 pub(crate) trait OutputFormatter {
@@ -31,6 +35,7 @@ fn write_result(
         result: &TestResult,
         exec_time: Option<&time::TestExecTime>,
         stdout: &[u8],
+        stderr: Option<&[u8]>,
         state: &ConsoleTestState,
     ) -> io::Result<()>;
     fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool>;
@@ -44,3 +49,21 @@ pub(crate) fn write_stderr_delimiter(test_output: &mut Vec<u8>, test_name: &Test
     }
     writeln!(test_output, "---- {test_name} stderr ----").unwrap();
 }
+
+/// Joins the stdout and the separately captured stderr of a test into the single stream of output
+/// that most formats show for it.
+pub(crate) fn join_output<'a>(
+    test_name: &TestName,
+    stdout: &'a [u8],
+    stderr: Option<&[u8]>,
+) -> Cow<'a, [u8]> {
+    match stderr {
+        None => Cow::Borrowed(stdout),
+        Some(stderr) => {
+            let mut test_output = stdout.to_vec();
+            write_stderr_delimiter(&mut test_output, test_name);
+            test_output.extend_from_slice(stderr);
+            Cow::Owned(test_output)
+        }
+    }
+}
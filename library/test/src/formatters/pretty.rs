@@ -236,6 +236,7 @@ fn write_result(
         result: &TestResult,
         exec_time: Option<&time::TestExecTime>,
         _: &[u8],
+        _: Option<&[u8]>,
         _: &ConsoleTestState,
     ) -> io::Result<()> {
         if self.is_multithreaded {
@@ -0,0 +1,194 @@
+use std::{io, io::prelude::Write};
+
+use super::{EscapedString, OutputFormatter};
+use crate::{
+    console::{ConsoleTestDiscoveryState, ConsoleTestState, OutputLocation},
+    test_result::TestResult,
+    time,
+    types::TestDesc,
+};
+
+/// Emits the results in the [TAP 14](https://testanything.org/tap-version-14-specification.html)
+/// format. Additional information about a test point, such as the failure message or the captured
+/// output, goes into its YAML diagnostic block.
+pub(crate) struct TapFormatter<T> {
+    out: OutputLocation<T>,
+    /// Number of the last test point written
+    test_number: usize,
+}
+
+impl<T: Write> TapFormatter<T> {
+    pub fn new(out: OutputLocation<T>) -> Self {
+        Self { out, test_number: 0 }
+    }
+
+    #[cfg(test)]
+    pub fn output_location(&self) -> &OutputLocation<T> {
+        &self.out
+    }
+
+    fn write_plain<S: AsRef<str>>(&mut self, s: S) -> io::Result<()> {
+        let s = s.as_ref();
+        self.out.write_all(s.as_bytes())?;
+        self.out.flush()
+    }
+
+    fn write_test_point(
+        &mut self,
+        ok: bool,
+        desc: &TestDesc,
+        directive: Option<&str>,
+        diagnostics: &[(&str, String)],
+    ) -> io::Result<()> {
+        self.test_number += 1;
+
+        let mut line = format!(
+            "{}{} - {}",
+            if ok { "ok " } else { "not ok " },
+            self.test_number,
+            EscapedDescription(desc.name.as_slice())
+        );
+        if let Some(directive) = directive {
+            line.push_str(" # ");
+            line.push_str(directive);
+        }
+        line.push('\n');
+
+        if !diagnostics.is_empty() {
+            line.push_str("  ---\n");
+            for (key, value) in diagnostics {
+                line.push_str(&format!("  {key}: {value}\n"));
+            }
+            line.push_str("  ...\n");
+        }
+
+        self.write_plain(line)
+    }
+}
+
+impl<T: Write> OutputFormatter for TapFormatter<T> {
+    fn write_discovery_start(&mut self) -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::NotFound, "Not yet implemented!"))
+    }
+
+    fn write_test_discovered(&mut self, _desc: &TestDesc, _test_type: &str) -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::NotFound, "Not yet implemented!"))
+    }
+
+    fn write_discovery_finish(&mut self, _state: &ConsoleTestDiscoveryState) -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::NotFound, "Not yet implemented!"))
+    }
+
+    fn write_run_start(&mut self, test_count: usize, shuffle_seed: Option<u64>) -> io::Result<()> {
+        self.write_plain(format!("TAP version 14\n1..{test_count}\n"))?;
+        if let Some(shuffle_seed) = shuffle_seed {
+            self.write_plain(format!("# shuffle seed: {shuffle_seed}\n"))?;
+        }
+        Ok(())
+    }
+
+    fn write_test_start(&mut self, _desc: &TestDesc) -> io::Result<()> {
+        // We do not output anything on test start.
+        Ok(())
+    }
+
+    fn write_timeout(&mut self, desc: &TestDesc) -> io::Result<()> {
+        self.write_plain(format!(
+            "# test {} has been running for over {} seconds\n",
+            desc.name,
+            time::TEST_WARN_TIMEOUT_S
+        ))
+    }
+
+    fn write_result(
+        &mut self,
+        desc: &TestDesc,
+        result: &TestResult,
+        exec_time: Option<&time::TestExecTime>,
+        stdout: &[u8],
+        stderr: Option<&[u8]>,
+        state: &ConsoleTestState,
+    ) -> io::Result<()> {
+        // YAML accepts JSON strings as double-quoted scalars, so we reuse the JSON escaping.
+        let quoted = |s: &str| format!("\"{}\"", EscapedString(s));
+
+        let mut diagnostics = Vec::new();
+        match *result {
+            TestResult::TrFailedMsg(ref m) => diagnostics.push(("message", quoted(m))),
+            TestResult::TrTimedFail => diagnostics.push(("message", quoted("time limit exceeded"))),
+            TestResult::TrBench(ref bs) => {
+                let median = bs.ns_iter_summ.median as usize;
+                let deviation = (bs.ns_iter_summ.max - bs.ns_iter_summ.min) as usize;
+                diagnostics.push(("median", median.to_string()));
+                diagnostics.push(("deviation", deviation.to_string()));
+                if bs.mb_s != 0 {
+                    diagnostics.push(("mb_per_second", bs.mb_s.to_string()));
+                }
+            }
+            TestResult::TrOk | TestResult::TrFailed | TestResult::TrIgnored => {}
+        }
+        if let Some(exec_time) = exec_time {
+            diagnostics.push(("exec_time", exec_time.0.as_secs_f64().to_string()));
+        }
+        let display_stdout = state.options.display_output || *result != TestResult::TrOk;
+        if display_stdout && !stdout.is_empty() {
+            diagnostics.push(("stdout", quoted(&String::from_utf8_lossy(stdout))));
+        }
+        if let Some(stderr) = stderr.filter(|stderr| display_stdout && !stderr.is_empty()) {
+            diagnostics.push(("stderr", quoted(&String::from_utf8_lossy(stderr))));
+        }
+
+        match *result {
+            TestResult::TrOk | TestResult::TrBench(_) => {
+                self.write_test_point(true, desc, None, &diagnostics)
+            }
+            TestResult::TrIgnored => {
+                let directive = match desc.ignore_message {
+                    Some(msg) => format!("SKIP {}", EscapedDescription(msg)),
+                    None => String::from("SKIP"),
+                };
+                self.write_test_point(true, desc, Some(&directive), &diagnostics)
+            }
+            TestResult::TrFailed | TestResult::TrFailedMsg(_) | TestResult::TrTimedFail => {
+                self.write_test_point(false, desc, None, &diagnostics)
+            }
+        }
+    }
+
+    fn write_run_finish(&mut self, state: &ConsoleTestState) -> io::Result<bool> {
+        let mut summary = format!(
+            "# test result: {}. {} passed; {} failed; {} ignored; {} measured; {} filtered out",
+            if state.failed == 0 { "ok" } else { "FAILED" },
+            state.passed,
+            state.failed,
+            state.ignored,
+            state.measured,
+            state.filtered_out
+        );
+        if let Some(ref exec_time) = state.exec_time {
+            summary.push_str(&format!("; finished in {exec_time}"));
+        }
+        summary.push('\n');
+        self.write_plain(summary)?;
+
+        Ok(state.failed == 0)
+    }
+}
+
+/// Escapes the characters that would otherwise end a TAP test point description, or the whole
+/// line.
+struct EscapedDescription<'a>(&'a str);
+
+impl std::fmt::Display for EscapedDescription<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for c in self.0.chars() {
+            match c {
+                '\\' | '#' => write!(f, "\\{c}")?,
+                '\n' => f.write_str("\\n")?,
+                '\r' => f.write_str("\\r")?,
+                c => write!(f, "{c}")?,
+            }
+        }
+        Ok(())
+    }
+}
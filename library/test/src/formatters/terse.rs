@@ -208,6 +208,7 @@ fn write_result(
         result: &TestResult,
         _: Option<&time::TestExecTime>,
         _: &[u8],
+        _: Option<&[u8]>,
         _: &ConsoleTestState,
     ) -> io::Result<()> {
         match *result {
@@ -54,16 +54,17 @@ pub mod test {
 use std::{
     collections::VecDeque,
     env, io,
-    io::prelude::Write,
+    io::prelude::{Read, Write},
     mem::ManuallyDrop,
     panic::{self, catch_unwind, AssertUnwindSafe, PanicInfo},
     process::{self, Command, Termination},
-    sync::mpsc::{channel, Sender},
+    sync::mpsc::{channel, Receiver, Sender},
     sync::{Arc, Mutex},
     thread,
     time::{Duration, Instant},
 };
 
+mod baseline;
 pub mod bench;
 mod cli;
 mod console;
@@ -98,6 +99,14 @@ pub mod test {
 // The default console test runner. It accepts the command line
 // arguments and a vector of test_descs.
 pub fn test_main(args: &[String], tests: Vec<TestDescAndFn>, options: Option<Options>) {
+    // If we're being run in SpawnedSecondary mode, run the test here. run_test
+    // will then exit the process.
+    if let Ok(name) = env::var(SECONDARY_TEST_INVOKER_VAR) {
+        env::remove_var(SECONDARY_TEST_INVOKER_VAR);
+        let panic_abort = options.is_some_and(|options| options.panic_abort);
+        run_secondary_test(&name, tests, panic_abort);
+    }
+
     let mut opts = match cli::parse_opts(args) {
         Some(Ok(o)) => o,
         Some(Err(msg)) => {
@@ -169,40 +178,37 @@ pub fn test_main_static(tests: &[&TestDescAndFn]) {
 /// This is the entry point for the main function generated by `rustc --test`
 /// when panic=abort.
 pub fn test_main_static_abort(tests: &[&TestDescAndFn]) {
-    // If we're being run in SpawnedSecondary mode, run the test here. run_test
-    // will then exit the process.
-    if let Ok(name) = env::var(SECONDARY_TEST_INVOKER_VAR) {
-        env::remove_var(SECONDARY_TEST_INVOKER_VAR);
+    let args = env::args().collect::<Vec<_>>();
+    let owned_tests: Vec<_> = tests.iter().map(make_owned_test).collect();
+    test_main(&args, owned_tests, Some(Options::new().panic_abort(true)))
+}
 
-        // Convert benchmarks to tests if we're not benchmarking.
-        let mut tests = tests.iter().map(make_owned_test).collect::<Vec<_>>();
-        if env::var(SECONDARY_TEST_BENCH_BENCHMARKS_VAR).is_ok() {
-            env::remove_var(SECONDARY_TEST_BENCH_BENCHMARKS_VAR);
-        } else {
-            tests = convert_benchmarks_to_tests(tests);
-        };
+/// Runs the test called `name` in this process, which was spawned by `spawn_test_subprocess`
+/// to run it, then exits.
+fn run_secondary_test(name: &str, mut tests: Vec<TestDescAndFn>, panic_abort: bool) -> ! {
+    // Convert benchmarks to tests if we're not benchmarking.
+    if env::var(SECONDARY_TEST_BENCH_BENCHMARKS_VAR).is_ok() {
+        env::remove_var(SECONDARY_TEST_BENCH_BENCHMARKS_VAR);
+    } else {
+        tests = convert_benchmarks_to_tests(tests);
+    };
 
-        let test = tests
-            .into_iter()
-            .find(|test| test.desc.name.as_slice() == name)
-            .unwrap_or_else(|| panic!("couldn't find a test with the provided name '{name}'"));
-        let TestDescAndFn { desc, testfn } = test;
-        match testfn.into_runnable() {
-            Runnable::Test(runnable_test) => {
-                if runnable_test.is_dynamic() {
-                    panic!("only static tests are supported");
-                }
-                run_test_in_spawned_subprocess(desc, runnable_test);
-            }
-            Runnable::Bench(_) => {
-                panic!("benchmarks should not be executed into child processes")
+    let test = tests
+        .into_iter()
+        .find(|test| test.desc.name.as_slice() == name)
+        .unwrap_or_else(|| panic!("couldn't find a test with the provided name '{name}'"));
+    let TestDescAndFn { desc, testfn } = test;
+    match testfn.into_runnable() {
+        Runnable::Test(runnable_test) => {
+            if runnable_test.is_dynamic() {
+                panic!("only static tests are supported");
             }
+            run_test_in_spawned_subprocess(desc, runnable_test, panic_abort);
+        }
+        Runnable::Bench(_) => {
+            panic!("benchmarks should not be executed into child processes")
         }
     }
-
-    let args = env::args().collect::<Vec<_>>();
-    let owned_tests: Vec<_> = tests.iter().map(make_owned_test).collect();
-    test_main(&args, owned_tests, Some(Options::new().panic_abort(true)))
 }
 
 /// Clones static values for putting into a dynamic vector, which test_main()
@@ -268,9 +274,50 @@ pub fn run_tests<F>(
 
     struct RunningTest {
         join_handle: Option<thread::JoinHandle<()>>,
+        deadline: Option<Deadline>,
+    }
+
+    // Tests running in this process cannot be killed once they exceed their timeout, so the
+    // runner reports them as failed and stops waiting for them instead.
+    struct Deadline {
+        desc: TestDesc,
+        timeout: Duration,
+        deadline: Instant,
     }
 
     impl RunningTest {
+        fn new(
+            join_handle: Option<thread::JoinHandle<()>>,
+            desc: &TestDesc,
+            timeout: Option<Duration>,
+            run_strategy: RunStrategy,
+        ) -> RunningTest {
+            // Tests run in a subprocess are killed by the thread waiting for them, and tests run
+            // synchronously have already completed.
+            let waits_for_thread =
+                matches!(run_strategy, RunStrategy::InProcess) && join_handle.is_some();
+            let deadline = timeout.filter(|_| waits_for_thread).and_then(|timeout| {
+                let deadline = Instant::now().checked_add(timeout)?;
+                Some(Deadline { desc: desc.clone(), timeout, deadline })
+            });
+            RunningTest { join_handle, deadline }
+        }
+
+        fn is_timed_out(&self, now: Instant) -> bool {
+            self.deadline.as_ref().is_some_and(|deadline| now >= deadline.deadline)
+        }
+
+        // Gives up on a test that exceeded its timeout, leaving its thread running.
+        fn abandon(&mut self, id: TestId) -> CompletedTest {
+            let Deadline { desc, timeout, .. } = self.deadline.take().unwrap();
+            self.join_handle = None;
+            let message = format!(
+                "test did not complete within {timeout:?}; \
+                 it cannot be killed and keeps running in the background"
+            );
+            CompletedTest::new(id, desc, TrFailedMsg(message), None, Vec::new())
+        }
+
         fn join(self, completed_test: &mut CompletedTest) {
             if let Some(join_handle) = self.join_handle {
                 if let Err(_) = join_handle.join() {
@@ -343,11 +390,20 @@ struct TimeoutEntry {
     let mut pending = 0;
 
     let (tx, rx) = channel::<CompletedTest>();
-    let run_strategy = if opts.options.panic_abort && !opts.force_run_in_process {
+    let run_strategy = if (opts.options.panic_abort || opts.isolate) && !opts.force_run_in_process {
         RunStrategy::SpawnPrimary
     } else {
         RunStrategy::InProcess
     };
+    // Dynamic tests cannot be looked up by name in a child process, so `--isolate` leaves them
+    // in this one. With panic=abort there is no choice but to fail on them in `run_test`.
+    let test_run_strategy = |test: &TestDescAndFn| {
+        if opts.isolate && !opts.options.panic_abort && test.testfn.is_dynamic() {
+            RunStrategy::InProcess
+        } else {
+            run_strategy
+        }
+    };
 
     let mut running_tests: TestMap = HashMap::default();
     let mut timeout_queue: VecDeque<TimeoutEntry> = VecDeque::new();
@@ -370,22 +426,63 @@ fn get_timed_out_tests(
         timed_out
     }
 
-    fn calc_timeout(timeout_queue: &VecDeque<TimeoutEntry>) -> Option<Duration> {
-        timeout_queue.front().map(|&TimeoutEntry { timeout: next_timeout, .. }| {
+    fn calc_timeout(
+        running_tests: &TestMap,
+        timeout_queue: &VecDeque<TimeoutEntry>,
+    ) -> Option<Duration> {
+        let next_warning = timeout_queue.front().map(|entry| entry.timeout);
+        let next_deadline = running_tests
+            .values()
+            .filter_map(|running_test| running_test.deadline.as_ref())
+            .map(|deadline| deadline.deadline)
+            .min();
+        next_warning.into_iter().chain(next_deadline).min().map(|next_timeout| {
             let now = Instant::now();
             if next_timeout >= now { next_timeout - now } else { Duration::new(0, 0) }
         })
     }
 
+    // Keeps receiving in the background, so that the threads of tests that are no longer waited for
+    // do not panic when they eventually report their results. The thread exits once the last of
+    // them is done.
+    fn drain_abandoned(rx: Receiver<CompletedTest>) {
+        thread::spawn(move || rx.into_iter().for_each(drop));
+    }
+
+    // Whether a test was given up on after exceeding its timeout. Its thread may still report a
+    // result later on, so the receiver has to outlive this function.
+    let mut abandoned_tests = false;
+
     if concurrency == 1 {
         while !remaining.is_empty() {
             let (id, test) = remaining.pop_front().unwrap();
-            let event = TestEvent::TeWait(test.desc.clone());
+            let desc = test.desc.clone();
+            let test_timeout = desc.timeout.or(opts.test_timeout);
+            let strategy = test_run_strategy(&test);
+            let event = TestEvent::TeWait(desc.clone());
             notify_about_test_event(event)?;
-            let join_handle = run_test(opts, !opts.run_tests, id, test, run_strategy, tx.clone());
+            let join_handle = run_test(opts, !opts.run_tests, id, test, strategy, tx.clone());
+            let mut running_test = RunningTest::new(join_handle, &desc, test_timeout, strategy);
             // Wait for the test to complete.
-            let mut completed_test = rx.recv().unwrap();
-            RunningTest { join_handle }.join(&mut completed_test);
+            let mut completed_test = loop {
+                let res = match &running_test.deadline {
+                    Some(deadline) => {
+                        rx.recv_timeout(deadline.deadline.saturating_duration_since(Instant::now()))
+                    }
+                    None => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+                };
+                match res {
+                    Ok(completed_test) if completed_test.id == id => break completed_test,
+                    // A previously abandoned test completed after all.
+                    Ok(_) => {}
+                    Err(RecvTimeoutError::Timeout) => {
+                        abandoned_tests = true;
+                        break running_test.abandon(id);
+                    }
+                    Err(RecvTimeoutError::Disconnected) => panic!("test result channel closed"),
+                }
+            };
+            running_test.join(&mut completed_test);
 
             let fail_fast = match completed_test.result {
                 TrIgnored | TrOk | TrBench(_) => false,
@@ -396,6 +493,9 @@ fn calc_timeout(timeout_queue: &VecDeque<TimeoutEntry>) -> Option<Duration> {
             notify_about_test_event(event)?;
 
             if fail_fast {
+                if abandoned_tests {
+                    drain_abandoned(rx);
+                }
                 return Ok(());
             }
         }
@@ -405,19 +505,21 @@ fn calc_timeout(timeout_queue: &VecDeque<TimeoutEntry>) -> Option<Duration> {
                 let (id, test) = remaining.pop_front().unwrap();
                 let timeout = time::get_default_test_timeout();
                 let desc = test.desc.clone();
+                let test_timeout = desc.timeout.or(opts.test_timeout);
+                let strategy = test_run_strategy(&test);
 
                 let event = TestEvent::TeWait(desc.clone());
                 notify_about_test_event(event)?; //here no pad
-                let join_handle =
-                    run_test(opts, !opts.run_tests, id, test, run_strategy, tx.clone());
-                running_tests.insert(id, RunningTest { join_handle });
+                let join_handle = run_test(opts, !opts.run_tests, id, test, strategy, tx.clone());
+                let running_test = RunningTest::new(join_handle, &desc, test_timeout, strategy);
+                running_tests.insert(id, running_test);
                 timeout_queue.push_back(TimeoutEntry { id, desc, timeout });
                 pending += 1;
             }
 
             let mut res;
             loop {
-                if let Some(timeout) = calc_timeout(&timeout_queue) {
+                if let Some(timeout) = calc_timeout(&running_tests, &timeout_queue) {
                     res = rx.recv_timeout(timeout);
                     for test in get_timed_out_tests(&running_tests, &mut timeout_queue) {
                         let event = TestEvent::TeTimeout(test);
@@ -426,7 +528,16 @@ fn calc_timeout(timeout_queue: &VecDeque<TimeoutEntry>) -> Option<Duration> {
 
                     match res {
                         Err(RecvTimeoutError::Timeout) => {
-                            // Result is not yet ready, continue waiting.
+                            // Result is not yet ready, unless a test has run out of time.
+                            let now = Instant::now();
+                            let timed_out = running_tests
+                                .iter_mut()
+                                .find(|(_, running_test)| running_test.is_timed_out(now));
+                            if let Some((&id, running_test)) = timed_out {
+                                abandoned_tests = true;
+                                res = Ok(running_test.abandon(id));
+                                break;
+                            }
                         }
                         _ => {
                             // We've got a result, stop the loop.
@@ -440,7 +551,10 @@ fn calc_timeout(timeout_queue: &VecDeque<TimeoutEntry>) -> Option<Duration> {
             }
 
             let mut completed_test = res.unwrap();
-            let running_test = running_tests.remove(&completed_test.id).unwrap();
+            let Some(running_test) = running_tests.remove(&completed_test.id) else {
+                // A previously abandoned test completed after all.
+                continue;
+            };
             running_test.join(&mut completed_test);
 
             let fail_fast = match completed_test.result {
@@ -454,7 +568,7 @@ fn calc_timeout(timeout_queue: &VecDeque<TimeoutEntry>) -> Option<Duration> {
 
             if fail_fast {
                 // Prevent remaining test threads from panicking
-                std::mem::forget(rx);
+                drain_abandoned(rx);
                 return Ok(());
             }
         }
@@ -467,13 +581,22 @@ fn calc_timeout(timeout_queue: &VecDeque<TimeoutEntry>) -> Option<Duration> {
             notify_about_test_event(event)?;
             let join_handle = run_test(opts, false, id, b, run_strategy, tx.clone());
             // Wait for the test to complete.
-            let mut completed_test = rx.recv().unwrap();
-            RunningTest { join_handle }.join(&mut completed_test);
+            let mut completed_test = loop {
+                let completed_test = rx.recv().unwrap();
+                if completed_test.id == id {
+                    break completed_test;
+                }
+            };
+            RunningTest { join_handle, deadline: None }.join(&mut completed_test);
 
             let event = TestEvent::TeResult(completed_test);
             notify_about_test_event(event)?;
         }
     }
+
+    if abandoned_tests {
+        drain_abandoned(rx);
+    }
     Ok(())
 }
 
@@ -567,6 +690,7 @@ pub fn run_test(
             let nocapture = opts.nocapture;
             let time_options = opts.time_options;
             let bench_benchmarks = opts.bench_benchmarks;
+            let timeout = desc.timeout.or(opts.test_timeout);
 
             let runtest = move || match strategy {
                 RunStrategy::InProcess => run_test_in_process(
@@ -586,6 +710,7 @@ pub fn run_test(
                     monitor_ch,
                     time_options,
                     bench_benchmarks,
+                    timeout,
                 ),
             };
 
@@ -614,7 +739,7 @@ pub fn run_test(
         }
         Runnable::Bench(runnable_bench) => {
             // Benchmarks aren't expected to panic, so we run them all in-process.
-            runnable_bench.run(id, &desc, &monitor_ch, opts.nocapture);
+            runnable_bench.run(id, &desc, &monitor_ch, opts.nocapture, opts.bench_options);
             None
         }
     }
@@ -684,8 +809,9 @@ fn spawn_test_subprocess(
     monitor_ch: Sender<CompletedTest>,
     time_opts: Option<time::TestTimeOptions>,
     bench_benchmarks: bool,
+    timeout: Option<Duration>,
 ) {
-    let (result, test_output, exec_time) = (|| {
+    let (result, stdout, stderr, exec_time, exit_status) = (|| {
         let args = env::args().collect::<Vec<_>>();
         let current_exe = &args[0];
 
@@ -697,14 +823,21 @@ fn spawn_test_subprocess(
         if nocapture {
             command.stdout(process::Stdio::inherit());
             command.stderr(process::Stdio::inherit());
+        } else {
+            command.stdout(process::Stdio::piped());
+            command.stderr(process::Stdio::piped());
         }
 
         let start = report_time.then(Instant::now);
-        let output = match command.output() {
+        let output = match timeout {
+            Some(timeout) => output_with_timeout(&mut command, timeout),
+            None => command.output().map(|output| (output, false)),
+        };
+        let (output, killed) = match output {
             Ok(out) => out,
             Err(e) => {
                 let err = format!("Failed to spawn {} as child for test: {:?}", args[0], e);
-                return (TrFailed, err.into_bytes(), None);
+                return (TrFailed, err.into_bytes(), None, None, None);
             }
         };
         let exec_time = start.map(|start| {
@@ -712,10 +845,13 @@ fn spawn_test_subprocess(
             TestExecTime(duration)
         });
 
-        let std::process::Output { stdout, stderr, status } = output;
-        let mut test_output = stdout;
-        formatters::write_stderr_delimiter(&mut test_output, &desc.name);
-        test_output.extend_from_slice(&stderr);
+        let std::process::Output { stdout, mut stderr, status } = output;
+
+        if killed {
+            let timeout = timeout.unwrap();
+            let message = format!("test did not complete within {timeout:?} and was killed");
+            return (TrFailedMsg(message), stdout, Some(stderr), exec_time, Some(status));
+        }
 
         let result = match (|| -> Result<TestResult, String> {
             let exit_code = get_exit_code(status)?;
@@ -723,19 +859,86 @@ fn spawn_test_subprocess(
         })() {
             Ok(r) => r,
             Err(e) => {
-                write!(&mut test_output, "Unexpected error: {e}").unwrap();
+                write!(&mut stderr, "Unexpected error: {e}").unwrap();
                 TrFailed
             }
         };
 
-        (result, test_output, exec_time)
+        (result, stdout, Some(stderr), exec_time, Some(status))
     })();
 
-    let message = CompletedTest::new(id, desc, result, exec_time, test_output);
+    let message = CompletedTest {
+        stderr,
+        exit_status,
+        ..CompletedTest::new(id, desc, result, exec_time, stdout)
+    };
     monitor_ch.send(message).unwrap();
 }
 
-fn run_test_in_spawned_subprocess(desc: TestDesc, runnable_test: RunnableTest) -> ! {
+/// Like `Command::output`, but kills the child once `timeout` has elapsed. The returned flag
+/// tells whether the child had to be killed.
+fn output_with_timeout(
+    command: &mut Command,
+    timeout: Duration,
+) -> io::Result<(process::Output, bool)> {
+    fn read_in_background<R: Read + Send + 'static>(
+        pipe: Option<R>,
+    ) -> thread::JoinHandle<io::Result<Vec<u8>>> {
+        thread::spawn(move || {
+            let mut buf = Vec::new();
+            if let Some(mut pipe) = pipe {
+                pipe.read_to_end(&mut buf)?;
+            }
+            Ok(buf)
+        })
+    }
+
+    let Some(deadline) = Instant::now().checked_add(timeout) else {
+        return command.output().map(|output| (output, false));
+    };
+    let mut child = command.stdin(process::Stdio::null()).spawn()?;
+    let stdout = read_in_background(child.stdout.take());
+    let stderr = read_in_background(child.stderr.take());
+
+    // `Child::kill` needs the same `Child` that `wait` would block on, so the child is polled
+    // instead, backing off so that long-running tests don't keep waking this thread up.
+    let mut interval = Duration::from_millis(1);
+    let mut killed = false;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        let now = Instant::now();
+        if now >= deadline {
+            // Fails only if the child exited in the meantime, which still counts as a timeout.
+            let _ = child.kill();
+            killed = true;
+            break child.wait()?;
+        }
+        thread::sleep((deadline - now).min(interval));
+        interval = (interval * 2).min(Duration::from_millis(100));
+    };
+
+    let stdout = stdout.join().unwrap()?;
+    let stderr = stderr.join().unwrap()?;
+    Ok((process::Output { status, stdout, stderr }, killed))
+}
+
+fn run_test_in_spawned_subprocess(
+    desc: TestDesc,
+    runnable_test: RunnableTest,
+    panic_abort: bool,
+) -> ! {
+    if !panic_abort {
+        // Panics the test catches itself must not end the process, so the result is only known
+        // once the test has returned, just like when it runs in the test harness process.
+        let test_result = match fold_err(catch_unwind(AssertUnwindSafe(|| runnable_test.run()))) {
+            Ok(()) => calc_result(&desc, Ok(()), &None, &None),
+            Err(e) => calc_result(&desc, Err(e.as_ref()), &None, &None),
+        };
+        exit_with_result(&test_result);
+    }
+
     let builtin_panic_hook = panic::take_hook();
     let record_result = Arc::new(move |panic_info: Option<&'_ PanicInfo<'_>>| {
         let test_result = match panic_info {
@@ -743,21 +946,11 @@ fn run_test_in_spawned_subprocess(desc: TestDesc, runnable_test: RunnableTest) -
             None => calc_result(&desc, Ok(()), &None, &None),
         };
 
-        // We don't support serializing TrFailedMsg, so just
-        // print the message out to stderr.
-        if let TrFailedMsg(msg) = &test_result {
-            eprintln!("{msg}");
-        }
-
         if let Some(info) = panic_info {
             builtin_panic_hook(info);
         }
 
-        if let TrOk = test_result {
-            process::exit(test_result::TR_OK);
-        } else {
-            process::exit(test_result::TR_FAILED);
-        }
+        exit_with_result(&test_result);
     });
     let record_result2 = record_result.clone();
     panic::set_hook(Box::new(move |info| record_result2(Some(info))));
@@ -767,3 +960,19 @@ fn run_test_in_spawned_subprocess(desc: TestDesc, runnable_test: RunnableTest) -
     record_result(None);
     unreachable!("panic=abort callback should have exited the process")
 }
+
+/// Reports the result of a test run by `run_test_in_spawned_subprocess` to the parent process
+/// through the exit code.
+fn exit_with_result(test_result: &TestResult) -> ! {
+    // We don't support serializing TrFailedMsg, so just
+    // print the message out to stderr.
+    if let TrFailedMsg(msg) = test_result {
+        eprintln!("{msg}");
+    }
+
+    if let TrOk = test_result {
+        process::exit(test_result::TR_OK);
+    } else {
+        process::exit(test_result::TR_FAILED);
+    }
+}
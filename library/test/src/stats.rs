@@ -300,3 +300,154 @@ pub fn winsorize(samples: &mut [f64], pct: f64) {
         }
     }
 }
+
+/// Number of samples lying outside of Tukey's fences: mild outliers are more than 1.5 times the
+/// interquartile range below the first or above the third quartile, severe outliers more than 3
+/// times.
+///
+/// See: <https://en.wikipedia.org/wiki/Outlier#Tukey's_fences>
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Outliers {
+    pub low_severe: usize,
+    pub low_mild: usize,
+    pub high_mild: usize,
+    pub high_severe: usize,
+}
+
+impl Outliers {
+    /// Classify the outliers of a sample set. Must be called before winsorizing it.
+    pub fn classify(samples: &[f64]) -> Outliers {
+        let (q1, _, q3) = samples.quartiles();
+        let iqr = q3 - q1;
+        let mut outliers = Outliers::default();
+        for &samp in samples {
+            if samp < q1 - 3.0 * iqr {
+                outliers.low_severe += 1;
+            } else if samp < q1 - 1.5 * iqr {
+                outliers.low_mild += 1;
+            } else if samp > q3 + 3.0 * iqr {
+                outliers.high_severe += 1;
+            } else if samp > q3 + 1.5 * iqr {
+                outliers.high_mild += 1;
+            }
+        }
+        outliers
+    }
+
+    pub fn total(&self) -> usize {
+        self.low_severe + self.low_mild + self.high_mild + self.high_severe
+    }
+}
+
+/// Welch's t-test: the two-sided p-value of the hypothesis that two sample sets, given by their
+/// means, variances and sizes, come from distributions with the same mean. Unlike Student's
+/// t-test, it does not assume that both distributions have the same variance.
+///
+/// See: <https://en.wikipedia.org/wiki/Welch%27s_t-test>
+pub fn welch_t_test(
+    mean_a: f64,
+    var_a: f64,
+    n_a: usize,
+    mean_b: f64,
+    var_b: f64,
+    n_b: usize,
+) -> f64 {
+    assert!(n_a > 1 && n_b > 1);
+    let (se_a, se_b) = (var_a / n_a as f64, var_b / n_b as f64);
+    let se = se_a + se_b;
+    if se == 0.0 {
+        return if mean_a == mean_b { 1.0 } else { 0.0 };
+    }
+    let t = (mean_a - mean_b) / se.sqrt();
+    // Welch–Satterthwaite approximation of the degrees of freedom.
+    let df = se * se / (se_a * se_a / (n_a - 1) as f64 + se_b * se_b / (n_b - 1) as f64);
+    regularized_incomplete_beta(df / (df + t * t), df / 2.0, 0.5)
+}
+
+// Helper function: the regularized incomplete beta function I_x(a, b), evaluated with a continued
+// fraction. The two-sided tail probability of Student's t-distribution with `df` degrees of
+// freedom at `t` is I_{df/(df+t^2)}(df/2, 1/2).
+fn regularized_incomplete_beta(x: f64, a: f64, b: f64) -> f64 {
+    if x <= 0.0 {
+        return 0.0;
+    }
+    if x >= 1.0 {
+        return 1.0;
+    }
+    let ln_front = ln_gamma(a + b) - ln_gamma(a) - ln_gamma(b) + a * x.ln() + b * (1.0 - x).ln();
+    // The continued fraction converges quickly only on one side of the mean of the distribution,
+    // use the symmetry I_x(a, b) = 1 - I_{1-x}(b, a) on the other.
+    if x < (a + 1.0) / (a + b + 2.0) {
+        ln_front.exp() * beta_continued_fraction(x, a, b) / a
+    } else {
+        1.0 - ln_front.exp() * beta_continued_fraction(1.0 - x, b, a) / b
+    }
+}
+
+// Evaluates the continued fraction of the incomplete beta function with the modified Lentz method.
+fn beta_continued_fraction(x: f64, a: f64, b: f64) -> f64 {
+    const MAX_ITERATIONS: u32 = 300;
+    const EPSILON: f64 = 1e-14;
+    const TINY: f64 = 1e-300;
+
+    let mut c = 1.0;
+    let mut d = 1.0 - (a + b) * x / (a + 1.0);
+    if d.abs() < TINY {
+        d = TINY;
+    }
+    d = 1.0 / d;
+    let mut fraction = d;
+    // Folds the next coefficient into the fraction, returning the relative change it made.
+    let mut step = |coefficient: f64| {
+        d = 1.0 + coefficient * d;
+        if d.abs() < TINY {
+            d = TINY;
+        }
+        c = 1.0 + coefficient / c;
+        if c.abs() < TINY {
+            c = TINY;
+        }
+        d = 1.0 / d;
+        fraction *= c * d;
+        c * d
+    };
+
+    for m in 1..MAX_ITERATIONS {
+        let m = m as f64;
+        step(m * (b - m) * x / ((a + 2.0 * m - 1.0) * (a + 2.0 * m)));
+        let delta = step(-(a + m) * (a + b + m) * x / ((a + 2.0 * m) * (a + 2.0 * m + 1.0)));
+        if (delta - 1.0).abs() < EPSILON {
+            break;
+        }
+    }
+    fraction
+}
+
+// Helper function: the natural logarithm of the gamma function, using the Lanczos approximation.
+fn ln_gamma(x: f64) -> f64 {
+    const G: f64 = 7.0;
+    const COEFFICIENTS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+
+    if x < 0.5 {
+        // Reflection formula.
+        let pi = std::f64::consts::PI;
+        return (pi / (pi * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let mut sum = COEFFICIENTS[0];
+    for (i, &coefficient) in COEFFICIENTS.iter().enumerate().skip(1) {
+        sum += coefficient / (x + i as f64);
+    }
+    let t = x + G + 0.5;
+    0.5 * (2.0 * std::f64::consts::PI).ln() + (x + 0.5) * t.ln() - t + sum.ln()
+}
@@ -589,3 +589,31 @@ pub fn sum_many_f64(b: &mut Bencher) {
 
 #[bench]
 pub fn no_iter(_: &mut Bencher) {}
+
+#[test]
+fn test_outliers() {
+    let mut samples = vec![10.0; 20];
+    samples.extend_from_slice(&[11.0; 20]);
+    // The quartiles are 10 and 11, so the mild fences are at 8.5 and 12.5, the severe ones at 7
+    // and 14.
+    samples.extend_from_slice(&[8.0, 6.0, 6.5, 13.0, 20.0]);
+    let outliers = Outliers::classify(&samples);
+    assert_eq!(outliers, Outliers { low_severe: 2, low_mild: 1, high_mild: 1, high_severe: 1 });
+    assert_eq!(outliers.total(), 5);
+
+    assert_eq!(Outliers::classify(&[1.0, 2.0, 3.0, 4.0, 5.0]).total(), 0);
+}
+
+#[test]
+fn test_welch_t_test() {
+    // R: t.test(c(1, 2, 3, 4, 5), c(2, 4, 6, 8, 10))
+    let p = welch_t_test(3.0, 2.5, 5, 6.0, 10.0, 5);
+    assert_approx_eq!(p, 0.1075312);
+    // R: 2 * pt(-2, 10)
+    assert_approx_eq!(regularized_incomplete_beta(10.0 / 14.0, 5.0, 0.5), 0.07338803);
+
+    assert_eq!(welch_t_test(1.0, 1.0, 10, 1.0, 1.0, 10), 1.0);
+    assert_eq!(welch_t_test(1.0, 0.0, 10, 1.0, 0.0, 10), 1.0);
+    assert_eq!(welch_t_test(1.0, 0.0, 10, 2.0, 0.0, 10), 0.0);
+    assert!(welch_t_test(100.0, 1.0, 50, 110.0, 1.0, 50) < 1e-12);
+}
@@ -3,7 +3,7 @@
 use crate::{
     bench::Bencher,
     console::OutputLocation,
-    formatters::PrettyFormatter,
+    formatters::{GithubFormatter, JsonFormatter, OutputFormatter, PrettyFormatter, TapFormatter},
     options::OutputFormat,
     test::{
         filter_tests,
@@ -37,6 +37,7 @@ fn new() -> TestOpts {
             filters: vec![],
             filter_exact: false,
             force_run_in_process: false,
+            isolate: false,
             exclude_should_panic: false,
             run_ignored: RunIgnored::No,
             run_tests: false,
@@ -50,6 +51,9 @@ fn new() -> TestOpts {
             test_threads: None,
             skip: vec![],
             time_options: None,
+            test_timeout: None,
+            bench_options: Default::default(),
+            baseline_options: Default::default(),
             options: Options::new(),
             fail_fast: false,
         }
@@ -72,6 +76,7 @@ fn one_ignored_one_unignored_test() -> Vec<TestDescAndFn> {
                 compile_fail: false,
                 no_run: false,
                 test_type: TestType::Unknown,
+                timeout: None,
             },
             testfn: DynTestFn(Box::new(move || Ok(()))),
         },
@@ -89,6 +94,7 @@ fn one_ignored_one_unignored_test() -> Vec<TestDescAndFn> {
                 compile_fail: false,
                 no_run: false,
                 test_type: TestType::Unknown,
+                timeout: None,
             },
             testfn: DynTestFn(Box::new(move || Ok(()))),
         },
@@ -114,6 +120,7 @@ fn f() -> Result<(), String> {
             compile_fail: false,
             no_run: false,
             test_type: TestType::Unknown,
+            timeout: None,
         },
         testfn: DynTestFn(Box::new(f)),
     };
@@ -142,6 +149,7 @@ fn f() -> Result<(), String> {
             compile_fail: false,
             no_run: false,
             test_type: TestType::Unknown,
+            timeout: None,
         },
         testfn: DynTestFn(Box::new(f)),
     };
@@ -173,6 +181,7 @@ fn f() -> Result<(), String> {
             compile_fail: false,
             no_run: false,
             test_type: TestType::Unknown,
+            timeout: None,
         },
         testfn: DynTestFn(Box::new(f)),
     };
@@ -204,6 +213,7 @@ fn f() -> Result<(), String> {
             compile_fail: false,
             no_run: false,
             test_type: TestType::Unknown,
+            timeout: None,
         },
         testfn: DynTestFn(Box::new(f)),
     };
@@ -240,6 +250,7 @@ fn f() -> Result<(), String> {
             compile_fail: false,
             no_run: false,
             test_type: TestType::Unknown,
+            timeout: None,
         },
         testfn: DynTestFn(Box::new(f)),
     };
@@ -280,6 +291,7 @@ fn f() -> Result<(), String> {
             compile_fail: false,
             no_run: false,
             test_type: TestType::Unknown,
+            timeout: None,
         },
         testfn: DynTestFn(Box::new(f)),
     };
@@ -314,6 +326,7 @@ fn f() -> Result<(), String> {
                 compile_fail: false,
                 no_run: false,
                 test_type: TestType::Unknown,
+                timeout: None,
             },
             testfn: DynTestFn(Box::new(f)),
         };
@@ -347,6 +360,7 @@ fn f() -> Result<(), String> {
             compile_fail: false,
             no_run: false,
             test_type: TestType::Unknown,
+            timeout: None,
         },
         testfn: DynTestFn(Box::new(f)),
     };
@@ -389,6 +403,7 @@ fn f() -> Result<(), String> {
             compile_fail: false,
             no_run: false,
             test_type,
+            timeout: None,
         },
         testfn: DynTestFn(Box::new(f)),
     };
@@ -433,6 +448,7 @@ fn typed_test_desc(test_type: TestType) -> TestDesc {
         compile_fail: false,
         no_run: false,
         test_type,
+        timeout: None,
     }
 }
 
@@ -495,6 +511,37 @@ fn parse_include_ignored_flag() {
     assert_eq!(opts.run_ignored, RunIgnored::Yes);
 }
 
+#[test]
+fn parse_isolate_flag() {
+    let args =
+        vec!["progname".to_string(), "-Zunstable-options".to_string(), "--isolate".to_string()];
+    let opts = parse_opts(&args).unwrap().unwrap();
+    assert!(opts.isolate);
+
+    let args = vec![
+        "progname".to_string(),
+        "-Zunstable-options".to_string(),
+        "--isolate".to_string(),
+        "--force-run-in-process".to_string(),
+    ];
+    assert!(parse_opts(&args).unwrap().is_err());
+}
+
+#[test]
+fn parse_test_timeout_option() {
+    let args = vec![
+        "progname".to_string(),
+        "-Zunstable-options".to_string(),
+        "--test-timeout".to_string(),
+        "5".to_string(),
+    ];
+    let opts = parse_opts(&args).unwrap().unwrap();
+    assert_eq!(opts.test_timeout, Some(Duration::from_secs(5)));
+
+    let args = vec!["progname".to_string(), "--test-timeout".to_string(), "5".to_string()];
+    assert!(parse_opts(&args).unwrap().is_err());
+}
+
 #[test]
 pub fn filter_for_ignored_option() {
     // When we run ignored tests the test filter should filter out all the
@@ -550,6 +597,7 @@ pub fn exclude_should_panic_option() {
             compile_fail: false,
             no_run: false,
             test_type: TestType::Unknown,
+            timeout: None,
         },
         testfn: DynTestFn(Box::new(move || Ok(()))),
     });
@@ -579,6 +627,7 @@ fn tests() -> Vec<TestDescAndFn> {
                     compile_fail: false,
                     no_run: false,
                     test_type: TestType::Unknown,
+                    timeout: None,
                 },
                 testfn: DynTestFn(Box::new(move || Ok(()))),
             })
@@ -675,6 +724,7 @@ fn testfn() -> Result<(), String> {
                 compile_fail: false,
                 no_run: false,
                 test_type: TestType::Unknown,
+                timeout: None,
             },
             testfn: DynTestFn(Box::new(testfn)),
         };
@@ -809,9 +859,10 @@ fn f(_: &mut Bencher) -> Result<(), String> {
         compile_fail: false,
         no_run: false,
         test_type: TestType::Unknown,
+        timeout: None,
     };
 
-    crate::bench::benchmark(TestId(0), desc, tx, true, f);
+    crate::bench::benchmark(TestId(0), desc, tx, true, Default::default(), f);
     rx.recv().unwrap();
 }
 
@@ -837,9 +888,10 @@ fn f(b: &mut Bencher) -> Result<(), String> {
         compile_fail: false,
         no_run: false,
         test_type: TestType::Unknown,
+        timeout: None,
     };
 
-    crate::bench::benchmark(TestId(0), desc, tx, true, f);
+    crate::bench::benchmark(TestId(0), desc, tx, true, Default::default(), f);
     rx.recv().unwrap();
 }
 
@@ -858,6 +910,7 @@ fn should_sort_failures_before_printing_them() {
         compile_fail: false,
         no_run: false,
         test_type: TestType::Unknown,
+        timeout: None,
     };
 
     let test_b = TestDesc {
@@ -873,6 +926,7 @@ fn should_sort_failures_before_printing_them() {
         compile_fail: false,
         no_run: false,
         test_type: TestType::Unknown,
+        timeout: None,
     };
 
     let mut out = PrettyFormatter::new(OutputLocation::Raw(Vec::new()), false, 10, false, None);
@@ -892,6 +946,8 @@ fn should_sort_failures_before_printing_them() {
         not_failures: Vec::new(),
         ignores: Vec::new(),
         time_failures: Vec::new(),
+        baseline: None,
+        new_baseline: None,
     };
 
     out.write_failures(&st).unwrap();
@@ -925,6 +981,7 @@ fn f(_: &mut Bencher) -> Result<(), String> {
             compile_fail: false,
             no_run: false,
             test_type: TestType::Unknown,
+            timeout: None,
         },
         testfn: DynBenchFn(Box::new(f)),
     };
@@ -939,3 +996,222 @@ fn f(_: &mut Bencher) -> Result<(), String> {
     let result = rx.recv().unwrap().result;
     assert_eq!(result, TrFailed);
 }
+
+fn hanging_test_template(
+    test_threads: usize,
+    test_timeout: Duration,
+    hanging_test_timeout: Option<Duration>,
+) -> Vec<TestResult> {
+    let test = |name, timeout, f: fn() -> Result<(), String>| TestDescAndFn {
+        desc: TestDesc {
+            name: StaticTestName(name),
+            ignore: false,
+            ignore_message: None,
+            source_file: "",
+            start_line: 0,
+            start_col: 0,
+            end_line: 0,
+            end_col: 0,
+            should_panic: ShouldPanic::No,
+            compile_fail: false,
+            no_run: false,
+            test_type: TestType::Unknown,
+            timeout,
+        },
+        testfn: DynTestFn(Box::new(f)),
+    };
+    let tests = vec![
+        test("hangs", hanging_test_timeout, || {
+            loop {
+                thread::park();
+            }
+        }),
+        test("passes", None, || Ok(())),
+    ];
+
+    let (tx, rx) = channel();
+    let notify = move |event: TestEvent| {
+        if let TestEvent::TeResult(result) = event {
+            tx.send(result).unwrap();
+        }
+        Ok(())
+    };
+    let opts = TestOpts {
+        run_tests: true,
+        test_threads: Some(test_threads),
+        test_timeout: Some(test_timeout),
+        ..TestOpts::new()
+    };
+    run_tests(&opts, tests, notify).unwrap();
+    let mut results: Vec<_> = rx.iter().collect();
+    results.sort_by_key(|completed_test| completed_test.id.0);
+    results.into_iter().map(|completed_test| completed_test.result).collect()
+}
+
+#[test]
+#[cfg(not(target_os = "emscripten"))]
+fn test_hanging_test_times_out() {
+    for test_threads in [1, 2] {
+        let results = hanging_test_template(test_threads, Duration::from_millis(100), None);
+        assert!(
+            matches!(&results[0], TrFailedMsg(msg) if msg.contains("100ms")),
+            "{:?}",
+            results[0]
+        );
+        assert_eq!(results[1], TrOk);
+    }
+}
+
+#[test]
+#[cfg(not(target_os = "emscripten"))]
+fn test_timeout_overrides_test_timeout_option() {
+    for test_threads in [1, 2] {
+        let results = hanging_test_template(
+            test_threads,
+            Duration::from_secs(3600),
+            Some(Duration::from_millis(100)),
+        );
+        assert!(
+            matches!(&results[0], TrFailedMsg(msg) if msg.contains("100ms")),
+            "{:?}",
+            results[0]
+        );
+        assert_eq!(results[1], TrOk);
+    }
+}
+
+#[test]
+#[cfg(unix)]
+fn test_output_with_timeout_kills_child() {
+    let mut command = Command::new("sleep");
+    command.arg("60").stdout(process::Stdio::piped()).stderr(process::Stdio::piped());
+    let start = Instant::now();
+    let (output, killed) = output_with_timeout(&mut command, Duration::from_millis(100)).unwrap();
+    assert!(killed);
+    assert!(!output.status.success());
+    assert!(start.elapsed() < Duration::from_secs(60));
+
+    let mut command = Command::new("true");
+    let (output, killed) = output_with_timeout(&mut command, Duration::from_secs(60)).unwrap();
+    assert!(!killed);
+    assert!(output.status.success());
+}
+
+fn formatter_test_desc(name: &'static str, ignore_message: Option<&'static str>) -> TestDesc {
+    TestDesc {
+        name: StaticTestName(name),
+        ignore: ignore_message.is_some(),
+        ignore_message,
+        source_file: "src/lib.rs",
+        start_line: 3,
+        start_col: 4,
+        end_line: 3,
+        end_col: 10,
+        should_panic: ShouldPanic::No,
+        compile_fail: false,
+        no_run: false,
+        test_type: TestType::UnitTest,
+        timeout: None,
+    }
+}
+
+#[test]
+fn test_tap_output() {
+    let st = console::ConsoleTestState::new(&TestOpts::new()).unwrap();
+    let mut out = TapFormatter::new(OutputLocation::Raw(Vec::new()));
+
+    out.write_run_start(3, None).unwrap();
+    out.write_result(&formatter_test_desc("a", None), &TrOk, None, b"", None, &st).unwrap();
+    out.write_result(
+        &formatter_test_desc("b#1", None),
+        &TrFailedMsg("boom".to_string()),
+        None,
+        b"out\n",
+        None,
+        &st,
+    )
+    .unwrap();
+    out.write_result(&formatter_test_desc("c", Some("slow")), &TrIgnored, None, b"", None, &st)
+        .unwrap();
+
+    let s = match out.output_location() {
+        OutputLocation::Raw(m) => String::from_utf8_lossy(m),
+        OutputLocation::Pretty(_) => unreachable!(),
+    };
+    assert_eq!(
+        s,
+        "TAP version 14\n\
+         1..3\n\
+         ok 1 - a\n\
+         not ok 2 - b\\#1\n  \
+           ---\n  \
+           message: \"boom\"\n  \
+           stdout: \"out\\n\"\n  \
+           ...\n\
+         ok 3 - c # SKIP slow\n"
+    );
+}
+
+#[test]
+fn test_github_annotations() {
+    let st = console::ConsoleTestState::new(&TestOpts::new()).unwrap();
+    let pretty = PrettyFormatter::new(OutputLocation::Raw(Vec::new()), false, 10, true, None);
+    let mut out = GithubFormatter::new(pretty);
+
+    let stdout = "thread 'a' panicked at src/lib.rs:7:9:\n\
+                  assertion failed: x, y\n\
+                  50% done\n\
+                  note: run with `RUST_BACKTRACE=1` environment variable to display a backtrace\n";
+    out.write_result(
+        &formatter_test_desc("a", None),
+        &TrFailed,
+        None,
+        stdout.as_bytes(),
+        None,
+        &st,
+    )
+    .unwrap();
+    out.write_result(&formatter_test_desc("b", None), &TrFailed, None, b"", None, &st).unwrap();
+    out.write_result(&formatter_test_desc("c", None), &TrOk, None, b"", None, &st).unwrap();
+
+    let s = match out.output_location() {
+        OutputLocation::Raw(m) => String::from_utf8_lossy(m),
+        OutputLocation::Pretty(_) => unreachable!(),
+    };
+    let annotations: Vec<_> = s.lines().filter(|line| line.starts_with("::")).collect();
+    assert_eq!(
+        annotations,
+        [
+            "::error file=src/lib.rs,line=7,col=9,title=a::assertion failed: x, y%0A50%25 done",
+            "::error file=src/lib.rs,line=3,col=4,title=b::test failed",
+        ]
+    );
+}
+
+#[test]
+fn test_json_events() {
+    let st = console::ConsoleTestState::new(&TestOpts::new()).unwrap();
+    let mut out = JsonFormatter::new(OutputLocation::Raw(Vec::new()));
+
+    let desc = TestDesc {
+        should_panic: ShouldPanic::YesWithMessage("bad \"input\""),
+        ..formatter_test_desc("a", None)
+    };
+    out.write_run_start(1, None).unwrap();
+    out.write_test_start(&desc).unwrap();
+    out.write_result(&desc, &TrFailed, None, b"out", Some(b"err"), &st).unwrap();
+
+    let s = match out.output_location() {
+        OutputLocation::Raw(m) => String::from_utf8_lossy(m),
+        OutputLocation::Pretty(_) => unreachable!(),
+    };
+    let events: Vec<_> = s.lines().collect();
+    assert_eq!(
+        events,
+        [
+            r#"{ "type": "suite", "event": "started", "schema_version": 1, "test_count": 1 }"#,
+            r#"{ "type": "test", "event": "started", "name": "a", "source_path": "src/lib.rs", "start_line": 3, "start_col": 4, "end_line": 3, "end_col": 10, "should_panic": true, "should_panic_message": "bad \"input\"" }"#,
+            r#"{ "type": "test", "name": "a", "event": "failed", "stdout": "out", "stderr": "err" }"#,
+        ]
+    );
+}
@@ -3,9 +3,10 @@
 use std::borrow::Cow;
 use std::fmt;
 use std::sync::mpsc::Sender;
+use std::time::Duration;
 
 use super::__rust_begin_short_backtrace;
-use super::bench::Bencher;
+use super::bench::{BenchOptions, Bencher};
 use super::event::CompletedTest;
 use super::options;
 
@@ -103,6 +104,13 @@ pub fn padding(&self) -> NamePadding {
         }
     }
 
+    pub(crate) fn is_dynamic(&self) -> bool {
+        match *self {
+            StaticTestFn(..) | StaticBenchFn(..) | StaticBenchAsTestFn(..) => false,
+            DynTestFn(..) | DynBenchFn(..) | DynBenchAsTestFn(..) => true,
+        }
+    }
+
     pub(crate) fn into_runnable(self) -> Runnable {
         match self {
             StaticTestFn(f) => Runnable::Test(RunnableTest::Static(f)),
@@ -176,13 +184,14 @@ pub(crate) fn run(
         desc: &TestDesc,
         monitor_ch: &Sender<CompletedTest>,
         nocapture: bool,
+        options: BenchOptions,
     ) {
         match self {
             RunnableBench::Static(f) => {
-                crate::bench::benchmark(id, desc.clone(), monitor_ch.clone(), nocapture, f)
+                crate::bench::benchmark(id, desc.clone(), monitor_ch.clone(), nocapture, options, f)
             }
             RunnableBench::Dynamic(f) => {
-                crate::bench::benchmark(id, desc.clone(), monitor_ch.clone(), nocapture, f)
+                crate::bench::benchmark(id, desc.clone(), monitor_ch.clone(), nocapture, options, f)
             }
         }
     }
@@ -208,6 +217,8 @@ pub struct TestDesc {
     pub compile_fail: bool,
     pub no_run: bool,
     pub test_type: TestType,
+    // The time after which the test is failed, overriding `--test-timeout`
+    pub timeout: Option<Duration>,
 }
 
 impl TestDesc {
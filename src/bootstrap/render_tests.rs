@@ -191,11 +191,16 @@ fn render_suite_outcome(&self, outcome: Outcome<'_>, suite: &SuiteOutcome) {
         if !self.failures.is_empty() {
             println!("\nfailures:\n");
             for failure in &self.failures {
-                if failure.stdout.is_some() || failure.message.is_some() {
+                if failure.stdout.is_some() || failure.stderr.is_some() || failure.message.is_some()
+                {
                     println!("---- {} stdout ----", failure.name);
                     if let Some(stdout) = &failure.stdout {
                         println!("{stdout}");
                     }
+                    if let Some(stderr) = &failure.stderr {
+                        println!("---- {} stderr ----", failure.name);
+                        println!("{stderr}");
+                    }
                     if let Some(message) = &failure.message {
                         println!("note: {message}");
                     }
@@ -266,6 +271,7 @@ fn render_message(&mut self, message: Message) {
                     name: outcome.name.clone(),
                     exec_time: None,
                     stdout: None,
+                    stderr: None,
                     message: None,
                 };
                 self.render_test_outcome(Outcome::BenchOk, &fake_test_outcome);
@@ -396,5 +402,6 @@ struct TestOutcome {
     name: String,
     exec_time: Option<f64>,
     stdout: Option<String>,
+    stderr: Option<String>,
     message: Option<String>,
 }
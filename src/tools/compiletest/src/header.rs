@@ -988,6 +988,7 @@ macro_rules! decision {
         compile_fail: false,
         no_run: false,
         test_type: test::TestType::Unknown,
+        timeout: None,
     }
 }
 
@@ -0,0 +1,93 @@
+// run-pass
+// Test that users are able to use stable mir APIs to retrieve type layouts and function ABIs
+
+// ignore-stage1
+// ignore-cross-compile
+// ignore-remote
+// edition: 2021
+
+#![feature(rustc_private)]
+
+extern crate rustc_middle;
+extern crate rustc_smir;
+extern crate stable_mir;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+
+use stable_mir::abi::{CallConvention, FieldsShape, PassMode, TagEncoding, VariantsShape};
+use stable_mir::mir::mono::Instance;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+/// This function uses the Stable MIR APIs to get information about the test crate.
+fn test_stable_mir(_tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let items = stable_mir::all_local_items();
+    let target_fn = items.iter().find(|item| item.name() == "c_fn").unwrap();
+    let instance = Instance::mono(target_fn).unwrap();
+    let fn_abi = instance.fn_abi().unwrap();
+    assert_eq!(fn_abi.conv, CallConvention::C);
+    assert_eq!(fn_abi.args.len(), 2);
+    assert!(!fn_abi.c_variadic);
+
+    // The `#[repr(C)]` struct keeps its fields in declaration order.
+    let repr_c = &fn_abi.args[0];
+    assert_eq!(repr_c.layout, repr_c.ty.layout().unwrap());
+    assert_eq!(repr_c.layout.size, 12);
+    assert_eq!(repr_c.layout.abi_align, 4);
+    assert_eq!(repr_c.layout.fields, FieldsShape::Arbitrary { offsets: vec![0, 4, 8] });
+    assert_eq!(repr_c.layout.fields.fields_by_offset_order(), vec![0, 1, 2]);
+    assert!(!repr_c.layout.is_unsized());
+
+    // `Option<&u8>` uses the null pointer niche of the reference to encode `None`.
+    let option = &fn_abi.args[1];
+    assert_eq!(option.layout.size, std::mem::size_of::<usize>());
+    assert!(matches!(
+        option.layout.variants,
+        VariantsShape::Multiple { tag_encoding: TagEncoding::Niche { .. }, .. }
+    ));
+    assert!(matches!(option.mode, PassMode::Direct(_)));
+
+    // The unit return type is ignored.
+    assert_eq!(fn_abi.ret.mode, PassMode::Ignore);
+    assert_eq!(fn_abi.ret.layout.size, 0);
+
+    ControlFlow::Continue(())
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "abi_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    rustc_internal::StableMir::new(args, test_stable_mir).run().unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    #[repr(C)]
+    pub struct ReprC {{
+        a: u8,
+        b: u32,
+        c: u16,
+    }}
+
+    pub extern "C" fn c_fn(_s: ReprC, _opt: Option<&u8>) {{}}
+    "#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,130 @@
+// run-pass
+// Test that users are able to use stable mir APIs to evaluate constants and statics, and to
+// decode the allocations that hold their values.
+
+// ignore-stage1
+// ignore-cross-compile
+// ignore-remote
+// edition: 2021
+
+#![feature(rustc_private)]
+#![feature(assert_matches)]
+
+extern crate rustc_middle;
+extern crate rustc_smir;
+extern crate stable_mir;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+
+use stable_mir::mir::alloc::{GlobalAlloc, Value};
+use stable_mir::mir::mono::StaticDef;
+use stable_mir::ty::ConstDef;
+use stable_mir::{CrateItem, ItemKind};
+use std::assert_matches::assert_matches;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+/// This function uses the Stable MIR APIs to get information about the test crate.
+fn test_stable_mir(_tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let items = stable_mir::all_local_items();
+
+    let answer = ConstDef::try_from(get_item(&items, "ANSWER")).unwrap();
+    let alloc = answer.eval().unwrap();
+    assert_eq!(alloc.raw_bytes().unwrap(), 42u32.to_ne_bytes());
+    assert_eq!(alloc.decode(answer.ty()).unwrap(), Value::Uint(42));
+
+    let table = StaticDef::try_from(get_item(&items, "TABLE")).unwrap();
+    let alloc = table.eval_initializer().unwrap();
+    assert_eq!(
+        alloc.decode(table.ty()).unwrap(),
+        Value::Array(vec![
+            Value::Adt { variant: 0, fields: vec![Value::Int(-1), Value::Bool(true)] },
+            Value::Adt { variant: 0, fields: vec![Value::Int(2), Value::Bool(false)] },
+        ])
+    );
+
+    let greeting = ConstDef::try_from(get_item(&items, "GREETING")).unwrap();
+    let alloc = greeting.eval().unwrap();
+    assert_eq!(alloc.decode(greeting.ty()).unwrap(), Value::Str("hello".to_string()));
+
+    // Follow the pointer stored in `REF` to the static it points to.
+    let reference = StaticDef::try_from(get_item(&items, "REF")).unwrap();
+    let alloc = reference.eval_initializer().unwrap();
+    let value = alloc.decode(reference.ty()).unwrap();
+    let Value::Pointer { alloc: Some(alloc_id), offset: 0 } = value else {
+        panic!("Expected a pointer into an allocation, but found: {value:?}");
+    };
+    assert_eq!(alloc.provenance.ptrs.len(), 1);
+    assert_eq!(alloc.provenance.ptrs[0].1.0, alloc_id);
+    assert_matches!(GlobalAlloc::from(alloc_id), GlobalAlloc::Static(def) if def == table);
+
+    let function = get_item(&items, "answer");
+    assert_eq!(function.item_kind(), ItemKind::Fn);
+    assert!(StaticDef::try_from(function.clone()).is_err());
+    assert!(ConstDef::try_from(function.clone()).is_err());
+
+    // The constructor of a tuple struct is a function item with a body.
+    let ctor = get_item(&items, "Wrapper");
+    assert_eq!(ctor.item_kind(), ItemKind::Fn);
+    assert_eq!(ctor.body().arg_locals().len(), 1);
+
+    // Every crate item has a kind.
+    for item in items.iter() {
+        let _ = item.item_kind();
+    }
+
+    ControlFlow::Continue(())
+}
+
+fn get_item<'a>(items: &'a stable_mir::CrateItems, name: &str) -> CrateItem {
+    items.iter().find(|item| item.name() == name).unwrap().clone()
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "alloc_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    rustc_internal::StableMir::new(args, test_stable_mir).run().unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    pub struct Entry {{
+        pub value: i16,
+        pub enabled: bool,
+    }}
+
+    pub const ANSWER: u32 = 42;
+    pub const GREETING: &str = "hello";
+
+    pub static TABLE: [Entry; 2] = [
+        Entry {{ value: -1, enabled: true }},
+        Entry {{ value: 2, enabled: false }},
+    ];
+    pub static REF: &[Entry; 2] = &TABLE;
+
+    pub struct Wrapper(pub u32);
+
+    pub fn answer() -> u32 {{
+        ANSWER
+    }}
+    "#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,160 @@
+// run-pass
+// Test that users are able to run dataflow analyses over the stable mir of a function.
+
+// ignore-stage1
+// ignore-cross-compile
+// ignore-remote
+// edition: 2021
+
+#![feature(rustc_private)]
+
+extern crate rustc_middle;
+extern crate rustc_smir;
+extern crate stable_mir;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+
+use stable_mir::mir::dataflow::impls::{
+    MaybeBorrowedLocals, MaybeInitializedLocals, MaybeLiveLocals,
+};
+use stable_mir::mir::dataflow::{Analysis, Direction};
+use stable_mir::mir::visit::Location;
+use stable_mir::mir::{Body, Statement, Terminator, TerminatorKind, START_BLOCK};
+use stable_mir::CrateItem;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+/// This function uses the Stable MIR APIs to get information about the test crate.
+fn test_stable_mir(_tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let items = stable_mir::all_local_items();
+
+    // Only the first argument is read.
+    let body = get_item(&items, "first").body();
+    let live = MaybeLiveLocals.iterate_to_fixpoint(&body);
+    let live_at_start: Vec<_> = live.state_before(&body, Location::START).iter().collect();
+    assert_eq!(live_at_start, vec![1]);
+    let return_location = find_return(&body);
+    let live_at_return: Vec<_> = live.state_before(&body, return_location).iter().collect();
+    assert_eq!(live_at_return, vec![0]);
+
+    // The argument is moved into the return value.
+    let body = get_item(&items, "forward").body();
+    let init = MaybeInitializedLocals.iterate_to_fixpoint(&body);
+    let init_at_start: Vec<_> = init.entry_set_for_block(START_BLOCK).iter().collect();
+    assert_eq!(init_at_start, vec![1]);
+    let init_at_return = init.state_before(&body, find_return(&body));
+    assert!(init_at_return.contains(0));
+    assert!(!init_at_return.contains(1));
+
+    // The argument is no longer initialized once it has been dropped.
+    let body = get_item(&items, "discard").body();
+    let init = MaybeInitializedLocals.iterate_to_fixpoint(&body);
+    let drop_location = find_terminator(&body, |kind| matches!(kind, TerminatorKind::Drop { .. }));
+    assert!(init.state_before(&body, drop_location).contains(1));
+    assert!(!init.state_before(&body, find_return(&body)).contains(1));
+
+    // The argument is borrowed to be passed to `black_box`, but not before.
+    let body = get_item(&items, "leak").body();
+    let borrowed = MaybeBorrowedLocals.iterate_to_fixpoint(&body);
+    assert!(borrowed.state_before(&body, Location::START).is_empty());
+    assert!(borrowed.state_before(&body, find_return(&body)).contains(1));
+
+    // Custom analyses only need to implement the transfer functions.
+    for item in &items {
+        let body = item.body();
+        let reachable = Reachable.iterate_to_fixpoint(&body);
+        for block in 0..body.blocks.len() {
+            assert!(*reachable.entry_set_for_block(block), "{}: bb{block}", item.name());
+        }
+    }
+
+    ControlFlow::Continue(())
+}
+
+/// A forward analysis of whether a block may be executed.
+struct Reachable;
+
+impl Analysis for Reachable {
+    type Domain = bool;
+
+    const DIRECTION: Direction = Direction::Forward;
+
+    fn bottom_value(&self, _body: &Body) -> bool {
+        false
+    }
+
+    fn initialize_start_block(&self, _body: &Body, state: &mut bool) {
+        *state = true;
+    }
+
+    fn apply_statement_effect(&self, _state: &mut bool, _stmt: &Statement, _loc: Location) {}
+
+    fn apply_terminator_effect(&self, _state: &mut bool, _term: &Terminator, _loc: Location) {}
+}
+
+fn find_return(body: &Body) -> Location {
+    find_terminator(body, |kind| matches!(kind, TerminatorKind::Return))
+}
+
+fn find_terminator(body: &Body, f: impl Fn(&TerminatorKind) -> bool) -> Location {
+    let block = body.blocks.iter().position(|block| f(&block.terminator.kind)).unwrap();
+    Location { block, statement_index: body.blocks[block].statements.len() }
+}
+
+fn get_item<'a>(items: &'a stable_mir::CrateItems, name: &str) -> CrateItem {
+    items.iter().find(|item| item.name() == name).unwrap().clone()
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "dataflow_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    rustc_internal::StableMir::new(args, test_stable_mir).run().unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    pub fn first(a: u32, _b: u32) -> u32 {{
+        a
+    }}
+
+    pub fn forward(v: Vec<u8>) -> Vec<u8> {{
+        v
+    }}
+
+    pub fn discard(_v: Vec<u8>) {{}}
+
+    pub fn leak(x: u32) -> u32 {{
+        std::hint::black_box(&x);
+        x
+    }}
+
+    pub fn count(values: &[u32]) -> u32 {{
+        let mut total = 0;
+        for value in values {{
+            if *value > 10 {{
+                total += value;
+            }}
+        }}
+        total
+    }}
+    "#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,155 @@
+// run-pass
+// Test that users are able to use stable mir APIs to retrieve monomorphized instances
+
+// ignore-stage1
+// ignore-cross-compile
+// ignore-remote
+// edition: 2021
+
+#![feature(rustc_private)]
+
+extern crate rustc_middle;
+extern crate rustc_smir;
+extern crate stable_mir;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+
+use stable_mir::mir::mono::{Instance, InstanceKind, MonoItem};
+use stable_mir::mir::{Operand, TerminatorKind};
+use stable_mir::ty::{GenericArgKind, GenericArgs, RigidTy, Ty, TyKind};
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+/// This function uses the Stable MIR APIs to get information about the test crate.
+fn test_stable_mir(_tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let entry = stable_mir::entry_fn().unwrap();
+    let main = Instance::mono(&entry).unwrap();
+    assert_eq!(main.kind, InstanceKind::Item);
+    assert_eq!(main.name(), "main");
+    assert!(main.args().0.is_empty());
+
+    // Resolve the calls made by `main` and check that they are monomorphized.
+    let body = main.body().unwrap();
+    let mut callees = vec![];
+    let mut identities = 0;
+    for block in &body.blocks {
+        if let TerminatorKind::Call { func: Operand::Constant(c), .. } = &block.terminator.kind {
+            let TyKind::RigidTy(RigidTy::FnDef(def, args)) = c.literal.ty.kind() else {
+                panic!("Unexpected callee: {c:?}")
+            };
+            let callee = Instance::resolve(def, &args).unwrap();
+            if callee.name().starts_with("generic") {
+                callees.push(callee);
+            } else if callee.name().starts_with("identity") {
+                // Resolving with a copy of the arguments built from their stable representation
+                // should yield the same instance.
+                assert_eq!(Instance::resolve(def, &rebuild_args(&args)), Some(callee));
+                identities += 1;
+            }
+        }
+    }
+    assert_eq!(callees.len(), 2);
+    assert_eq!(identities, 2);
+    for callee in &callees {
+        assert_eq!(callee.kind, InstanceKind::Item);
+        let body = callee.body().unwrap();
+        assert!(!body.locals.is_empty());
+        for local in &body.locals {
+            assert!(!matches!(local.ty.kind(), TyKind::Param(_)), "{:?}", local.ty);
+        }
+    }
+    assert_ne!(callees[0].mangled_name(), callees[1].mangled_name());
+
+    // The drop glue of `String` is a shim with a body.
+    let string = body
+        .locals
+        .iter()
+        .find(|local| matches!(local.ty.kind(), TyKind::RigidTy(RigidTy::Adt(..))))
+        .unwrap();
+    let drop_glue = Instance::resolve_drop_in_place(string.ty);
+    assert_eq!(drop_glue.kind, InstanceKind::DropGlue);
+    assert!(drop_glue.body().is_some());
+
+    // Every instance reachable from `main` should be collected.
+    let items = stable_mir::reachable_mono_items();
+    let instances: Vec<_> = items
+        .iter()
+        .filter_map(|item| match item {
+            MonoItem::Fn(instance) => Some(*instance),
+            _ => None,
+        })
+        .collect();
+    assert!(instances.contains(&main));
+    for callee in &callees {
+        assert!(instances.contains(callee), "Missing {}", callee.name());
+    }
+    assert!(instances.iter().any(|instance| instance.kind == InstanceKind::DropGlue));
+
+    ControlFlow::Continue(())
+}
+
+/// Replace every type argument by a new type built from its stable representation.
+fn rebuild_args(args: &GenericArgs) -> GenericArgs {
+    GenericArgs(
+        args.0
+            .iter()
+            .map(|arg| match arg {
+                GenericArgKind::Type(ty) => GenericArgKind::Type(rebuild_ty(*ty)),
+                arg => arg.clone(),
+            })
+            .collect(),
+    )
+}
+
+fn rebuild_ty(ty: Ty) -> Ty {
+    match ty.kind() {
+        TyKind::RigidTy(RigidTy::Ref(region, pointee, mutability)) => {
+            Ty::from(TyKind::RigidTy(RigidTy::Ref(region, rebuild_ty(pointee), mutability)))
+        }
+        kind => Ty::from(kind),
+    }
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "instance_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=bin".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    rustc_internal::StableMir::new(args, test_stable_mir).run().unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    fn generic<T: Default>(t: T) -> T {{
+        let _ = t;
+        T::default()
+    }}
+
+    fn identity<T>(t: T) -> T {{
+        t
+    }}
+
+    fn main() {{
+        let _ = generic::<u32>(10);
+        let _ = generic::<String>(String::new());
+        let _ = identity::<fn(&u8) -> u8>(|x| *x);
+        let _ = identity::<&dyn std::fmt::Debug>(&0u8);
+    }}"#
+    )?;
+    Ok(())
+}
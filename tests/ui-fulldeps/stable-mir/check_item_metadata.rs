@@ -0,0 +1,139 @@
+// run-pass
+// Test that users are able to use stable mir APIs to retrieve the attributes, documentation,
+// visibility and definition path of an item.
+
+// ignore-stage1
+// ignore-cross-compile
+// ignore-remote
+// edition: 2021
+
+#![feature(rustc_private)]
+
+extern crate rustc_middle;
+extern crate rustc_smir;
+extern crate stable_mir;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+
+use stable_mir::item::{DefPathData, Visibility};
+use stable_mir::{CrateItem, DefId};
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+/// This function uses the Stable MIR APIs to get information about the test crate.
+fn test_stable_mir(_tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let items = stable_mir::all_local_items();
+
+    // Tool attributes and doc comments are both reported, in source order.
+    let audited = get_item(&items, "audited");
+    let attrs = audited.attrs();
+    assert_eq!(attrs.len(), 4, "{attrs:?}");
+    assert!(attrs[0].path.is_empty());
+    assert_eq!(attrs[0].value, "/// Reads from a raw pointer.");
+    let reviewed = audited.attrs_by_path(&["mytool", "reviewed"]);
+    assert_eq!(reviewed.len(), 1);
+    let expected = r#"#[mytool::reviewed(by = "alice")]"#;
+    assert_eq!(reviewed[0].value, expected);
+    assert_eq!(reviewed[0].span.snippet().as_deref(), Some(expected));
+    assert_eq!(audited.attrs_by_path(&["inline"]).len(), 1);
+    assert!(audited.attrs_by_path(&["mytool"]).is_empty());
+
+    // `#[cfg_attr]` is expanded before the documentation is collected.
+    assert_eq!(
+        audited.doc_comments().as_deref(),
+        Some("Reads from a raw pointer.\n\n# Safety\nThe pointer must be valid.")
+    );
+    assert_eq!(get_item(&items, "private_helper").doc_comments(), None);
+
+    // Private items are restricted to their module, which is the crate root for
+    // `pub(crate)` and `pub(super)` items of a top-level module.
+    assert_eq!(audited.visibility(), Visibility::Public);
+    let root = restricted_to(&get_item(&items, "private_helper"));
+    assert_eq!(restricted_to(&get_item(&items, "inner::crate_helper")), root);
+    assert_eq!(restricted_to(&get_item(&items, "inner::super_helper")), root);
+    let inner = restricted_to(&get_item(&items, "inner::module_helper"));
+    assert_ne!(inner, root);
+    assert_eq!(inner.name(), "inner");
+
+    // Definition path.
+    let path = get_item(&items, "inner::crate_helper").def_path();
+    assert_eq!(path.krate, stable_mir::local_crate().id);
+    let data: Vec<_> = path.data.iter().map(|data| data.data.clone()).collect();
+    assert_eq!(
+        data,
+        vec![DefPathData::TypeNs("inner".to_string()), DefPathData::ValueNs("crate_helper".into())]
+    );
+
+    // Generics and predicates of an item.
+    let generic = get_item(&items, "generic");
+    assert_eq!(generic.generics_of().params.len(), 1);
+    assert!(!generic.predicates_of().predicates.is_empty());
+
+    ControlFlow::Continue(())
+}
+
+fn get_item<'a>(items: &'a stable_mir::CrateItems, name: &str) -> CrateItem {
+    items.iter().find(|item| item.name() == name).unwrap().clone()
+}
+
+/// Return the module that the given item is restricted to.
+fn restricted_to(item: &CrateItem) -> DefId {
+    let visibility = item.visibility();
+    let Visibility::Restricted(module) = visibility else {
+        panic!("Expected a restricted visibility, but found: {visibility:?}");
+    };
+    module
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "item_metadata_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        "--cfg=audit".to_string(),
+        path.to_string(),
+    ];
+    rustc_internal::StableMir::new(args, test_stable_mir).run().unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    #![feature(register_tool)]
+    #![register_tool(mytool)]
+
+    /// Reads from a raw pointer.
+    #[cfg_attr(audit, doc = "\n# Safety\nThe pointer must be valid.")]
+    #[mytool::reviewed(by = "alice")]
+    #[inline]
+    pub unsafe fn audited(ptr: *const u8) -> u8 {{
+        *ptr
+    }}
+
+    fn private_helper() {{}}
+
+    pub fn generic<T: Clone>(value: &T) -> T {{
+        value.clone()
+    }}
+
+    pub mod inner {{
+        pub(crate) fn crate_helper() {{}}
+        pub(super) fn super_helper() {{}}
+        fn module_helper() {{}}
+    }}
+    "#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,155 @@
+// run-pass
+// Test that users are able to instrument the code being compiled by replacing the MIR of local
+// items using the stable mir APIs.
+
+// ignore-stage1
+// ignore-cross-compile
+// ignore-remote
+// edition: 2021
+
+#![feature(rustc_private)]
+
+extern crate rustc_middle;
+extern crate rustc_smir;
+extern crate stable_mir;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+
+use stable_mir::mir::alloc::Value;
+use stable_mir::mir::visit::{Location, MutVisitor};
+use stable_mir::mir::{Body, Constant};
+use stable_mir::ty::{Allocation, ConstantKind, ProvenanceMap};
+use stable_mir::{CompilerError, CrateItem};
+use std::io::Write;
+use std::ops::ControlFlow;
+use std::process::Command;
+
+const CRATE_NAME: &str = "input";
+
+/// The analysis callback is the first to request the MIR of `answer`, and it should already see
+/// the replaced body.
+fn test_stable_mir(_tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let items = stable_mir::all_local_items();
+    let answer = items.iter().find(|item| item.name() == "answer").unwrap();
+    let mut visitor = ReplaceConstant { from: 42, to: 42, replaced: 0 };
+    visitor.visit_body(&mut answer.body());
+    assert_eq!(visitor.replaced, 1);
+    ControlFlow::Continue(())
+}
+
+/// Analysis callback for the input that uses inline assembly.
+fn test_asm(_tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    ControlFlow::Continue(())
+}
+
+/// Replace the value returned by `answer` by 42.
+fn override_mir(item: &CrateItem, body: &Body) -> Option<Body> {
+    if item.name() != "answer" {
+        return None;
+    }
+    let mut body = body.clone();
+    let mut visitor = ReplaceConstant { from: 1, to: 42, replaced: 0 };
+    visitor.visit_body(&mut body);
+    assert_eq!(visitor.replaced, 1);
+    Some(body)
+}
+
+/// Try to replace the body of every item by a copy of itself.
+fn copy_mir(_item: &CrateItem, body: &Body) -> Option<Body> {
+    Some(body.clone())
+}
+
+struct ReplaceConstant {
+    from: u32,
+    to: u32,
+    replaced: usize,
+}
+
+impl MutVisitor for ReplaceConstant {
+    fn visit_constant(&mut self, constant: &mut Constant, location: Location) {
+        if constant.literal.decode() == Ok(Value::Uint(self.from.into())) {
+            let bytes = self.to.to_ne_bytes().iter().copied().map(Some).collect();
+            let alloc = Allocation {
+                bytes,
+                provenance: ProvenanceMap { ptrs: vec![] },
+                align: 4,
+                mutability: stable_mir::mir::Mutability::Not,
+            };
+            constant.literal.literal = ConstantKind::Allocated(alloc);
+            self.replaced += 1;
+        }
+        self.super_constant(constant, location)
+    }
+}
+
+/// This test will generate and compile a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` with a MIR override and run the compiler.
+/// Finally, it will run the generated binary and check that the override took effect.
+///
+/// Replacing a body that contains inline assembly should be reported as an error.
+fn main() {
+    let path = "override_input.rs";
+    let output = "override_output";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=bin".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        "-o".to_string(),
+        output.to_string(),
+        path.to_string(),
+    ];
+    rustc_internal::StableMir::new(args, test_stable_mir)
+        .with_mir_override(override_mir)
+        .run()
+        .unwrap();
+    let status = Command::new(format!("./{output}")).status().unwrap();
+    assert_eq!(status.code(), Some(42));
+
+    let path = "override_asm_input.rs";
+    generate_asm_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        "--emit=obj".to_string(),
+        path.to_string(),
+    ];
+    let result = rustc_internal::StableMir::new(args, test_asm).with_mir_override(copy_mir).run();
+    assert!(matches!(result, Err(CompilerError::CompilationFailed)));
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    #[inline(never)]
+    fn answer() -> u32 {{
+        1
+    }}
+
+    fn main() {{
+        std::process::exit(answer() as i32);
+    }}
+    "#
+    )?;
+    Ok(())
+}
+
+fn generate_asm_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    pub fn nothing() {{
+        unsafe {{ std::arch::asm!("") }}
+    }}
+    "#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,108 @@
+// run-pass
+// Test that users are able to pretty print stable MIR bodies in the same format as -Zunpretty=mir
+
+// ignore-stage1
+// ignore-cross-compile
+// ignore-remote
+// edition: 2021
+
+#![feature(rustc_private)]
+
+extern crate rustc_middle;
+extern crate rustc_smir;
+extern crate stable_mir;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+
+use stable_mir::CrateItem;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+/// This function uses the Stable MIR APIs to get information about the test crate.
+fn test_stable_mir(_tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let items = stable_mir::all_local_items();
+
+    let add = get_item(&items, "add");
+    let body = add.body();
+    assert_eq!(body.arg_count, 2);
+    assert_eq!(body.arg_locals().len(), 2);
+    assert_eq!(body.ret_local().ty.to_string(), "u32");
+    assert_eq!(
+        pretty(add),
+        r#"fn add(_1: u32, _2: u32) -> u32 {
+    let mut _0: u32;
+    let mut _3: (u32, bool);
+
+    bb0: {
+        _3 = CheckedAdd(_1, _2);
+        assert(!move (_3.1: bool), "attempt to compute `{} + {}`, which would overflow", _1, _2) -> [success: bb1, unwind continue];
+    }
+
+    bb1: {
+        _0 = move (_3.0: u32);
+        return;
+    }
+}
+"#
+    );
+
+    let hello = pretty(get_item(&items, "hello"));
+    assert!(hello.starts_with("fn hello() -> &str {\n"), "{hello}");
+    assert!(hello.contains("_0 = const \"hello\";"), "{hello}");
+
+    let call = pretty(get_item(&items, "call"));
+    assert!(call.contains("= add(const 1_u32, const 2_u32) -> [return: bb1, unwind continue];"));
+
+    ControlFlow::Continue(())
+}
+
+fn get_item<'a>(items: &'a stable_mir::CrateItems, name: &str) -> &'a CrateItem {
+    items.iter().find(|item| item.name() == name).unwrap()
+}
+
+fn pretty(item: &CrateItem) -> String {
+    let mut out = Vec::new();
+    item.emit_mir(&mut out).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "pretty_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    rustc_internal::StableMir::new(args, test_stable_mir).run().unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    pub fn add(a: u32, b: u32) -> u32 {{
+        a + b
+    }}
+
+    pub fn hello() -> &'static str {{
+        "hello"
+    }}
+
+    pub fn call() -> u32 {{
+        add(1, 2)
+    }}
+    "#
+    )?;
+    Ok(())
+}
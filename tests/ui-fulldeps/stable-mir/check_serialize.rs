@@ -0,0 +1,110 @@
+// run-pass
+// Test that users are able to serialize the stable mir of the local crate, and to dump it to a
+// file using the stable mir driver.
+
+// ignore-stage1
+// ignore-cross-compile
+// ignore-remote
+// edition: 2021
+
+#![feature(rustc_private)]
+
+extern crate rustc_middle;
+extern crate rustc_smir;
+extern crate stable_mir;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+
+use stable_mir::serialize::{CrateDump, DumpFormat};
+use stable_mir::ty::{RigidTy, TyKind, UintTy};
+use stable_mir::ItemKind;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+/// This function uses the Stable MIR APIs to get information about the test crate.
+fn test_stable_mir(_tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let dump = CrateDump::local_crate();
+    assert_eq!(dump.krate.name, CRATE_NAME);
+    assert_eq!(dump.items.len(), stable_mir::all_local_items().len());
+
+    let add = dump.items.iter().find(|item| item.name == "add").unwrap();
+    assert_eq!(add.kind, ItemKind::Fn);
+    assert_eq!(add.body.arg_count, 2);
+
+    // Every type used by a local is described in the dump, including nested types.
+    for local in &add.body.locals {
+        assert!(dump.types.iter().any(|ty| ty.ty.0 == local.ty.0), "{:?}", local.ty);
+    }
+    let total = dump.items.iter().find(|item| item.name == "total").unwrap();
+    assert!(dump.types.iter().any(|ty| ty.name == "&[u32]"));
+    let is_u32 = |kind: &TyKind| matches!(kind, TyKind::RigidTy(RigidTy::Uint(UintTy::U32)));
+    assert!(dump.types.iter().any(|ty| is_u32(&ty.kind)), "{:?}", dump.types);
+    let span = dump.spans.iter().find(|span| span.span.0 == total.span.0).unwrap();
+    assert!(span.location.contains("serialize_input.rs"), "{}", span.location);
+
+    let mut json = vec![];
+    dump.write(&mut json, DumpFormat::Json).unwrap();
+    let json = String::from_utf8(json).unwrap();
+    assert!(json.contains(r#""name": "add""#), "{json}");
+
+    let mut binary = vec![];
+    dump.write(&mut binary, DumpFormat::Binary).unwrap();
+    assert!(!binary.is_empty());
+    assert!(binary.len() < json.len());
+
+    // Both formats can be read back.
+    let from_json = CrateDump::read(json.as_bytes(), DumpFormat::Json).unwrap();
+    assert_eq!(format!("{from_json:?}"), format!("{dump:?}"));
+    let from_binary = CrateDump::read(binary.as_slice(), DumpFormat::Binary).unwrap();
+    assert_eq!(format!("{from_binary:?}"), format!("{dump:?}"));
+
+    ControlFlow::Continue(())
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` that dumps the local bodies and it will run the compiler.
+/// Finally, it will check that the dump was written, and that it can be read back.
+fn main() {
+    let path = "serialize_input.rs";
+    let output = "serialize_output.json";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    rustc_internal::StableMir::new(args, test_stable_mir)
+        .with_body_dump(output, DumpFormat::Json)
+        .run()
+        .unwrap();
+    let json = std::fs::read_to_string(output).unwrap();
+    assert!(json.contains(r#""name": "total""#), "{json}");
+    assert!(json.contains(r#""types": ["#), "{json}");
+
+    // Reading the dump does not need the compiler.
+    let dump = CrateDump::read(json.as_bytes(), DumpFormat::Json).unwrap();
+    assert!(dump.items.iter().any(|item| item.name == "total"));
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    pub fn add(a: u32, b: u32) -> u32 {{
+        a + b
+    }}
+
+    pub fn total(values: &[u32]) -> u32 {{
+        values.iter().fold(0, |acc, v| add(acc, *v))
+    }}
+    "#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,107 @@
+// run-pass
+// Test that users are able to use stable mir APIs to retrieve source information of a span,
+// and to report diagnostics at a span.
+
+// ignore-stage1
+// ignore-cross-compile
+// ignore-remote
+// edition: 2021
+
+#![feature(rustc_private)]
+
+extern crate rustc_middle;
+extern crate rustc_smir;
+extern crate stable_mir;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+
+use stable_mir::diagnostic::Diagnostic;
+use stable_mir::ty::{LineInfo, Span};
+use stable_mir::CrateItem;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+const INPUT_FILE: &str = "span_input.rs";
+
+/// This function uses the Stable MIR APIs to get information about the test crate.
+fn test_stable_mir(_tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let items = stable_mir::all_local_items();
+    let quadruple = get_item(&items, "quadruple");
+
+    let span = quadruple.span();
+    assert!(span.get_filename().ends_with(INPUT_FILE), "{}", span.get_filename());
+    assert_eq!(
+        span.get_lines(),
+        LineInfo { start_line: 7, start_col: 1, end_line: 7, end_col: 32 }
+    );
+    assert_eq!(span.snippet().as_deref(), Some("pub fn quadruple(x: u32) -> u32"));
+    assert!(span.macro_backtrace().is_empty());
+    assert!(span.diagnostic().contains(INPUT_FILE));
+
+    // The multiplication inside `double!` comes from the macro expansion.
+    let body = quadruple.body();
+    let spans: Vec<Span> = body
+        .blocks
+        .iter()
+        .flat_map(|block| {
+            block.statements.iter().map(|stmt| stmt.span).chain([block.terminator.span])
+        })
+        .collect();
+    let expanded = spans
+        .iter()
+        .find(|span| !span.macro_backtrace().is_empty())
+        .expect("Expected a span from a macro expansion");
+    let backtrace = expanded.macro_backtrace();
+    assert_eq!(backtrace.len(), 1);
+    assert_eq!(backtrace[0].name, "double!");
+    assert_eq!(backtrace[0].call_site.snippet().as_deref(), Some("double!(x)"));
+    assert_eq!(backtrace[0].call_site.get_lines().start_line, 8);
+    assert_eq!(backtrace[0].def_site.get_lines().start_line, 2);
+
+    Diagnostic::warning(span, "found a function that multiplies by four")
+        .span_label(backtrace[0].call_site, "the first multiplication happens here")
+        .note("this diagnostic was emitted through the stable MIR APIs")
+        .emit();
+
+    ControlFlow::Continue(())
+}
+
+fn get_item<'a>(items: &'a stable_mir::CrateItems, name: &str) -> CrateItem {
+    items.iter().find(|item| item.name() == name).unwrap().clone()
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    generate_input(INPUT_FILE).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        INPUT_FILE.to_string(),
+    ];
+    rustc_internal::StableMir::new(args, test_stable_mir).run().unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+macro_rules! double {{
+    ($e:expr) => {{
+        $e * 2
+    }};
+}}
+pub fn quadruple(x: u32) -> u32 {{
+    double!(x) * 2
+}}
+"#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,190 @@
+// run-pass
+// Test that users are able to use stable mir APIs to check whether a type implements a trait,
+// and to resolve trait method calls to the implementation they dispatch to.
+
+// ignore-stage1
+// ignore-cross-compile
+// ignore-remote
+// edition: 2021
+
+#![feature(rustc_private)]
+
+extern crate rustc_middle;
+extern crate rustc_smir;
+extern crate stable_mir;
+
+use rustc_middle::ty::TyCtxt;
+use rustc_smir::rustc_internal;
+
+use stable_mir::mir::mono::{Instance, InstanceKind};
+use stable_mir::mir::{Operand, TerminatorKind};
+use stable_mir::ty::{GenericArgKind, GenericArgs, RigidTy, TraitRef, Ty, TyKind, UintTy};
+use stable_mir::CrateItem;
+use std::io::Write;
+use std::ops::ControlFlow;
+
+const CRATE_NAME: &str = "input";
+
+/// This function uses the Stable MIR APIs to get information about the test crate.
+fn test_stable_mir(_tcx: TyCtxt<'_>) -> ControlFlow<()> {
+    let items = stable_mir::all_local_items();
+
+    // Find the traits by looking at the trait methods called by each function.
+    let area = get_called_fn(&get_item(&items, "total_area"));
+    let shape = area.trait_of().unwrap();
+    let scale = get_called_fn(&get_item(&items, "double")).trait_of().unwrap();
+    assert_ne!(shape, scale);
+
+    let square = get_item(&items, "square").body().locals[0].ty;
+    let circle = get_item(&items, "circle").body().locals[0].ty;
+    let u8_ty = Ty::from(TyKind::RigidTy(RigidTy::Uint(UintTy::U8)));
+
+    // Both `Square` and `Circle` implement `Shape`, but `u8` does not.
+    let square_shape = TraitRef::new(shape, square, &[]);
+    assert!(square_shape.is_implemented());
+    assert!(TraitRef::new(shape, circle, &[]).is_implemented());
+    assert!(!TraitRef::new(shape, u8_ty, &[]).is_implemented());
+    assert_eq!(square_shape.self_ty().kind(), square.kind());
+
+    // `Square` implements `Scale<u8>`, but not `Scale<Circle>`.
+    assert!(TraitRef::new(scale, square, &[GenericArgKind::Type(u8_ty)]).is_implemented());
+    assert!(!TraitRef::new(scale, square, &[GenericArgKind::Type(circle)]).is_implemented());
+
+    // The impl that satisfies `Square: Shape` is one of the local trait impls.
+    let impl_def = square_shape.resolve_impl().unwrap();
+    assert!(stable_mir::all_trait_impls().contains(&impl_def));
+    let impl_trait = stable_mir::trait_impl(&impl_def);
+    assert_eq!(impl_trait.value.def_id, shape);
+    assert!(TraitRef::new(shape, u8_ty, &[]).resolve_impl().is_none());
+
+    // Trait references that selection cannot handle are rejected instead of crashing the
+    // compiler: a missing or an extra argument, a generic parameter, and overflow.
+    let scale_missing_arg = TraitRef::new(scale, square, &[]);
+    assert!(!scale_missing_arg.is_implemented());
+    assert!(scale_missing_arg.resolve_impl().is_none());
+    let shape_extra_arg = TraitRef::new(shape, square, &[GenericArgKind::Type(u8_ty)]);
+    assert!(!shape_extra_arg.is_implemented());
+    assert!(shape_extra_arg.resolve_impl().is_none());
+    let param_ref = get_item(&items, "total_area").body().locals[1].ty;
+    let TyKind::RigidTy(RigidTy::Ref(_, param, _)) = param_ref.kind() else { unreachable!() };
+    assert!(TraitRef::new(shape, param, &[]).resolve_impl().is_none());
+    let deep = stable_mir::all_trait_decls().into_iter().find(|t| t.0.name() == "Deep").unwrap();
+    assert!(!TraitRef::new(deep, u8_ty, &[]).is_implemented());
+    assert!(TraitRef::new(deep, u8_ty, &[]).resolve_impl().is_none());
+
+    // Resolve the call to `Shape::area` inside `total_area` for each type.
+    let square_area = Instance::resolve(area, &GenericArgs(vec![GenericArgKind::Type(square)]));
+    let square_area = square_area.unwrap();
+    assert_eq!(square_area.kind, InstanceKind::Item);
+    assert!(square_area.name().contains("Square as"), "{}", square_area.name());
+    let circle_area = Instance::resolve(area, &GenericArgs(vec![GenericArgKind::Type(circle)]));
+    assert!(circle_area.unwrap().name().contains("Circle as"));
+
+    // A free function is not associated with any trait.
+    assert_eq!(get_called_fn(&get_item(&items, "total_area_of_two")).trait_of(), None);
+
+    ControlFlow::Continue(())
+}
+
+fn get_item<'a>(items: &'a stable_mir::CrateItems, name: &str) -> CrateItem {
+    items.iter().find(|item| item.name() == name).unwrap().clone()
+}
+
+/// Return the function called by the first call terminator of the given item.
+fn get_called_fn(item: &CrateItem) -> stable_mir::ty::FnDef {
+    let body = item.body();
+    body.blocks
+        .iter()
+        .find_map(|block| match &block.terminator.kind {
+            TerminatorKind::Call { func: Operand::Constant(func), .. } => {
+                match func.literal.ty.kind() {
+                    TyKind::RigidTy(RigidTy::FnDef(def, _)) => Some(def),
+                    _ => None,
+                }
+            }
+            _ => None,
+        })
+        .unwrap()
+}
+
+/// This test will generate and analyze a dummy crate using the stable mir.
+/// For that, it will first write the dummy crate into a file.
+/// Then it will create a `StableMir` using custom arguments and then
+/// it will run the compiler.
+fn main() {
+    let path = "trait_queries_input.rs";
+    generate_input(&path).unwrap();
+    let args = vec![
+        "rustc".to_string(),
+        "--crate-type=lib".to_string(),
+        "--crate-name".to_string(),
+        CRATE_NAME.to_string(),
+        path.to_string(),
+    ];
+    rustc_internal::StableMir::new(args, test_stable_mir).run().unwrap();
+}
+
+fn generate_input(path: &str) -> std::io::Result<()> {
+    let mut file = std::fs::File::create(path)?;
+    write!(
+        file,
+        r#"
+    pub trait Shape {{
+        fn area(&self) -> u32;
+    }}
+
+    pub trait Scale<T> {{
+        fn scale(&mut self, factor: T);
+    }}
+
+    pub struct Square(u32);
+    pub struct Circle(u32);
+
+    impl Shape for Square {{
+        fn area(&self) -> u32 {{
+            self.0 * self.0
+        }}
+    }}
+
+    impl Shape for Circle {{
+        fn area(&self) -> u32 {{
+            3 * self.0 * self.0
+        }}
+    }}
+
+    impl Scale<u8> for Square {{
+        fn scale(&mut self, factor: u8) {{
+            self.0 *= factor as u32;
+        }}
+    }}
+
+    pub trait Deep {{}}
+
+    pub struct Nested<T>(T);
+
+    // Proving `T: Deep` for any type overflows.
+    impl<T> Deep for T where Nested<T>: Deep {{}}
+
+    pub fn square(s: Square) -> Square {{
+        s
+    }}
+
+    pub fn circle(c: Circle) -> Circle {{
+        c
+    }}
+
+    pub fn total_area<T: Shape>(a: &T, b: &T) -> u32 {{
+        a.area() + b.area()
+    }}
+
+    pub fn double(s: &mut Square) {{
+        s.scale(2)
+    }}
+
+    pub fn total_area_of_two(a: &Square, b: &Circle) -> u32 {{
+        total_area(a, a) + total_area(b, b)
+    }}
+    "#
+    )?;
+    Ok(())
+}
@@ -0,0 +1,80 @@
+// run-pass
+// needs-unwind
+// ignore-windows the aborting test is expected to be terminated by a signal
+// ignore-wasm no subprocess support
+// ignore-emscripten no subprocess support
+// ignore-sgx no subprocess support
+// ignore-fuchsia Test must be run out-of-process
+
+// Checks how tests run with `--isolate` report their results: a test catching its own panic
+// passes, and a test crashing its process fails with the status it exited with.
+
+#![feature(test)]
+
+extern crate test;
+
+use std::env;
+use std::os::unix::process::ExitStatusExt;
+use std::sync::mpsc::channel;
+use test::test::{
+    parse_opts, run_test, RunStrategy, ShouldPanic, StaticTestFn, StaticTestName, TestDesc,
+    TestDescAndFn, TestId, TestResult, TestType,
+};
+
+fn catches_panic() -> Result<(), String> {
+    assert!(std::panic::catch_unwind(|| panic!("caught")).is_err());
+    Ok(())
+}
+
+fn aborts() -> Result<(), String> {
+    std::process::abort()
+}
+
+fn tests() -> Vec<TestDescAndFn> {
+    let test = |name, f| TestDescAndFn {
+        desc: TestDesc {
+            name: StaticTestName(name),
+            ignore: false,
+            ignore_message: None,
+            source_file: "",
+            start_line: 0,
+            start_col: 0,
+            end_line: 0,
+            end_col: 0,
+            should_panic: ShouldPanic::No,
+            compile_fail: false,
+            no_run: false,
+            test_type: TestType::Unknown,
+            timeout: None,
+        },
+        testfn: StaticTestFn(f),
+    };
+    vec![test("catches_panic", catches_panic), test("aborts", aborts)]
+}
+
+fn main() {
+    let args = env::args().collect::<Vec<_>>();
+    // The child processes spawned by `run_test` run a single test and exit in there.
+    if env::var_os("__RUST_TEST_INVOKE").is_some() {
+        test::test_main(&args, tests(), None);
+        unreachable!();
+    }
+
+    let opts_args = [args[0].clone(), "-Zunstable-options".to_string(), "--isolate".to_string()];
+    let opts = parse_opts(&opts_args).unwrap().unwrap();
+    let mut results = Vec::new();
+    for (id, test) in tests().into_iter().enumerate() {
+        let (tx, rx) = channel();
+        let handle = run_test(&opts, false, TestId(id), test, RunStrategy::SpawnPrimary, tx);
+        results.push(rx.recv().unwrap());
+        if let Some(handle) = handle {
+            handle.join().unwrap();
+        }
+    }
+
+    assert_eq!(results[0].result, TestResult::TrOk);
+    assert_eq!(results[0].exit_status.unwrap().signal(), None);
+
+    assert_eq!(results[1].result, TestResult::TrFailed);
+    assert_eq!(results[1].exit_status.unwrap().signal(), Some(6), "terminated by SIGABRT");
+}